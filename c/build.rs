@@ -10,4 +10,8 @@ fn main() {
         .generate()
         .expect("Unable to generate bindings")
         .write_to_file("../target/mbinary.h");
+
+    // The C++ wrapper includes the generated header, so ship it alongside.
+    std::fs::copy("include/mbinary.hpp", "../target/mbinary.hpp")
+        .expect("Unable to copy C++ header");
 }
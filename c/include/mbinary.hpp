@@ -0,0 +1,718 @@
+// C++17 RAII wrapper around the mbinary C API.
+//
+// Copied next to the cbindgen generated `mbinary.h` by build.rs. Handles are owned by
+// move-only classes, failures are thrown as `mbinary::Error` carrying the C error code and
+// `mbinary_last_error()` message.
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <iterator>
+#include <memory>
+#include <optional>
+#include <stdexcept>
+#include <string>
+#include <variant>
+#include <vector>
+
+#include "mbinary.h"
+
+namespace mbinary {
+
+// Failure reported by the C API.
+class Error : public std::runtime_error {
+ public:
+  Error(MbinaryError code, const std::string& message)
+      : std::runtime_error(message), code_(code) {}
+
+  MbinaryError code() const noexcept { return code_; }
+
+ private:
+  MbinaryError code_;
+};
+
+namespace detail {
+
+[[noreturn]] inline void throw_last_error(MbinaryError fallback) {
+  MbinaryError code = mbinary_last_error_code();
+  const char* message = mbinary_last_error();
+  throw Error(code == Success ? fallback : code,
+              message != nullptr ? message : "mbinary call failed");
+}
+
+inline void check(MbinaryError code) {
+  if (code != Success) {
+    throw_last_error(code);
+  }
+}
+
+template <typename T>
+T* check(T* handle) {
+  if (handle == nullptr) {
+    throw_last_error(NullPointer);
+  }
+  return handle;
+}
+
+struct DecoderDeleter {
+  void operator()(CRecordDecoder* decoder) const noexcept { destroy_record_decoder(decoder); }
+};
+
+struct EncoderDeleter {
+  void operator()(CRecordEncoder* encoder) const noexcept { destroy_record_encoder(encoder); }
+};
+
+struct MetadataDeleter {
+  void operator()(CMetadata* metadata) const noexcept { destroy_metadata(metadata); }
+};
+
+struct SymbolMapDeleter {
+  void operator()(CSymbolMap* mappings) const noexcept { destroy_symbol_map(mappings); }
+};
+
+struct BacktestDeleter {
+  void operator()(CBacktest* backtest) const noexcept { destroy_backtest(backtest); }
+};
+
+struct LiveDataDeleter {
+  void operator()(CLiveData* live) const noexcept { destroy_live_data(live); }
+};
+
+struct FilterDeleter {
+  void operator()(CRecordFilter* filter) const noexcept { destroy_record_filter(filter); }
+};
+
+struct BorrowedDecoderDeleter {
+  void operator()(CBorrowedDecoder* decoder) const noexcept { destroy_borrowed_decoder(decoder); }
+};
+
+template <typename T>
+std::vector<uint8_t> encode(const T* handle, MbinaryError (*encode)(const T*, uint8_t*, size_t*)) {
+  size_t size = 0;
+  check(encode(handle, nullptr, &size));
+  std::vector<uint8_t> bytes(size);
+  check(encode(handle, bytes.data(), &size));
+  return bytes;
+}
+
+}  // namespace detail
+
+// TBBO records share the MBP1 layout, a distinct type keeps them apart in `RecordVariant`.
+struct TbboRecord : Mbp1Msg {};
+
+// Record copied out by type, alternatives are in `RType` order so `index() + 1 == rtype`.
+using RecordVariant = std::variant<Mbp1Msg, OhlcvMsg, TradeMsg, TbboRecord, BboMsg>;
+
+// Decoded record, a thin wrapper over `CRecordEnum` with typed access.
+class Record {
+ public:
+  Record() : raw_{} {}
+  explicit Record(const CRecordEnum& raw) : raw_(raw) {}
+
+  RType rtype() const noexcept { return raw_.rtype; }
+  const RecordHeader& header() const noexcept { return *get_header(&raw_); }
+  uint32_t instrument_id() const noexcept { return header().instrument_id; }
+  uint64_t timestamp() const noexcept { return get_timestamp(&raw_); }
+  int64_t price() const noexcept { return get_price(&raw_); }
+
+  // Typed accessors, null when the record holds another type.
+  const Mbp1Msg* mbp1() const noexcept { return is(Mbp1) ? &raw_.data.mbp1 : nullptr; }
+  const OhlcvMsg* ohlcv() const noexcept { return is(Ohlcv) ? &raw_.data.ohlcv : nullptr; }
+  const TradeMsg* trade() const noexcept { return is(Trades) ? &raw_.data.trade : nullptr; }
+  const TbboMsg* tbbo() const noexcept { return is(Tbbo) ? &raw_.data.tbbo : nullptr; }
+  const BboMsg* bbo() const noexcept { return is(Bbo) ? &raw_.data.bbo : nullptr; }
+
+  RecordVariant variant() const {
+    switch (raw_.rtype) {
+      case Mbp1:
+        return raw_.data.mbp1;
+      case Ohlcv:
+        return raw_.data.ohlcv;
+      case Trades:
+        return raw_.data.trade;
+      case Tbbo:
+        return TbboRecord{raw_.data.tbbo};
+      case Bbo:
+        return raw_.data.bbo;
+    }
+    throw Error(InvalidArgument, "Unknown rtype " + std::to_string(raw_.rtype));
+  }
+
+  const CRecordEnum& raw() const noexcept { return raw_; }
+  const RecordData& data() const noexcept { return raw_.data; }
+
+ private:
+  bool is(RType rtype) const noexcept { return raw_.rtype == rtype; }
+
+  CRecordEnum raw_;
+};
+
+// Non-owning view of metadata, e.g. the header read by a `RecordDecoder`.
+class MetadataView {
+ public:
+  explicit MetadataView(const CMetadata* metadata) : metadata_(metadata) {}
+
+  // Values of mbinary's `Schema` and `Dataset` enums.
+  uint8_t schema() const noexcept { return metadata_schema(metadata_); }
+  uint8_t dataset() const noexcept { return metadata_dataset(metadata_); }
+  uint64_t start() const noexcept { return metadata_start(metadata_); }
+  uint64_t end() const noexcept { return metadata_end(metadata_); }
+
+  std::vector<uint32_t> instrument_ids() const {
+    size_t size = 0;
+    detail::check(metadata_instrument_ids(metadata_, nullptr, &size));
+    std::vector<uint32_t> ids(size);
+    detail::check(metadata_instrument_ids(metadata_, ids.data(), &size));
+    return ids;
+  }
+
+  // Ticker mapped to `instrument_id`, empty when it is not in the symbol map.
+  std::optional<std::string> ticker(uint32_t instrument_id) const {
+    size_t size = 0;
+    MbinaryError code = metadata_get_ticker(metadata_, instrument_id, nullptr, &size);
+    if (code == NotFound) {
+      return std::nullopt;
+    }
+    detail::check(code);
+
+    std::string ticker(size, '\0');
+    detail::check(metadata_get_ticker(metadata_, instrument_id, ticker.data(), &size));
+    ticker.resize(size - 1);
+    return ticker;
+  }
+
+  const CMetadata* get() const noexcept { return metadata_; }
+
+ private:
+  const CMetadata* metadata_;
+};
+
+// Ticker to instrument id mappings used to build `Metadata`.
+class SymbolMap {
+ public:
+  SymbolMap() : mappings_(detail::check(create_symbol_map())) {}
+
+  SymbolMap& add(const std::string& ticker, uint32_t instrument_id) {
+    detail::check(symbol_map_add_instrument(mappings_.get(), ticker.c_str(), instrument_id));
+    return *this;
+  }
+
+  const CSymbolMap* get() const noexcept { return mappings_.get(); }
+
+ private:
+  std::unique_ptr<CSymbolMap, detail::SymbolMapDeleter> mappings_;
+};
+
+// Owned metadata header.
+class Metadata {
+ public:
+  Metadata(uint8_t schema, uint8_t dataset, uint64_t start, uint64_t end,
+           const SymbolMap& mappings)
+      : metadata_(detail::check(create_metadata(schema, dataset, start, end, mappings.get()))) {}
+
+  // Decode the length-prefixed header at the start of `source`.
+  static Metadata decode(const uint8_t* source, size_t size) {
+    return Metadata(detail::check(decode_metadata(source, size)));
+  }
+
+  static Metadata decode(const std::vector<uint8_t>& source) {
+    return decode(source.data(), source.size());
+  }
+
+  // Bytes the header takes at the start of `source`, 0 when it holds records only.
+  static size_t header_size(const uint8_t* source, size_t size) {
+    size_t header_size = 0;
+    detail::check(metadata_header_size(source, size, &header_size));
+    return header_size;
+  }
+
+  MetadataView view() const noexcept { return MetadataView(metadata_.get()); }
+  const CMetadata* get() const noexcept { return metadata_.get(); }
+
+ private:
+  explicit Metadata(CMetadata* metadata) : metadata_(metadata) {}
+
+  std::unique_ptr<CMetadata, detail::MetadataDeleter> metadata_;
+};
+
+// Input iterator over the records of `Decoder`, the default constructed iterator is the end.
+template <typename Decoder, typename Value>
+class DecoderIterator {
+ public:
+  using iterator_category = std::input_iterator_tag;
+  using value_type = Value;
+  using difference_type = std::ptrdiff_t;
+  using pointer = const Value*;
+  using reference = const Value&;
+
+  DecoderIterator() = default;
+  explicit DecoderIterator(Decoder* decoder) : decoder_(decoder) { ++*this; }
+
+  reference operator*() const noexcept { return value_; }
+  pointer operator->() const noexcept { return &value_; }
+
+  DecoderIterator& operator++() {
+    if (decoder_ != nullptr && !decoder_->next(value_)) {
+      decoder_ = nullptr;
+    }
+    return *this;
+  }
+
+  void operator++(int) { ++*this; }
+
+  bool operator==(const DecoderIterator& other) const noexcept {
+    return decoder_ == other.decoder_;
+  }
+  bool operator!=(const DecoderIterator& other) const noexcept { return !(*this == other); }
+
+ private:
+  Decoder* decoder_ = nullptr;
+  Value value_;
+};
+
+// Header predicates for `RecordDecoder::filter`, setters chain and unset predicates match
+// every record.
+class RecordFilter {
+ public:
+  RecordFilter() : filter_(detail::check(create_record_filter())) {}
+
+  // Keep records for `instrument_ids`, in addition to any instruments already kept.
+  RecordFilter& instrument_ids(const std::vector<uint32_t>& instrument_ids) {
+    detail::check(
+        record_filter_instrument_ids(filter_.get(), instrument_ids.data(), instrument_ids.size()));
+    return *this;
+  }
+
+  // Keep records of `rtypes`, in addition to any types already kept.
+  RecordFilter& rtypes(const std::vector<RType>& rtypes) {
+    std::vector<uint8_t> values(rtypes.begin(), rtypes.end());
+    detail::check(record_filter_rtypes(filter_.get(), values.data(), values.size()));
+    return *this;
+  }
+
+  // Keep records with a `ts_event` at or after `start`.
+  RecordFilter& start(uint64_t start) {
+    detail::check(record_filter_start(filter_.get(), start));
+    return *this;
+  }
+
+  // Keep records with a `ts_event` before `end`.
+  RecordFilter& end(uint64_t end) {
+    detail::check(record_filter_end(filter_.get(), end));
+    return *this;
+  }
+
+  bool matches(const RecordHeader& header) const noexcept {
+    return record_filter_matches(filter_.get(), &header);
+  }
+
+  const CRecordFilter* get() const noexcept { return filter_.get(); }
+
+ private:
+  std::unique_ptr<CRecordFilter, detail::FilterDeleter> filter_;
+};
+
+class RecordDecoder;
+using RecordIterator = DecoderIterator<RecordDecoder, Record>;
+
+// Owning wrapper around `CRecordDecoder`, iterable with range-based for.
+class RecordDecoder {
+ public:
+  // Decode a file of records only.
+  static RecordDecoder open(const std::string& path) {
+    return RecordDecoder(detail::check(create_file_decoder(path.c_str())));
+  }
+
+  // Decode a file, reading its metadata header first when it has one.
+  static RecordDecoder open_with_metadata(const std::string& path) {
+    return RecordDecoder(detail::check(create_metadata_file_decoder(path.c_str())));
+  }
+
+  // Decode a copy of `source`, which holds records only.
+  static RecordDecoder from_buffer(const uint8_t* source, size_t size) {
+    return RecordDecoder(detail::check(create_buffer_decoder(source, size)));
+  }
+
+  static RecordDecoder from_buffer(const std::vector<uint8_t>& source) {
+    return from_buffer(source.data(), source.size());
+  }
+
+  // Decode a copy of `source`, reading its metadata header first when it has one.
+  static RecordDecoder from_buffer_with_metadata(const uint8_t* source, size_t size) {
+    return RecordDecoder(detail::check(create_metadata_buffer_decoder(source, size)));
+  }
+
+  static RecordDecoder from_buffer_with_metadata(const std::vector<uint8_t>& source) {
+    return from_buffer_with_metadata(source.data(), source.size());
+  }
+
+  // Decode only the remaining records passing `filter`, replacing any filter set before.
+  RecordDecoder& filter(const RecordFilter& filter) {
+    detail::check(decoder_set_filter(decoder_.get(), filter.get()));
+    return *this;
+  }
+
+  // Metadata read from the source and narrowed by the filter, valid until the decoder is
+  // destroyed or filtered again.
+  std::optional<MetadataView> metadata() const {
+    const CMetadata* metadata = decoder_get_metadata(decoder_.get());
+    if (metadata == nullptr) {
+      return std::nullopt;
+    }
+    return MetadataView(metadata);
+  }
+
+  // Decodes the next record into `record`, false once all records are decoded.
+  bool next(Record& record) {
+    CRecordEnum raw;
+    if (decoder_iter(decoder_.get(), &raw)) {
+      record = Record(raw);
+      return true;
+    }
+    detail::check(mbinary_last_error_code());
+    return false;
+  }
+
+  std::optional<Record> next() {
+    Record record;
+    if (next(record)) {
+      return record;
+    }
+    return std::nullopt;
+  }
+
+  std::vector<Record> decode_all() {
+    std::vector<Record> records;
+    for (const Record& record : *this) {
+      records.push_back(record);
+    }
+    return records;
+  }
+
+  // Decodes up to `capacity` records into `output`, returning how many, 0 once all records
+  // are decoded.
+  size_t fill(CRecordEnum* output, size_t capacity) {
+    size_t count = 0;
+    detail::check(decoder_fill(decoder_.get(), output, capacity, &count));
+    return count;
+  }
+
+  RecordIterator begin() { return RecordIterator(this); }
+  RecordIterator end() { return RecordIterator(); }
+
+  CRecordDecoder* get() const noexcept { return decoder_.get(); }
+
+ private:
+  explicit RecordDecoder(CRecordDecoder* decoder) : decoder_(decoder) {}
+
+  std::unique_ptr<CRecordDecoder, detail::DecoderDeleter> decoder_;
+};
+
+// Record decoded in place by `BorrowedDecoder`, pointing into the caller's buffer.
+class RecordView {
+ public:
+  RecordView() = default;
+  explicit RecordView(const RecordHeader* header) noexcept : header_(header) {}
+
+  RType rtype() const noexcept { return static_cast<RType>(header_->rtype); }
+  const RecordHeader& header() const noexcept { return *header_; }
+  uint32_t instrument_id() const noexcept { return header_->instrument_id; }
+  uint64_t ts_event() const noexcept { return header_->ts_event; }
+
+  // Typed access, null when the record is of another type.
+  const Mbp1Msg* mbp1() const noexcept { return as<Mbp1Msg>(Mbp1); }
+  const OhlcvMsg* ohlcv() const noexcept { return as<OhlcvMsg>(Ohlcv); }
+  const TradeMsg* trade() const noexcept { return as<TradeMsg>(Trades); }
+  const TbboMsg* tbbo() const noexcept { return as<TbboMsg>(Tbbo); }
+  const BboMsg* bbo() const noexcept { return as<BboMsg>(Bbo); }
+
+  const RecordHeader* get() const noexcept { return header_; }
+
+ private:
+  template <typename T>
+  const T* as(RType rtype) const noexcept {
+    return header_->rtype == rtype ? reinterpret_cast<const T*>(header_) : nullptr;
+  }
+
+  const RecordHeader* header_ = nullptr;
+};
+
+class BorrowedDecoder;
+using RecordViewIterator = DecoderIterator<BorrowedDecoder, RecordView>;
+
+// Owning wrapper around `CBorrowedDecoder`, decoding without copying the source.
+//
+// The source must start 8-byte aligned and outlive the decoder and every `RecordView`.
+class BorrowedDecoder {
+ public:
+  static BorrowedDecoder from_buffer(const uint8_t* source, size_t size) {
+    return BorrowedDecoder(detail::check(create_borrowed_decoder(source, size)));
+  }
+
+  // Reads the metadata header first when `source` starts with one, the records after it
+  // must be 8-byte aligned rather than `source` itself, see `metadata_header_size`.
+  static BorrowedDecoder from_buffer_with_metadata(const uint8_t* source, size_t size) {
+    return BorrowedDecoder(detail::check(create_borrowed_metadata_decoder(source, size)));
+  }
+
+  std::optional<MetadataView> metadata() const {
+    const CMetadata* metadata = borrowed_decoder_get_metadata(decoder_.get());
+    if (metadata == nullptr) {
+      return std::nullopt;
+    }
+    return MetadataView(metadata);
+  }
+
+  // Points `record` at the next record, false once all records are decoded.
+  bool next(RecordView& record) {
+    const RecordHeader* header = nullptr;
+    if (borrowed_decoder_next(decoder_.get(), &header)) {
+      record = RecordView(header);
+      return true;
+    }
+    detail::check(mbinary_last_error_code());
+    return false;
+  }
+
+  // Writes pointers to up to `capacity` records into `output`, returning how many, 0 once
+  // all records are decoded.
+  size_t fill(const RecordHeader** output, size_t capacity) {
+    size_t count = 0;
+    detail::check(borrowed_decoder_fill(decoder_.get(), output, capacity, &count));
+    return count;
+  }
+
+  // Byte offset of the next record in the source.
+  size_t offset() const noexcept { return borrowed_decoder_offset(decoder_.get()); }
+
+  RecordViewIterator begin() { return RecordViewIterator(this); }
+  RecordViewIterator end() { return RecordViewIterator(); }
+
+  CBorrowedDecoder* get() const noexcept { return decoder_.get(); }
+
+ private:
+  explicit BorrowedDecoder(CBorrowedDecoder* decoder) : decoder_(decoder) {}
+
+  std::unique_ptr<CBorrowedDecoder, detail::BorrowedDecoderDeleter> decoder_;
+};
+
+// Owning wrapper around `CRecordEncoder`, encoding to a buffer or streaming to a file.
+class RecordEncoder {
+ public:
+  RecordEncoder() : encoder_(detail::check(create_record_encoder())) {}
+
+  // Stream to the file at `path`, appending to it or truncating it.
+  static RecordEncoder open(const std::string& path, bool append = false) {
+    return RecordEncoder(detail::check(create_file_encoder(path.c_str(), append)));
+  }
+
+#if defined(__unix__) || defined(__APPLE__)
+  // Stream to a duplicate of `fd`, the caller still owns and must close `fd`.
+  static RecordEncoder from_fd(int fd) {
+    return RecordEncoder(detail::check(create_fd_encoder(fd)));
+  }
+#endif
+
+  RecordEncoder& encode_metadata(const MetadataView& metadata) {
+    detail::check(::encode_metadata(encoder_.get(), metadata.get()));
+    return *this;
+  }
+
+  RecordEncoder& encode_metadata(const Metadata& metadata) {
+    return encode_metadata(metadata.view());
+  }
+
+  // Encode records after anything already encoded.
+  RecordEncoder& append(const RecordData* records, size_t count) {
+    detail::check(append_records(encoder_.get(), records, count));
+    return *this;
+  }
+
+  RecordEncoder& append(const std::vector<RecordData>& records) {
+    return append(records.data(), records.size());
+  }
+
+  RecordEncoder& append(const Record& record) { return append(&record.data(), 1); }
+
+  RecordEncoder& append(const std::vector<Record>& records) {
+    for (const Record& record : records) {
+      append(record);
+    }
+    return *this;
+  }
+
+  // Bytes encoded to the buffer, empty when streaming to a file.
+  std::vector<uint8_t> data() const {
+    size_t size = 0;
+    detail::check(get_encoded_data(encoder_.get(), nullptr, &size));
+    std::vector<uint8_t> data(size);
+    detail::check(get_encoded_data(encoder_.get(), data.data(), &size));
+    return data;
+  }
+
+  // Write the buffer to the file at `path`.
+  void write_to_file(const std::string& path, bool append = false) {
+    detail::check(write_buffer_to_file(encoder_.get(), path.c_str(), append));
+  }
+
+  void flush() { detail::check(flush_encoder(encoder_.get())); }
+
+  CRecordEncoder* get() const noexcept { return encoder_.get(); }
+
+ private:
+  explicit RecordEncoder(CRecordEncoder* encoder) : encoder_(encoder) {}
+
+  std::unique_ptr<CRecordEncoder, detail::EncoderDeleter> encoder_;
+};
+
+// Contiguous views returned by the backtest and live accessors.
+template <typename T>
+struct Span {
+  const T* data = nullptr;
+  size_t size = 0;
+
+  const T* begin() const noexcept { return data; }
+  const T* end() const noexcept { return data + size; }
+  const T& operator[](size_t i) const noexcept { return data[i]; }
+  bool empty() const noexcept { return size == 0; }
+};
+
+namespace detail {
+
+template <typename T, typename H>
+Span<T> span(const H* handle, const T* (*view)(const H*, size_t*)) {
+  Span<T> span;
+  span.data = view(handle, &span.size);
+  return span;
+}
+
+}  // namespace detail
+
+// Owning wrapper around `CBacktest`, views are valid until the backtest is modified.
+class Backtest {
+ public:
+  // `backtest_id` of 65535 means the id is assigned later.
+  Backtest(uint16_t backtest_id, const std::string& name, const CParameters& parameters,
+           const StaticStats* static_stats = nullptr)
+      : backtest_(
+            detail::check(create_backtest(backtest_id, name.c_str(), &parameters, static_stats))) {}
+
+  static Backtest decode(const std::vector<uint8_t>& source) {
+    return Backtest(detail::check(decode_backtest(source.data(), source.size())));
+  }
+
+  static Backtest read_file(const std::string& path) {
+    return Backtest(detail::check(read_backtest_file(path.c_str())));
+  }
+
+  Backtest& set_static_stats(const StaticStats& stats) {
+    detail::check(backtest_set_static_stats(backtest_.get(), &stats));
+    return *this;
+  }
+
+  Backtest& add_period_timeseries(const std::vector<TimeseriesStats>& stats) {
+    detail::check(backtest_add_period_timeseries(backtest_.get(), stats.data(), stats.size()));
+    return *this;
+  }
+
+  Backtest& add_daily_timeseries(const std::vector<TimeseriesStats>& stats) {
+    detail::check(backtest_add_daily_timeseries(backtest_.get(), stats.data(), stats.size()));
+    return *this;
+  }
+
+  Backtest& add_trades(const std::vector<CTrade>& trades) {
+    detail::check(backtest_add_trades(backtest_.get(), trades.data(), trades.size()));
+    return *this;
+  }
+
+  Backtest& add_signals(const std::vector<CSignal>& signals) {
+    detail::check(backtest_add_signals(backtest_.get(), signals.data(), signals.size()));
+    return *this;
+  }
+
+  std::vector<uint8_t> encode() const { return detail::encode(backtest_.get(), encode_backtest); }
+
+  void write_file(const std::string& path) const {
+    detail::check(write_backtest_file(backtest_.get(), path.c_str()));
+  }
+
+  uint16_t id() const noexcept { return backtest_get_id(backtest_.get()); }
+  std::string name() const { return backtest_name(backtest_.get()); }
+  const CParameters& parameters() const noexcept { return *backtest_parameters(backtest_.get()); }
+  const StaticStats& static_stats() const noexcept {
+    return *backtest_static_stats(backtest_.get());
+  }
+  Span<TimeseriesStats> period_timeseries() const {
+    return detail::span(backtest_.get(), backtest_period_timeseries);
+  }
+  Span<TimeseriesStats> daily_timeseries() const {
+    return detail::span(backtest_.get(), backtest_daily_timeseries);
+  }
+  Span<CTrade> trades() const { return detail::span(backtest_.get(), backtest_trades); }
+  Span<CSignal> signals() const { return detail::span(backtest_.get(), backtest_signals); }
+
+  CBacktest* get() const noexcept { return backtest_.get(); }
+
+ private:
+  explicit Backtest(CBacktest* backtest) : backtest_(backtest) {}
+
+  std::unique_ptr<CBacktest, detail::BacktestDeleter> backtest_;
+};
+
+// Owning wrapper around `CLiveData`, views are valid until the session is modified.
+class LiveData {
+ public:
+  // `live_id` of 65535 means the session has no id yet.
+  LiveData(uint16_t live_id, const CParameters& parameters, const CAccountSummary& account)
+      : live_(detail::check(create_live_data(live_id, &parameters, &account))) {}
+
+  static LiveData decode(const std::vector<uint8_t>& source) {
+    return LiveData(detail::check(decode_live_data(source.data(), source.size())));
+  }
+
+  static LiveData read_file(const std::string& path) {
+    return LiveData(detail::check(read_live_data_file(path.c_str())));
+  }
+
+  LiveData& add_trades(const std::vector<CTrade>& trades) {
+    detail::check(live_data_add_trades(live_.get(), trades.data(), trades.size()));
+    return *this;
+  }
+
+  LiveData& add_signals(const std::vector<CSignal>& signals) {
+    detail::check(live_data_add_signals(live_.get(), signals.data(), signals.size()));
+    return *this;
+  }
+
+  LiveData& add_account_snapshots(const std::vector<AccountSnapshot>& snapshots) {
+    detail::check(
+        live_data_add_account_snapshots(live_.get(), snapshots.data(), snapshots.size()));
+    return *this;
+  }
+
+  std::vector<uint8_t> encode() const { return detail::encode(live_.get(), encode_live_data); }
+
+  void write_file(const std::string& path) const {
+    detail::check(write_live_data_file(live_.get(), path.c_str()));
+  }
+
+  uint16_t id() const noexcept { return live_data_get_id(live_.get()); }
+  const CParameters& parameters() const noexcept { return *live_data_parameters(live_.get()); }
+  const CAccountSummary& account_summary() const noexcept {
+    return *live_data_account_summary(live_.get());
+  }
+  Span<CTrade> trades() const { return detail::span(live_.get(), live_data_trades); }
+  Span<CSignal> signals() const { return detail::span(live_.get(), live_data_signals); }
+  Span<AccountSnapshot> account_snapshots() const {
+    return detail::span(live_.get(), live_data_account_snapshots);
+  }
+
+  CLiveData* get() const noexcept { return live_.get(); }
+
+ private:
+  explicit LiveData(CLiveData* live) : live_(live) {}
+
+  std::unique_ptr<CLiveData, detail::LiveDataDeleter> live_;
+};
+
+}  // namespace mbinary
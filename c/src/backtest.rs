@@ -0,0 +1,575 @@
+use mbinary::backtest::{
+    BacktestData, BacktestMetaData, Parameters, SignalInstructions, Signals, StaticStats,
+    TimeseriesStats, Trades,
+};
+use mbinary::backtest_decoder::BacktestDecoder;
+use mbinary::backtest_encode::BacktestEncoder;
+use mbinary::enums::{OrderType, TradeAction};
+use std::cell::OnceCell;
+use std::ffi::CString;
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::error::{
+    as_mut, as_ref, as_slice, as_str, boxed, set_error, status, value, MbinaryError,
+};
+use crate::metadata::copy_slice;
+
+/// Limit and aux prices of a `CSignalInstruction` without a price.
+pub const MBINARY_UNDEF_PRICE: i64 = 9223372036854775807;
+
+/// C view of `Parameters`, strings are NUL-terminated UTF-8.
+#[repr(C)]
+pub struct CParameters {
+    pub strategy_name: *const c_char,
+    pub capital: i64,
+    pub schema: *const c_char,
+    pub data_type: *const c_char,
+    pub start: i64,
+    pub end: i64,
+    pub tickers: *const *const c_char,
+    pub ticker_count: usize,
+}
+
+/// C view of `Trades`, `action` is a `TradeAction` value.
+#[repr(C)]
+pub struct CTrade {
+    pub trade_id: i32,
+    pub signal_id: i32,
+    pub timestamp: i64,
+    pub ticker: *const c_char,
+    pub quantity: i64,    // Scaled by 1e9
+    pub avg_price: i64,   // Scaled by 1e9
+    pub trade_value: i64, // Scaled by 1e9
+    pub trade_cost: i64,  // Scaled by 1e9
+    pub action: u8,
+    pub fees: i64, // Scaled by 1e9
+}
+
+/// C view of `SignalInstructions`, `order_type` and `action` are `OrderType` and
+/// `TradeAction` values and missing prices are `MBINARY_UNDEF_PRICE`.
+#[repr(C)]
+pub struct CSignalInstruction {
+    pub ticker: *const c_char,
+    pub order_type: u8,
+    pub action: u8,
+    pub signal_id: i32,
+    pub weight: i64, // Scaled by 1e9
+    pub quantity: i32,
+    pub limit_price: i64, // Scaled by 1e9
+    pub aux_price: i64,   // Scaled by 1e9
+}
+
+/// C view of `Signals`.
+#[repr(C)]
+pub struct CSignal {
+    pub timestamp: i64,
+    pub instructions: *const CSignalInstruction,
+    pub instruction_count: usize,
+}
+
+fn price(price: i64) -> Option<i64> {
+    (price != MBINARY_UNDEF_PRICE).then_some(price)
+}
+
+fn trade_action(action: u8) -> Result<TradeAction, MbinaryError> {
+    TradeAction::try_from(action).map_err(|_| {
+        set_error(
+            MbinaryError::InvalidArgument,
+            format!("Unknown TradeAction value: {}", action),
+        )
+    })
+}
+
+pub(crate) fn to_parameters(parameters: &CParameters) -> Result<Parameters, MbinaryError> {
+    let tickers = as_slice(parameters.tickers, parameters.ticker_count, "tickers")?
+        .iter()
+        .map(|&ticker| as_str(ticker, "ticker").map(str::to_string))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Parameters {
+        strategy_name: as_str(parameters.strategy_name, "strategy_name")?.to_string(),
+        capital: parameters.capital,
+        schema: as_str(parameters.schema, "schema")?.to_string(),
+        data_type: as_str(parameters.data_type, "data_type")?.to_string(),
+        start: parameters.start,
+        end: parameters.end,
+        tickers,
+    })
+}
+
+pub(crate) fn to_trades(trades: &[CTrade]) -> Result<Vec<Trades>, MbinaryError> {
+    trades
+        .iter()
+        .map(|trade| {
+            Ok(Trades {
+                trade_id: trade.trade_id,
+                signal_id: trade.signal_id,
+                timestamp: trade.timestamp,
+                ticker: as_str(trade.ticker, "ticker")?.to_string(),
+                quantity: trade.quantity,
+                avg_price: trade.avg_price,
+                trade_value: trade.trade_value,
+                trade_cost: trade.trade_cost,
+                action: trade_action(trade.action)?,
+                fees: trade.fees,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn to_signals(signals: &[CSignal]) -> Result<Vec<Signals>, MbinaryError> {
+    signals
+        .iter()
+        .map(|signal| {
+            let instructions = as_slice(
+                signal.instructions,
+                signal.instruction_count,
+                "instructions",
+            )?;
+            let trade_instructions = instructions
+                .iter()
+                .map(|instruction| {
+                    let order_type = OrderType::try_from(instruction.order_type).map_err(|_| {
+                        set_error(
+                            MbinaryError::InvalidArgument,
+                            format!("Unknown OrderType value: {}", instruction.order_type),
+                        )
+                    })?;
+
+                    Ok(SignalInstructions {
+                        ticker: as_str(instruction.ticker, "ticker")?.to_string(),
+                        order_type,
+                        action: trade_action(instruction.action)?,
+                        signal_id: instruction.signal_id,
+                        weight: instruction.weight,
+                        quantity: instruction.quantity,
+                        limit_price: price(instruction.limit_price),
+                        aux_price: price(instruction.aux_price),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Signals {
+                timestamp: signal.timestamp,
+                trade_instructions,
+            })
+        })
+        .collect()
+}
+
+/// C views over the parameters, trades and signals shared by backtests and live sessions,
+/// owning the NUL-terminated copies of their strings.
+pub(crate) struct SessionViews {
+    strings: Vec<CString>,
+    tickers: Vec<*const c_char>,
+    pub(crate) parameters: CParameters,
+    pub(crate) trades: Vec<CTrade>,
+    _instructions: Vec<Vec<CSignalInstruction>>,
+    pub(crate) signals: Vec<CSignal>,
+}
+
+impl SessionViews {
+    pub(crate) fn new(parameters: &Parameters, trades: &[Trades], signals: &[Signals]) -> Self {
+        let mut views = SessionViews {
+            strings: Vec::new(),
+            tickers: Vec::new(),
+            parameters: CParameters {
+                strategy_name: ptr::null(),
+                capital: parameters.capital,
+                schema: ptr::null(),
+                data_type: ptr::null(),
+                start: parameters.start,
+                end: parameters.end,
+                tickers: ptr::null(),
+                ticker_count: parameters.tickers.len(),
+            },
+            trades: Vec::with_capacity(trades.len()),
+            _instructions: Vec::with_capacity(signals.len()),
+            signals: Vec::with_capacity(signals.len()),
+        };
+
+        views.parameters.strategy_name = views.string(&parameters.strategy_name);
+        views.parameters.schema = views.string(&parameters.schema);
+        views.parameters.data_type = views.string(&parameters.data_type);
+        views.tickers = parameters
+            .tickers
+            .iter()
+            .map(|ticker| views.string(ticker))
+            .collect();
+        views.parameters.tickers = views.tickers.as_ptr();
+
+        for trade in trades {
+            let ticker = views.string(&trade.ticker);
+            views.trades.push(CTrade {
+                trade_id: trade.trade_id,
+                signal_id: trade.signal_id,
+                timestamp: trade.timestamp,
+                ticker,
+                quantity: trade.quantity,
+                avg_price: trade.avg_price,
+                trade_value: trade.trade_value,
+                trade_cost: trade.trade_cost,
+                action: trade.action.into(),
+                fees: trade.fees,
+            });
+        }
+
+        for signal in signals {
+            let instructions: Vec<CSignalInstruction> = signal
+                .trade_instructions
+                .iter()
+                .map(|instruction| CSignalInstruction {
+                    ticker: views.string(&instruction.ticker),
+                    order_type: instruction.order_type.into(),
+                    action: instruction.action.into(),
+                    signal_id: instruction.signal_id,
+                    weight: instruction.weight,
+                    quantity: instruction.quantity,
+                    limit_price: instruction.limit_price.unwrap_or(MBINARY_UNDEF_PRICE),
+                    aux_price: instruction.aux_price.unwrap_or(MBINARY_UNDEF_PRICE),
+                })
+                .collect();
+            views.signals.push(CSignal {
+                timestamp: signal.timestamp,
+                instructions: instructions.as_ptr(),
+                instruction_count: instructions.len(),
+            });
+            // Moving the Vec keeps its heap buffer, so the pointer above stays valid
+            views._instructions.push(instructions);
+        }
+
+        views
+    }
+
+    /// NUL-terminated copy of `value` owned by the views.
+    pub(crate) fn string(&mut self, value: &str) -> *const c_char {
+        let string = CString::new(value.replace('\0', "")).unwrap_or_default();
+        let pointer = string.as_ptr();
+        self.strings.push(string);
+        pointer
+    }
+}
+
+/// Writes the pointer to the first view and the view count, shared by the slice accessors.
+pub(crate) fn view_slice<T>(items: &[T], count: *mut usize) -> Result<*const T, MbinaryError> {
+    *as_mut(count, "count")? = items.len();
+    Ok(items.as_ptr())
+}
+
+pub(crate) fn decode_error(e: mbinary::Error) -> MbinaryError {
+    set_error(MbinaryError::DecodeError, e)
+}
+
+pub(crate) fn read_file(file_path: *const c_char) -> Result<Vec<u8>, MbinaryError> {
+    let path = as_str(file_path, "file_path")?;
+    std::fs::read(path).map_err(|e| {
+        set_error(
+            MbinaryError::IoError,
+            format!("Failed to open {}: {}", path, e),
+        )
+    })
+}
+
+pub(crate) fn write_file(file_path: *const c_char, bytes: &[u8]) -> Result<(), MbinaryError> {
+    let path = as_str(file_path, "file_path")?;
+    std::fs::write(path, bytes).map_err(|e| {
+        set_error(
+            MbinaryError::IoError,
+            format!("Failed to write {}: {}", path, e),
+        )
+    })
+}
+
+/// C-compatible wrapper around BacktestData
+pub struct CBacktest {
+    backtest: BacktestData,
+    views: OnceCell<BacktestViews>,
+}
+
+struct BacktestViews {
+    session: SessionViews,
+    name: *const c_char,
+}
+
+impl CBacktest {
+    fn new(backtest: BacktestData) -> Self {
+        CBacktest {
+            backtest,
+            views: OnceCell::new(),
+        }
+    }
+
+    fn views(&self) -> &BacktestViews {
+        self.views.get_or_init(|| {
+            let backtest = &self.backtest;
+            let mut session = SessionViews::new(
+                &backtest.metadata.parameters,
+                &backtest.trades,
+                &backtest.signals,
+            );
+            let name = session.string(&backtest.metadata.backtest_name);
+            BacktestViews { session, name }
+        })
+    }
+
+    /// Mutable access to the backtest, dropping views that may point into it.
+    fn backtest_mut(&mut self) -> &mut BacktestData {
+        self.views.take();
+        &mut self.backtest
+    }
+}
+
+/// Create an empty `CBacktest` to add results to.
+///
+/// `backtest_id` of 65535 means the id is assigned later, as in `BacktestMetaData::new`.
+/// Null `static_stats` starts with zeroed stats, see `backtest_set_static_stats`.
+#[no_mangle]
+pub extern "C" fn create_backtest(
+    backtest_id: u16,
+    backtest_name: *const c_char,
+    parameters: *const CParameters,
+    static_stats: *const StaticStats,
+) -> *mut CBacktest {
+    boxed(|| {
+        let backtest_name = as_str(backtest_name, "backtest_name")?;
+        let parameters = to_parameters(as_ref(parameters, "parameters")?)?;
+        let static_stats = if static_stats.is_null() {
+            StaticStats::default()
+        } else {
+            unsafe { (*static_stats).clone() }
+        };
+
+        Ok(CBacktest::new(BacktestData {
+            metadata: BacktestMetaData::new(
+                Some(backtest_id),
+                backtest_name,
+                parameters,
+                static_stats,
+            ),
+            period_timeseries_stats: Vec::new(),
+            daily_timeseries_stats: Vec::new(),
+            trades: Vec::new(),
+            signals: Vec::new(),
+            position_timeseries: Vec::new(),
+        }))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn backtest_set_static_stats(
+    backtest: *mut CBacktest,
+    static_stats: *const StaticStats,
+) -> MbinaryError {
+    status(|| {
+        let backtest = as_mut(backtest, "backtest")?;
+        let static_stats = as_ref(static_stats, "static_stats")?;
+        backtest.backtest_mut().metadata.static_stats = static_stats.clone();
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn backtest_add_period_timeseries(
+    backtest: *mut CBacktest,
+    stats: *const TimeseriesStats,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let backtest = as_mut(backtest, "backtest")?;
+        let stats = as_slice(stats, count, "stats")?;
+        backtest
+            .backtest_mut()
+            .period_timeseries_stats
+            .extend_from_slice(stats);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn backtest_add_daily_timeseries(
+    backtest: *mut CBacktest,
+    stats: *const TimeseriesStats,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let backtest = as_mut(backtest, "backtest")?;
+        let stats = as_slice(stats, count, "stats")?;
+        backtest
+            .backtest_mut()
+            .daily_timeseries_stats
+            .extend_from_slice(stats);
+        Ok(())
+    })
+}
+
+/// Add trades, nothing is added when any trade is invalid.
+#[no_mangle]
+pub extern "C" fn backtest_add_trades(
+    backtest: *mut CBacktest,
+    trades: *const CTrade,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let backtest = as_mut(backtest, "backtest")?;
+        let trades = to_trades(as_slice(trades, count, "trades")?)?;
+        backtest.backtest_mut().trades.extend(trades);
+        Ok(())
+    })
+}
+
+/// Add signals, nothing is added when any signal is invalid.
+#[no_mangle]
+pub extern "C" fn backtest_add_signals(
+    backtest: *mut CBacktest,
+    signals: *const CSignal,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let backtest = as_mut(backtest, "backtest")?;
+        let signals = to_signals(as_slice(signals, count, "signals")?)?;
+        backtest.backtest_mut().signals.extend(signals);
+        Ok(())
+    })
+}
+
+/// Copies the backtest, encoded as `BacktestEncoder::encode_backtest` does, into `output`.
+///
+/// With a null `output` only the encoded size is written to `output_size`.
+#[no_mangle]
+pub extern "C" fn encode_backtest(
+    backtest: *const CBacktest,
+    output: *mut u8,
+    output_size: *mut usize,
+) -> MbinaryError {
+    status(|| {
+        let backtest = as_ref(backtest, "backtest")?;
+        let mut bytes = Vec::new();
+        BacktestEncoder::new(&mut bytes).encode_backtest(&backtest.backtest);
+        copy_slice(&bytes, output, output_size)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn write_backtest_file(
+    backtest: *const CBacktest,
+    file_path: *const c_char,
+) -> MbinaryError {
+    status(|| {
+        let backtest = as_ref(backtest, "backtest")?;
+        let mut bytes = Vec::new();
+        BacktestEncoder::new(&mut bytes).encode_backtest(&backtest.backtest);
+        write_file(file_path, &bytes)
+    })
+}
+
+/// Decode a backtest from an in-memory buffer, framed or in the legacy layout.
+#[no_mangle]
+pub extern "C" fn decode_backtest(source: *const u8, source_size: usize) -> *mut CBacktest {
+    boxed(|| {
+        let source = as_slice(source, source_size, "source")?;
+        let backtest = BacktestDecoder::new(source)
+            .decode_backtest()
+            .map_err(decode_error)?;
+        Ok(CBacktest::new(backtest))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn read_backtest_file(file_path: *const c_char) -> *mut CBacktest {
+    boxed(|| {
+        let bytes = read_file(file_path)?;
+        let backtest = BacktestDecoder::new(bytes.as_slice())
+            .decode_backtest()
+            .map_err(decode_error)?;
+        Ok(CBacktest::new(backtest))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn backtest_get_id(backtest: *const CBacktest) -> u16 {
+    value(u16::MAX, || {
+        Ok(as_ref(backtest, "backtest")?.backtest.metadata.backtest_id)
+    })
+}
+
+// The accessors below return views owned by the backtest, valid until it is modified or
+// destroyed.
+
+#[no_mangle]
+pub extern "C" fn backtest_name(backtest: *const CBacktest) -> *const c_char {
+    value(ptr::null(), || {
+        Ok(as_ref(backtest, "backtest")?.views().name)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn backtest_parameters(backtest: *const CBacktest) -> *const CParameters {
+    value(ptr::null(), || {
+        Ok(&as_ref(backtest, "backtest")?.views().session.parameters as *const CParameters)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn backtest_static_stats(backtest: *const CBacktest) -> *const StaticStats {
+    value(ptr::null(), || {
+        Ok(&as_ref(backtest, "backtest")?.backtest.metadata.static_stats as *const StaticStats)
+    })
+}
+
+/// Period timeseries, with their number written to `count`.
+#[no_mangle]
+pub extern "C" fn backtest_period_timeseries(
+    backtest: *const CBacktest,
+    count: *mut usize,
+) -> *const TimeseriesStats {
+    value(ptr::null(), || {
+        let backtest = as_ref(backtest, "backtest")?;
+        view_slice(&backtest.backtest.period_timeseries_stats, count)
+    })
+}
+
+/// Daily timeseries, with their number written to `count`.
+#[no_mangle]
+pub extern "C" fn backtest_daily_timeseries(
+    backtest: *const CBacktest,
+    count: *mut usize,
+) -> *const TimeseriesStats {
+    value(ptr::null(), || {
+        let backtest = as_ref(backtest, "backtest")?;
+        view_slice(&backtest.backtest.daily_timeseries_stats, count)
+    })
+}
+
+/// Trades, with their number written to `count`.
+#[no_mangle]
+pub extern "C" fn backtest_trades(backtest: *const CBacktest, count: *mut usize) -> *const CTrade {
+    value(ptr::null(), || {
+        let backtest = as_ref(backtest, "backtest")?;
+        view_slice(&backtest.views().session.trades, count)
+    })
+}
+
+/// Signals, with their number written to `count`.
+#[no_mangle]
+pub extern "C" fn backtest_signals(
+    backtest: *const CBacktest,
+    count: *mut usize,
+) -> *const CSignal {
+    value(ptr::null(), || {
+        let backtest = as_ref(backtest, "backtest")?;
+        view_slice(&backtest.views().session.signals, count)
+    })
+}
+
+/// Destroy the `CBacktest`, null is ignored like `free`.
+#[no_mangle]
+pub extern "C" fn destroy_backtest(backtest: *mut CBacktest) {
+    if backtest.is_null() {
+        return;
+    }
+
+    unsafe {
+        let _ = Box::from_raw(backtest);
+    }
+}
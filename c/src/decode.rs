@@ -1,15 +1,98 @@
 use mbinary::decode::RecordDecoder;
-use std::ffi::CStr;
+use mbinary::enums::RType;
+use mbinary::filter::RecordFilter;
+use mbinary::metadata::Metadata;
+use mbinary::record_enum::RecordEnum;
+use mbinary::records::{BboMsg, Mbp1Msg, OhlcvMsg, RecordHeader, TradeMsg};
+use std::fmt::Display;
 use std::fs::File;
 use std::io::{BufReader, Cursor, Read};
+use std::mem;
 use std::ptr;
 use std::slice;
 
+use crate::error::{
+    as_mut, as_ref, as_slice, as_str, boxed, not_null, set_error, status, value, MbinaryError,
+};
+use crate::filter::CRecordFilter;
+use crate::metadata::{read_metadata, CMetadata};
 use crate::records::CRecordEnum;
 
 /// C-compatible wrapper around RecordDecoder
 pub struct CRecordDecoder {
     decoder: RecordDecoder<Box<dyn Read>>,
+    metadata: Option<CMetadata>,
+    filter: RecordFilter,
+    filtered_metadata: Option<CMetadata>,
+}
+
+impl CRecordDecoder {
+    fn new(reader: Box<dyn Read>, metadata: Option<Metadata>) -> Self {
+        CRecordDecoder {
+            decoder: RecordDecoder::new(reader),
+            metadata: metadata.map(|metadata| CMetadata { metadata }),
+            filter: RecordFilter::new(),
+            filtered_metadata: None,
+        }
+    }
+
+    /// Decodes the next record passing the filter.
+    fn next_record(&mut self) -> Result<Option<CRecordEnum>, MbinaryError> {
+        let filter = &self.filter;
+        let record_ref = self
+            .decoder
+            .decode_ref_where(|header| filter.matches(header))
+            .map_err(record_error)?;
+
+        match record_ref {
+            Some(record_ref) => {
+                let record = RecordEnum::from_ref(record_ref).map_err(record_error)?;
+                Ok(Some(record.into()))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+fn record_error(e: impl Display) -> MbinaryError {
+    set_error(
+        MbinaryError::DecodeError,
+        format!("Failed to decode record: {}", e),
+    )
+}
+
+// Helper function, not exposed to C directly.
+fn source_buffer(source: *const u8, source_size: usize) -> Result<Cursor<Vec<u8>>, MbinaryError> {
+    not_null(source, "source")?;
+    if source_size == 0 {
+        return Err(set_error(
+            MbinaryError::InvalidArgument,
+            "`source_size` is zero",
+        ));
+    }
+
+    // Convert the raw pointer and size to a Vec<u8>
+    let source_slice = unsafe { slice::from_raw_parts(source, source_size) };
+    Ok(Cursor::new(source_slice.to_vec()))
+}
+
+// Helper function, not exposed to C directly.
+fn open_file(file_path: *const libc::c_char) -> Result<BufReader<File>, MbinaryError> {
+    let path = as_str(file_path, "file_path")?;
+    let file = File::open(path).map_err(|e| {
+        set_error(
+            MbinaryError::IoError,
+            format!("Failed to open {}: {}", path, e),
+        )
+    })?;
+    Ok(BufReader::new(file))
+}
+
+fn metadata_error(e: std::io::Error) -> MbinaryError {
+    set_error(
+        MbinaryError::DecodeError,
+        format!("Failed to read metadata: {}", e),
+    )
 }
 
 /// Create a new `CRecordDecoder` with an in-memory buffer as the source.
@@ -18,78 +101,358 @@ pub extern "C" fn create_buffer_decoder(
     source: *const u8,
     source_size: usize,
 ) -> *mut CRecordDecoder {
-    if source.is_null() || source_size == 0 {
-        return ptr::null_mut();
-    }
-
-    // Convert the raw pointer and size to a Vec<u8>
-    let source_slice = unsafe { slice::from_raw_parts(source, source_size) };
-    let source_buffer = Cursor::new(source_slice.to_vec());
-
-    let decoder = CRecordDecoder {
-        decoder: RecordDecoder::new(Box::new(source_buffer)),
-    };
+    boxed(|| {
+        let source_buffer = source_buffer(source, source_size)?;
 
-    Box::into_raw(Box::new(decoder))
+        Ok(CRecordDecoder::new(Box::new(source_buffer), None))
+    })
 }
 
 /// Create a new `CRecordDecoder` with a file as the source.
 #[no_mangle]
 pub extern "C" fn create_file_decoder(file_path: *const libc::c_char) -> *mut CRecordDecoder {
-    if file_path.is_null() {
-        return ptr::null_mut();
-    }
+    boxed(|| {
+        let reader = open_file(file_path)?;
+
+        Ok(CRecordDecoder::new(Box::new(reader), None))
+    })
+}
 
-    // Convert C string to Rust Path
-    let c_str = unsafe { CStr::from_ptr(file_path) };
-    let path = match c_str.to_str() {
-        Ok(path) => path,
-        Err(_) => return ptr::null_mut(),
-    };
+/// Create a new `CRecordDecoder` with an in-memory buffer as the source, reading the metadata
+/// header first when the buffer starts with one.
+#[no_mangle]
+pub extern "C" fn create_metadata_buffer_decoder(
+    source: *const u8,
+    source_size: usize,
+) -> *mut CRecordDecoder {
+    boxed(|| {
+        let mut source_buffer = source_buffer(source, source_size)?;
+        let metadata = read_metadata(&mut source_buffer).map_err(metadata_error)?;
 
-    let decoder = match create_decoder_from_file(path) {
-        Ok(f) => f,
-        Err(_) => return ptr::null_mut(),
-    };
+        Ok(CRecordDecoder::new(Box::new(source_buffer), metadata))
+    })
+}
 
-    let c_decoder = CRecordDecoder { decoder };
+/// Create a new `CRecordDecoder` with a file as the source, reading the metadata header first
+/// when the file starts with one.
+#[no_mangle]
+pub extern "C" fn create_metadata_file_decoder(
+    file_path: *const libc::c_char,
+) -> *mut CRecordDecoder {
+    boxed(|| {
+        let mut reader = open_file(file_path)?;
+        let metadata = read_metadata(&mut reader).map_err(metadata_error)?;
 
-    Box::into_raw(Box::new(c_decoder))
+        Ok(CRecordDecoder::new(Box::new(reader), metadata))
+    })
 }
 
-// Helper function, not exposed to C directly.
-fn create_decoder_from_file(
-    file_path: &str,
-) -> Result<RecordDecoder<Box<dyn Read>>, std::io::Error> {
-    let file = File::open(file_path)?;
-    let buf_reader = BufReader::new(file);
-    let boxed_reader: Box<dyn Read> = Box::new(buf_reader);
-    Ok(RecordDecoder::new(boxed_reader))
+/// Metadata read by the decoder, or null when the source had none. With a filter set the
+/// metadata is narrowed to match, see `decoder_set_filter`.
+///
+/// The metadata is owned by the decoder and valid until it is destroyed or its filter is set.
+#[no_mangle]
+pub extern "C" fn decoder_get_metadata(decoder: *const CRecordDecoder) -> *const CMetadata {
+    value(ptr::null(), || {
+        let decoder = as_ref(decoder, "decoder")?;
+        Ok(
+            match decoder
+                .filtered_metadata
+                .as_ref()
+                .or(decoder.metadata.as_ref())
+            {
+                Some(metadata) => metadata,
+                None => ptr::null(),
+            },
+        )
+    })
+}
+
+/// Decode only the remaining records passing `filter`, replacing any filter set before.
+///
+/// `filter` is copied so it can be destroyed or reused afterwards.
+#[no_mangle]
+pub extern "C" fn decoder_set_filter(
+    decoder: *mut CRecordDecoder,
+    filter: *const CRecordFilter,
+) -> MbinaryError {
+    status(|| {
+        let decoder = as_mut(decoder, "decoder")?;
+        let filter = as_ref(filter, "filter")?.filter.clone();
+        decoder.filtered_metadata = decoder.metadata.as_ref().map(|metadata| CMetadata {
+            metadata: filter.filter_metadata(&metadata.metadata),
+        });
+        decoder.filter = filter;
+        Ok(())
+    })
 }
 
 /// Iteratively decodes records, returning false when all records are decoded.
+///
+/// False with `mbinary_last_error_code()` other than `Success` means decoding failed.
 #[no_mangle]
 pub extern "C" fn decoder_iter(decoder: *mut CRecordDecoder, output: *mut CRecordEnum) -> bool {
-    if decoder.is_null() || output.is_null() {
-        return false;
+    value(false, || {
+        let decoder = as_mut(decoder, "decoder")?;
+        let output = as_mut(output, "output")?;
+
+        match decoder.next_record()? {
+            Some(c_record) => {
+                unsafe { ptr::write(output, c_record) };
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    })
+}
+
+/// Decodes up to `capacity` records into `output`, writing the number decoded to `count`.
+///
+/// A `count` of 0 means all records are decoded. On failure `count` holds the records decoded
+/// before the failing one.
+#[no_mangle]
+pub extern "C" fn decoder_fill(
+    decoder: *mut CRecordDecoder,
+    output: *mut CRecordEnum,
+    capacity: usize,
+    count: *mut usize,
+) -> MbinaryError {
+    status(|| {
+        let decoder = as_mut(decoder, "decoder")?;
+        let count = as_mut(count, "count")?;
+        *count = 0;
+        not_null(output, "output")?;
+
+        while *count < capacity {
+            match decoder.next_record()? {
+                Some(c_record) => {
+                    unsafe { ptr::write(output.add(*count), c_record) };
+                    *count += 1;
+                }
+                None => break,
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Destroy the `CRecordDecoder`, null is ignored like `free`.
+#[no_mangle]
+pub extern "C" fn destroy_record_decoder(decoder: *mut CRecordDecoder) {
+    if decoder.is_null() {
+        return;
     }
 
-    let decoder = unsafe { &mut *decoder };
-    let mut iterator = decoder.decoder.decode_iterator();
+    unsafe {
+        let _ = Box::from_raw(decoder);
+    }
+}
+
+/// Decodes records in place from a buffer borrowed from the caller, without copying it.
+pub struct CBorrowedDecoder {
+    source: *const u8,
+    source_size: usize,
+    offset: usize,
+    metadata: Option<CMetadata>,
+}
+
+/// Size of the message with `rtype`, none when the rtype is unknown.
+fn message_size(rtype: u8) -> Option<usize> {
+    Some(match RType::try_from(rtype).ok()? {
+        RType::Mbp1 | RType::Tbbo => mem::size_of::<Mbp1Msg>(),
+        RType::Ohlcv => mem::size_of::<OhlcvMsg>(),
+        RType::Trades => mem::size_of::<TradeMsg>(),
+        RType::Bbo => mem::size_of::<BboMsg>(),
+    })
+}
+
+fn is_aligned(pointer: *const u8) -> bool {
+    (pointer as usize).is_multiple_of(mem::align_of::<RecordHeader>())
+}
 
-    match iterator.next() {
-        Some(Ok(record_enum)) => {
-            let c_record: CRecordEnum = record_enum.into();
-            unsafe { ptr::write(output, c_record) };
-            true
+impl CBorrowedDecoder {
+    fn new(
+        source: &[u8],
+        offset: usize,
+        metadata: Option<CMetadata>,
+    ) -> Result<Self, MbinaryError> {
+        if offset < source.len() && !is_aligned(source[offset..].as_ptr()) {
+            return Err(set_error(
+                MbinaryError::InvalidArgument,
+                format!(
+                    "Records at offset {} of `source` are not {}-byte aligned",
+                    offset,
+                    mem::align_of::<RecordHeader>()
+                ),
+            ));
         }
-        _ => false,
+
+        Ok(CBorrowedDecoder {
+            source: source.as_ptr(),
+            source_size: source.len(),
+            offset,
+            metadata,
+        })
+    }
+
+    /// Next record in the source, advancing past it.
+    fn next(&mut self) -> Result<Option<*const RecordHeader>, MbinaryError> {
+        let remaining = self.source_size - self.offset;
+        if remaining == 0 {
+            return Ok(None);
+        }
+
+        let record = unsafe { self.source.add(self.offset) };
+        let decode_error = |message: &str| {
+            set_error(
+                MbinaryError::DecodeError,
+                format!("{} at offset {}", message, self.offset),
+            )
+        };
+        if remaining < mem::size_of::<RecordHeader>() {
+            return Err(decode_error("Truncated record header"));
+        }
+        if !is_aligned(record) {
+            return Err(decode_error("Misaligned record"));
+        }
+
+        let header = unsafe { &*(record as *const RecordHeader) };
+        let size = header.record_size();
+        let Some(message_size) = message_size(header.rtype) else {
+            return Err(decode_error(&format!("Unknown rtype {}", header.rtype)));
+        };
+        if size < message_size {
+            return Err(decode_error("Record shorter than its rtype"));
+        }
+        if size > remaining {
+            return Err(decode_error("Truncated record"));
+        }
+
+        self.offset += size;
+        Ok(Some(header))
     }
 }
 
-/// Destroy the `CRecordDecoder`
+/// Create a `CBorrowedDecoder` over records in `source`, which must start 8-byte aligned.
+///
+/// `source` is not copied and must outlive the decoder and the records it returns.
 #[no_mangle]
-pub extern "C" fn destroy_record_decoder(decoder: *mut CRecordDecoder) {
+pub extern "C" fn create_borrowed_decoder(
+    source: *const u8,
+    source_size: usize,
+) -> *mut CBorrowedDecoder {
+    boxed(|| {
+        let source = as_slice(source, source_size, "source")?;
+        CBorrowedDecoder::new(source, 0, None)
+    })
+}
+
+/// Create a `CBorrowedDecoder`, reading the metadata header first when `source` starts with
+/// one.
+///
+/// The header is not padded, so `source` must be placed such that the records after it are
+/// 8-byte aligned in memory, `metadata_header_size` gives where they start.
+#[no_mangle]
+pub extern "C" fn create_borrowed_metadata_decoder(
+    source: *const u8,
+    source_size: usize,
+) -> *mut CBorrowedDecoder {
+    boxed(|| {
+        let source = as_slice(source, source_size, "source")?;
+        let mut cursor = Cursor::new(source);
+        let metadata = read_metadata(&mut cursor).map_err(metadata_error)?;
+        let offset = cursor.position() as usize;
+
+        CBorrowedDecoder::new(
+            source,
+            offset,
+            metadata.map(|metadata| CMetadata { metadata }),
+        )
+    })
+}
+
+/// Metadata read by the decoder, or null when the source had none.
+///
+/// The metadata is owned by the decoder and valid until it is destroyed.
+#[no_mangle]
+pub extern "C" fn borrowed_decoder_get_metadata(
+    decoder: *const CBorrowedDecoder,
+) -> *const CMetadata {
+    value(ptr::null(), || {
+        let decoder = as_ref(decoder, "decoder")?;
+        Ok(match &decoder.metadata {
+            Some(metadata) => metadata,
+            None => ptr::null(),
+        })
+    })
+}
+
+/// Points `output` at the next record in the source, returning false when all records are
+/// decoded.
+///
+/// The record is cast to its message type by `rtype`. False with `mbinary_last_error_code()`
+/// other than `Success` means the record is invalid.
+#[no_mangle]
+pub extern "C" fn borrowed_decoder_next(
+    decoder: *mut CBorrowedDecoder,
+    output: *mut *const RecordHeader,
+) -> bool {
+    value(false, || {
+        let decoder = as_mut(decoder, "decoder")?;
+        let output = as_mut(output, "output")?;
+
+        match decoder.next()? {
+            Some(record) => {
+                *output = record;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    })
+}
+
+/// Writes pointers to up to `capacity` records into `output` and their number to `count`.
+///
+/// A `count` of 0 means all records are decoded. On failure `count` holds the records before
+/// the invalid one.
+#[no_mangle]
+pub extern "C" fn borrowed_decoder_fill(
+    decoder: *mut CBorrowedDecoder,
+    output: *mut *const RecordHeader,
+    capacity: usize,
+    count: *mut usize,
+) -> MbinaryError {
+    status(|| {
+        let decoder = as_mut(decoder, "decoder")?;
+        let count = as_mut(count, "count")?;
+        *count = 0;
+        not_null(output, "output")?;
+
+        while *count < capacity {
+            match decoder.next()? {
+                Some(record) => {
+                    unsafe { *output.add(*count) = record };
+                    *count += 1;
+                }
+                None => break,
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Offset in the source of the next record, e.g. to carry a partial record over to the next
+/// buffer.
+#[no_mangle]
+pub extern "C" fn borrowed_decoder_offset(decoder: *const CBorrowedDecoder) -> usize {
+    value(0, || Ok(as_ref(decoder, "decoder")?.offset))
+}
+
+/// Destroy the `CBorrowedDecoder`, leaving the source untouched. Null is ignored like `free`.
+#[no_mangle]
+pub extern "C" fn destroy_borrowed_decoder(decoder: *mut CBorrowedDecoder) {
     if decoder.is_null() {
         return;
     }
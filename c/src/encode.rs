@@ -1,74 +1,196 @@
-use mbinary::encode::RecordEncoder;
-use std::ffi::CStr;
+use mbinary::encode::{MetadataEncoder, RecordEncoder};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
 use std::os::raw::c_char;
 use std::path::Path;
 
+use crate::error::{as_mut, as_ref, as_str, boxed, not_null, set_error, status, MbinaryError};
+use crate::metadata::CMetadata;
 use crate::records::RecordData;
 
 /// C-compatible wrapper around RecordEncoder
 pub struct CRecordEncoder {
     buffer: Vec<u8>,
+    writer: Option<BufWriter<File>>,
+}
+
+impl CRecordEncoder {
+    /// Where encoded bytes go, the file when streaming and the buffer otherwise.
+    fn sink(&mut self) -> &mut dyn Write {
+        match &mut self.writer {
+            Some(writer) => writer,
+            None => &mut self.buffer,
+        }
+    }
+
+    fn from_file(file: File) -> CRecordEncoder {
+        CRecordEncoder {
+            buffer: Vec::new(),
+            writer: Some(BufWriter::new(file)),
+        }
+    }
+}
+
+fn io_error(context: &str, e: std::io::Error) -> MbinaryError {
+    set_error(MbinaryError::IoError, format!("{}: {}", context, e))
 }
 
 /// Create a new 'CRecordEncoder', encoding to a buffer.
 #[no_mangle]
 pub extern "C" fn create_record_encoder() -> *mut CRecordEncoder {
-    let c_encoder = CRecordEncoder { buffer: Vec::new() };
-    Box::into_raw(Box::new(c_encoder))
+    boxed(|| {
+        Ok(CRecordEncoder {
+            buffer: Vec::new(),
+            writer: None,
+        })
+    })
+}
+
+/// Create a new 'CRecordEncoder' streaming to the file at `file_path`, appending to it or
+/// truncating it.
+#[no_mangle]
+pub extern "C" fn create_file_encoder(
+    file_path: *const c_char,
+    append: bool,
+) -> *mut CRecordEncoder {
+    boxed(|| {
+        let path = as_str(file_path, "file_path")?;
+
+        let mut options = OpenOptions::new();
+        options.create(true);
+        if append {
+            options.append(true);
+        } else {
+            options.write(true).truncate(true);
+        }
+
+        let file = options
+            .open(path)
+            .map_err(|e| io_error(&format!("Failed to open {}", path), e))?;
+        Ok(CRecordEncoder::from_file(file))
+    })
+}
+
+/// Create a new 'CRecordEncoder' streaming to an open file descriptor.
+///
+/// The descriptor is duplicated, so the caller still owns and must close `fd`.
+#[cfg(unix)]
+#[no_mangle]
+pub extern "C" fn create_fd_encoder(fd: i32) -> *mut CRecordEncoder {
+    use std::os::unix::io::FromRawFd;
+
+    boxed(|| {
+        let duplicate = unsafe { libc::dup(fd) };
+        if duplicate < 0 {
+            let e = std::io::Error::last_os_error();
+            return Err(io_error(&format!("Failed to duplicate fd {}", fd), e));
+        }
+
+        Ok(CRecordEncoder::from_file(unsafe {
+            File::from_raw_fd(duplicate)
+        }))
+    })
 }
 
+/// Encode the length-prefixed metadata header, after anything already encoded.
+#[no_mangle]
+pub extern "C" fn encode_metadata(
+    encoder: *mut CRecordEncoder,
+    metadata: *const CMetadata,
+) -> MbinaryError {
+    status(|| {
+        let encoder = as_mut(encoder, "encoder")?;
+        let metadata = as_ref(metadata, "metadata")?;
+
+        MetadataEncoder::new(encoder.sink())
+            .encode_metadata(&metadata.metadata)
+            .map_err(|e| {
+                set_error(
+                    MbinaryError::EncodeError,
+                    format!("Failed to encode metadata: {}", e),
+                )
+            })
+    })
+}
+
+/// Encode records, replacing anything already in the buffer.
 #[no_mangle]
 pub extern "C" fn encode_records(
     encoder: *mut CRecordEncoder,
     records: *const RecordData,
     record_count: usize,
-) -> i32 {
-    if encoder.is_null() || records.is_null() {
-        return -1;
+) -> MbinaryError {
+    let cleared = status(|| {
+        let encoder = as_mut(encoder, "encoder")?;
+        encoder.buffer.clear(); // Clear previous data
+        Ok(())
+    });
+    if cleared != MbinaryError::Success {
+        return cleared;
     }
 
-    let encoder = unsafe { &mut *encoder };
-    encoder.buffer.clear(); // Clear previous data
-    let mut record_encoder = RecordEncoder::new(&mut encoder.buffer);
-
-    for i in 0..record_count {
-        // records is pointer to first RecordData, this iterated the pointer to i-th RecordData
-        let record = unsafe { &*records.add(i) };
-        let record_ref = record.to_record_ref();
+    append_records(encoder, records, record_count)
+}
 
-        // Attempt to encode the record
-        if let Err(_) = record_encoder.encode_record(&record_ref) {
-            return -2;
+/// Encode records after anything already encoded, so a file can be built across calls.
+#[no_mangle]
+pub extern "C" fn append_records(
+    encoder: *mut CRecordEncoder,
+    records: *const RecordData,
+    record_count: usize,
+) -> MbinaryError {
+    status(|| {
+        let encoder = as_mut(encoder, "encoder")?;
+        not_null(records, "records")?;
+        let mut record_encoder = RecordEncoder::new(encoder.sink());
+
+        for i in 0..record_count {
+            // records is pointer to first RecordData, this iterated the pointer to i-th RecordData
+            let record = unsafe { &*records.add(i) };
+            let record_ref = record.to_record_ref();
+
+            // Attempt to encode the record
+            record_encoder.encode_record(&record_ref).map_err(|e| {
+                set_error(
+                    MbinaryError::EncodeError,
+                    format!("Failed to encode record {}: {}", i, e),
+                )
+            })?;
         }
-    }
 
-    0
+        Ok(())
+    })
 }
 
+/// Copies the encoded buffer into `output`.
+///
+/// With a null `output` only the buffer size is written to `output_size`.
 #[no_mangle]
 pub extern "C" fn get_encoded_data(
     encoder: *const CRecordEncoder,
     output: *mut u8,
     output_size: *mut usize,
-) -> i32 {
-    if encoder.is_null() || output_size.is_null() {
-        return -1;
-    }
-
-    let encoder = unsafe { &*encoder };
-    let buffer = &encoder.buffer;
-
-    if !output.is_null() {
-        let output_slice = unsafe { std::slice::from_raw_parts_mut(output, buffer.len()) };
-        output_slice.copy_from_slice(buffer);
-    }
+) -> MbinaryError {
+    status(|| {
+        let encoder = as_ref(encoder, "encoder")?;
+        let output_size = as_mut(output_size, "output_size")?;
+        let buffer = &encoder.buffer;
+
+        if !output.is_null() {
+            if *output_size < buffer.len() {
+                return Err(set_error(
+                    MbinaryError::BufferTooSmall,
+                    format!("Output holds {} of {} bytes", output_size, buffer.len()),
+                ));
+            }
+            let output_slice = unsafe { std::slice::from_raw_parts_mut(output, buffer.len()) };
+            output_slice.copy_from_slice(buffer);
+        }
 
-    // Return the size of the buffer
-    unsafe {
+        // Return the size of the buffer
         *output_size = buffer.len();
-    }
-
-    0
+        Ok(())
+    })
 }
 
 #[no_mangle]
@@ -76,30 +198,34 @@ pub extern "C" fn write_buffer_to_file(
     encoder: *mut CRecordEncoder,
     file_path: *const c_char,
     append: bool,
-) -> i32 {
-    if encoder.is_null() || file_path.is_null() {
-        return -1;
-    }
-
-    // Convert the C string file path to a Rust Path
-    let c_str = unsafe { CStr::from_ptr(file_path) };
-    let path = match c_str.to_str() {
-        Ok(s) => Path::new(s),
-        Err(_) => return -2,
-    };
-
-    let encoder = unsafe { &mut *encoder };
-    let record_encoder = RecordEncoder::new(&mut encoder.buffer);
-
-    // Write the buffer to the file
-    if let Err(_) = record_encoder.write_to_file(path, append) {
-        return -4;
-    }
+) -> MbinaryError {
+    status(|| {
+        let encoder = as_mut(encoder, "encoder")?;
+        // Convert the C string file path to a Rust Path
+        let path = as_str(file_path, "file_path")?;
+        let record_encoder = RecordEncoder::new(&mut encoder.buffer);
+
+        // Write the buffer to the file
+        record_encoder
+            .write_to_file(Path::new(path), append)
+            .map_err(|e| io_error(&format!("Failed to write {}", path), e))
+    })
+}
 
-    0
+/// Flush records buffered for the file being streamed to, a no-op when encoding to a buffer.
+#[no_mangle]
+pub extern "C" fn flush_encoder(encoder: *mut CRecordEncoder) -> MbinaryError {
+    status(|| {
+        let encoder = as_mut(encoder, "encoder")?;
+        encoder
+            .sink()
+            .flush()
+            .map_err(|e| io_error("Failed to flush", e))
+    })
 }
 
-/// Destroy the `CRecordEncoder`
+/// Destroy the `CRecordEncoder`, flushing any file being streamed to. Null is ignored like
+/// `free`.
 #[no_mangle]
 pub extern "C" fn destroy_record_encoder(encoder: *mut CRecordEncoder) {
     if encoder.is_null() {
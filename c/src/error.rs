@@ -0,0 +1,132 @@
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::fmt::Display;
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+
+/// Result codes returned by the C API, `mbinary_last_error` describes the failure.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MbinaryError {
+    Success = 0,
+    NullPointer = -1,
+    InvalidArgument = -2,
+    BufferTooSmall = -3,
+    IoError = -4,
+    EncodeError = -5,
+    DecodeError = -6,
+    NotFound = -7,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(MbinaryError, CString)>> = const { RefCell::new(None) };
+}
+
+/// Records `message` as the calling thread's last error and returns `code`.
+pub(crate) fn set_error(code: MbinaryError, message: impl Display) -> MbinaryError {
+    let message = message.to_string().replace('\0', " ");
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some((code, message)));
+    code
+}
+
+pub(crate) fn clear_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Runs an API call body, clearing the last error first and reporting its result code.
+pub(crate) fn status<F>(body: F) -> MbinaryError
+where
+    F: FnOnce() -> Result<(), MbinaryError>,
+{
+    clear_error();
+    match body() {
+        Ok(()) => MbinaryError::Success,
+        Err(code) => code,
+    }
+}
+
+/// Like `status` for calls returning a value, `default` when the call fails.
+pub(crate) fn value<T, F>(default: T, body: F) -> T
+where
+    F: FnOnce() -> Result<T, MbinaryError>,
+{
+    clear_error();
+    body().unwrap_or(default)
+}
+
+/// Like `status` for constructors, boxing the result or returning null when the call fails.
+pub(crate) fn boxed<T, F>(body: F) -> *mut T
+where
+    F: FnOnce() -> Result<T, MbinaryError>,
+{
+    value(ptr::null_mut(), || {
+        body().map(|v| Box::into_raw(Box::new(v)))
+    })
+}
+
+pub(crate) fn not_null<T>(pointer: *const T, name: &str) -> Result<(), MbinaryError> {
+    if pointer.is_null() {
+        return Err(set_error(
+            MbinaryError::NullPointer,
+            format!("`{}` is null", name),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn as_ref<'a, T>(pointer: *const T, name: &str) -> Result<&'a T, MbinaryError> {
+    not_null(pointer, name)?;
+    Ok(unsafe { &*pointer })
+}
+
+pub(crate) fn as_mut<'a, T>(pointer: *mut T, name: &str) -> Result<&'a mut T, MbinaryError> {
+    not_null(pointer, name)?;
+    Ok(unsafe { &mut *pointer })
+}
+
+/// Borrows `count` items from a C array, null is allowed when empty.
+pub(crate) fn as_slice<'a, T>(
+    items: *const T,
+    count: usize,
+    name: &str,
+) -> Result<&'a [T], MbinaryError> {
+    if count == 0 {
+        return Ok(&[]);
+    }
+    not_null(items, name)?;
+    Ok(unsafe { slice::from_raw_parts(items, count) })
+}
+
+/// Borrows a NUL-terminated UTF-8 string argument.
+pub(crate) fn as_str<'a>(pointer: *const c_char, name: &str) -> Result<&'a str, MbinaryError> {
+    not_null(pointer, name)?;
+    let c_str = unsafe { CStr::from_ptr(pointer) };
+    c_str.to_str().map_err(|e| {
+        set_error(
+            MbinaryError::InvalidArgument,
+            format!("`{}` is not valid UTF-8: {}", name, e),
+        )
+    })
+}
+
+/// Message describing the last failed call on this thread, or null when it succeeded.
+///
+/// The string is owned by the library and valid until the next call on this thread.
+#[no_mangle]
+pub extern "C" fn mbinary_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| match &*last.borrow() {
+        Some((_, message)) => message.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Code of the last failed call on this thread, `Success` when it succeeded.
+#[no_mangle]
+pub extern "C" fn mbinary_last_error_code() -> MbinaryError {
+    LAST_ERROR.with(|last| match &*last.borrow() {
+        Some((code, _)) => *code,
+        None => MbinaryError::Success,
+    })
+}
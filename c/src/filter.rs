@@ -0,0 +1,108 @@
+use mbinary::enums::RType;
+use mbinary::filter::RecordFilter;
+use mbinary::records::RecordHeader;
+use std::mem;
+
+use crate::error::{as_mut, as_ref, as_slice, boxed, set_error, status, value, MbinaryError};
+
+/// C-compatible wrapper around RecordFilter
+pub struct CRecordFilter {
+    pub(crate) filter: RecordFilter,
+}
+
+impl CRecordFilter {
+    fn update(&mut self, builder: impl FnOnce(RecordFilter) -> RecordFilter) {
+        self.filter = builder(mem::take(&mut self.filter));
+    }
+}
+
+/// Create a `CRecordFilter` matching every record.
+#[no_mangle]
+pub extern "C" fn create_record_filter() -> *mut CRecordFilter {
+    boxed(|| {
+        Ok(CRecordFilter {
+            filter: RecordFilter::new(),
+        })
+    })
+}
+
+/// Keep records for `instrument_ids`, in addition to any instruments already kept.
+#[no_mangle]
+pub extern "C" fn record_filter_instrument_ids(
+    filter: *mut CRecordFilter,
+    instrument_ids: *const u32,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let filter = as_mut(filter, "filter")?;
+        let instrument_ids = as_slice(instrument_ids, count, "instrument_ids")?;
+        filter.update(|f| f.instrument_ids(instrument_ids.iter().copied()));
+        Ok(())
+    })
+}
+
+/// Keep records of `rtypes`, in addition to any types already kept.
+///
+/// Nothing is added when any value is not an `RType`.
+#[no_mangle]
+pub extern "C" fn record_filter_rtypes(
+    filter: *mut CRecordFilter,
+    rtypes: *const u8,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let filter = as_mut(filter, "filter")?;
+        let rtypes = as_slice(rtypes, count, "rtypes")?
+            .iter()
+            .map(|&rtype| {
+                RType::try_from(rtype)
+                    .map_err(|e| set_error(MbinaryError::InvalidArgument, e.extract_message()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        filter.update(|f| f.rtypes(rtypes));
+        Ok(())
+    })
+}
+
+/// Keep records with a `ts_event` at or after `start`.
+#[no_mangle]
+pub extern "C" fn record_filter_start(filter: *mut CRecordFilter, start: u64) -> MbinaryError {
+    status(|| {
+        as_mut(filter, "filter")?.update(|f| f.start(start));
+        Ok(())
+    })
+}
+
+/// Keep records with a `ts_event` before `end`.
+#[no_mangle]
+pub extern "C" fn record_filter_end(filter: *mut CRecordFilter, end: u64) -> MbinaryError {
+    status(|| {
+        as_mut(filter, "filter")?.update(|f| f.end(end));
+        Ok(())
+    })
+}
+
+/// Whether a record with `header` passes the filter, e.g. for records from a
+/// `CBorrowedDecoder`.
+#[no_mangle]
+pub extern "C" fn record_filter_matches(
+    filter: *const CRecordFilter,
+    header: *const RecordHeader,
+) -> bool {
+    value(false, || {
+        let filter = as_ref(filter, "filter")?;
+        Ok(filter.filter.matches(as_ref(header, "header")?))
+    })
+}
+
+/// Destroy the `CRecordFilter`, null is ignored like `free`.
+#[no_mangle]
+pub extern "C" fn destroy_record_filter(filter: *mut CRecordFilter) {
+    if filter.is_null() {
+        return;
+    }
+
+    unsafe {
+        let _ = Box::from_raw(filter);
+    }
+}
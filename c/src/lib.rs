@@ -1,3 +1,12 @@
+// Pointer validity is the C caller's contract, null checks go through the `as_ref`, `as_mut`
+// and `as_slice` helpers in `error`.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub mod backtest;
 pub mod decode;
 pub mod encode;
+pub mod error;
+pub mod filter;
+pub mod live;
+pub mod metadata;
 pub mod records;
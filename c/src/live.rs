@@ -0,0 +1,257 @@
+use mbinary::live::{AccountSnapshot, AccountSummary, LiveData};
+use mbinary::live_decoder::LiveDecoder;
+use mbinary::live_encode::LiveEncoder;
+use std::cell::OnceCell;
+use std::os::raw::c_char;
+use std::ptr;
+
+use crate::backtest::{
+    decode_error, read_file, to_parameters, to_signals, to_trades, view_slice, write_file,
+    CParameters, CSignal, CTrade, SessionViews,
+};
+use crate::error::{as_mut, as_ref, as_slice, as_str, boxed, status, value, MbinaryError};
+use crate::metadata::copy_slice;
+
+/// C view of `AccountSummary`, the account at the start and end of the session.
+#[repr(C)]
+pub struct CAccountSummary {
+    pub currency: *const c_char,
+    pub start: AccountSnapshot,
+    pub end: AccountSnapshot,
+}
+
+/// C-compatible wrapper around LiveData
+pub struct CLiveData {
+    live: LiveData,
+    views: OnceCell<LiveViews>,
+}
+
+struct LiveViews {
+    session: SessionViews,
+    account: CAccountSummary,
+}
+
+impl CLiveData {
+    fn new(live: LiveData) -> Self {
+        CLiveData {
+            live,
+            views: OnceCell::new(),
+        }
+    }
+
+    fn views(&self) -> &LiveViews {
+        self.views.get_or_init(|| {
+            let live = &self.live;
+            let mut session = SessionViews::new(&live.parameters, &live.trades, &live.signals);
+            let summary = live.account_summary();
+            let account = CAccountSummary {
+                currency: session.string(&summary.currency),
+                start: summary.start(),
+                end: summary.end(),
+            };
+            LiveViews { session, account }
+        })
+    }
+
+    /// Mutable access to the session, dropping views that may point into it.
+    fn live_mut(&mut self) -> &mut LiveData {
+        self.views.take();
+        &mut self.live
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        LiveEncoder::new(&mut bytes).encode_live(&self.live);
+        bytes
+    }
+}
+
+/// Create a `CLiveData` session to add results to.
+///
+/// `live_id` of 65535 means the session has no id yet.
+#[no_mangle]
+pub extern "C" fn create_live_data(
+    live_id: u16,
+    parameters: *const CParameters,
+    account: *const CAccountSummary,
+) -> *mut CLiveData {
+    boxed(|| {
+        let parameters = to_parameters(as_ref(parameters, "parameters")?)?;
+        let account = as_ref(account, "account")?;
+        let currency = as_str(account.currency, "currency")?;
+
+        Ok(CLiveData::new(LiveData {
+            live_id: (live_id != u16::MAX).then_some(live_id),
+            parameters,
+            trades: Vec::new(),
+            signals: Vec::new(),
+            account: AccountSummary::from_snapshots(currency, &account.start, &account.end),
+            account_snapshots: Vec::new(),
+        }))
+    })
+}
+
+/// Add trades, nothing is added when any trade is invalid.
+#[no_mangle]
+pub extern "C" fn live_data_add_trades(
+    live: *mut CLiveData,
+    trades: *const CTrade,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let live = as_mut(live, "live")?;
+        let trades = to_trades(as_slice(trades, count, "trades")?)?;
+        live.live_mut().trades.extend(trades);
+        Ok(())
+    })
+}
+
+/// Add signals, nothing is added when any signal is invalid.
+#[no_mangle]
+pub extern "C" fn live_data_add_signals(
+    live: *mut CLiveData,
+    signals: *const CSignal,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let live = as_mut(live, "live")?;
+        let signals = to_signals(as_slice(signals, count, "signals")?)?;
+        live.live_mut().signals.extend(signals);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn live_data_add_account_snapshots(
+    live: *mut CLiveData,
+    snapshots: *const AccountSnapshot,
+    count: usize,
+) -> MbinaryError {
+    status(|| {
+        let live = as_mut(live, "live")?;
+        let snapshots = as_slice(snapshots, count, "snapshots")?;
+        let live = live.live_mut();
+        for snapshot in snapshots {
+            live.push_account_snapshot(snapshot.clone());
+        }
+        Ok(())
+    })
+}
+
+/// Copies the session, encoded as `LiveEncoder::encode_live` does, into `output`.
+///
+/// With a null `output` only the encoded size is written to `output_size`.
+#[no_mangle]
+pub extern "C" fn encode_live_data(
+    live: *const CLiveData,
+    output: *mut u8,
+    output_size: *mut usize,
+) -> MbinaryError {
+    status(|| {
+        let live = as_ref(live, "live")?;
+        copy_slice(&live.encode(), output, output_size)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn write_live_data_file(
+    live: *const CLiveData,
+    file_path: *const c_char,
+) -> MbinaryError {
+    status(|| {
+        let live = as_ref(live, "live")?;
+        write_file(file_path, &live.encode())
+    })
+}
+
+/// Decode a live session from an in-memory buffer, in any supported live format version.
+#[no_mangle]
+pub extern "C" fn decode_live_data(source: *const u8, source_size: usize) -> *mut CLiveData {
+    boxed(|| {
+        let source = as_slice(source, source_size, "source")?;
+        let live = LiveDecoder::new(source)
+            .decode_live()
+            .map_err(decode_error)?;
+        Ok(CLiveData::new(live))
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn read_live_data_file(file_path: *const c_char) -> *mut CLiveData {
+    boxed(|| {
+        let bytes = read_file(file_path)?;
+        let live = LiveDecoder::new(bytes.as_slice())
+            .decode_live()
+            .map_err(decode_error)?;
+        Ok(CLiveData::new(live))
+    })
+}
+
+/// Id of the session, 65535 when it has none.
+#[no_mangle]
+pub extern "C" fn live_data_get_id(live: *const CLiveData) -> u16 {
+    value(u16::MAX, || {
+        Ok(as_ref(live, "live")?.live.live_id.unwrap_or(u16::MAX))
+    })
+}
+
+// The accessors below return views owned by the session, valid until it is modified or
+// destroyed.
+
+#[no_mangle]
+pub extern "C" fn live_data_parameters(live: *const CLiveData) -> *const CParameters {
+    value(ptr::null(), || {
+        Ok(&as_ref(live, "live")?.views().session.parameters as *const CParameters)
+    })
+}
+
+/// Account at the start and end of the session, from the first and last snapshots when any
+/// were added.
+#[no_mangle]
+pub extern "C" fn live_data_account_summary(live: *const CLiveData) -> *const CAccountSummary {
+    value(ptr::null(), || {
+        Ok(&as_ref(live, "live")?.views().account as *const CAccountSummary)
+    })
+}
+
+/// Trades, with their number written to `count`.
+#[no_mangle]
+pub extern "C" fn live_data_trades(live: *const CLiveData, count: *mut usize) -> *const CTrade {
+    value(ptr::null(), || {
+        let live = as_ref(live, "live")?;
+        view_slice(&live.views().session.trades, count)
+    })
+}
+
+/// Signals, with their number written to `count`.
+#[no_mangle]
+pub extern "C" fn live_data_signals(live: *const CLiveData, count: *mut usize) -> *const CSignal {
+    value(ptr::null(), || {
+        let live = as_ref(live, "live")?;
+        view_slice(&live.views().session.signals, count)
+    })
+}
+
+/// Account snapshots, with their number written to `count`.
+#[no_mangle]
+pub extern "C" fn live_data_account_snapshots(
+    live: *const CLiveData,
+    count: *mut usize,
+) -> *const AccountSnapshot {
+    value(ptr::null(), || {
+        let live = as_ref(live, "live")?;
+        view_slice(&live.live.account_snapshots, count)
+    })
+}
+
+/// Destroy the `CLiveData`, null is ignored like `free`.
+#[no_mangle]
+pub extern "C" fn destroy_live_data(live: *mut CLiveData) {
+    if live.is_null() {
+        return;
+    }
+
+    unsafe {
+        let _ = Box::from_raw(live);
+    }
+}
@@ -0,0 +1,289 @@
+use mbinary::decode::MetadataDecoder;
+use mbinary::enums::{Dataset, Schema};
+use mbinary::metadata::Metadata;
+use mbinary::symbols::SymbolMap;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::os::raw::c_char;
+use std::slice;
+
+use crate::error::{
+    as_mut, as_ref, as_slice, as_str, boxed, not_null, set_error, status, value, MbinaryError,
+};
+
+/// C-compatible wrapper around Metadata
+pub struct CMetadata {
+    pub(crate) metadata: Metadata,
+}
+
+/// C-compatible wrapper around SymbolMap
+pub struct CSymbolMap {
+    mappings: SymbolMap,
+}
+
+/// Reads a metadata header from the start of `reader`, rewinding to where it started when
+/// the source holds records only.
+pub(crate) fn read_metadata<R: Read + Seek>(reader: &mut R) -> std::io::Result<Option<Metadata>> {
+    let start = reader.stream_position()?;
+
+    match MetadataDecoder::new(&mut *reader).decode() {
+        Ok(Some(metadata)) => Ok(Some(metadata)),
+        _ => {
+            reader.seek(SeekFrom::Start(start))?;
+            Ok(None)
+        }
+    }
+}
+
+/// Copies `value` into `output` as a NUL-terminated string.
+///
+/// With a null `output` only the required size, including the terminator, is written to
+/// `output_size`.
+pub(crate) fn copy_string(
+    value: &str,
+    output: *mut c_char,
+    output_size: *mut usize,
+) -> Result<(), MbinaryError> {
+    let output_size = as_mut(output_size, "output_size")?;
+    let required = value.len() + 1;
+
+    if !output.is_null() {
+        if *output_size < required {
+            return Err(set_error(
+                MbinaryError::BufferTooSmall,
+                format!("Output holds {} of {} bytes", output_size, required),
+            ));
+        }
+        let output_slice = unsafe { slice::from_raw_parts_mut(output as *mut u8, required) };
+        output_slice[..value.len()].copy_from_slice(value.as_bytes());
+        output_slice[value.len()] = 0;
+    }
+
+    *output_size = required;
+    Ok(())
+}
+
+/// Copies `values` into `output`.
+///
+/// With a null `output` only the number of values is written to `output_size`.
+pub(crate) fn copy_slice<T: Copy>(
+    values: &[T],
+    output: *mut T,
+    output_size: *mut usize,
+) -> Result<(), MbinaryError> {
+    let output_size = as_mut(output_size, "output_size")?;
+
+    if !output.is_null() {
+        if *output_size < values.len() {
+            return Err(set_error(
+                MbinaryError::BufferTooSmall,
+                format!("Output holds {} of {} values", output_size, values.len()),
+            ));
+        }
+        let output_slice = unsafe { slice::from_raw_parts_mut(output, values.len()) };
+        output_slice.copy_from_slice(values);
+    }
+
+    *output_size = values.len();
+    Ok(())
+}
+
+/// Create an empty `CSymbolMap`.
+#[no_mangle]
+pub extern "C" fn create_symbol_map() -> *mut CSymbolMap {
+    boxed(|| {
+        Ok(CSymbolMap {
+            mappings: SymbolMap::new(),
+        })
+    })
+}
+
+/// Map `instrument_id` to `ticker`, replacing any earlier ticker for the id.
+#[no_mangle]
+pub extern "C" fn symbol_map_add_instrument(
+    mappings: *mut CSymbolMap,
+    ticker: *const c_char,
+    instrument_id: u32,
+) -> MbinaryError {
+    status(|| {
+        let mappings = as_mut(mappings, "mappings")?;
+        let ticker = as_str(ticker, "ticker")?;
+        mappings.mappings.add_instrument(ticker, instrument_id);
+        Ok(())
+    })
+}
+
+/// Destroy the `CSymbolMap`, null is ignored like `free`.
+#[no_mangle]
+pub extern "C" fn destroy_symbol_map(mappings: *mut CSymbolMap) {
+    if mappings.is_null() {
+        return;
+    }
+
+    unsafe {
+        let _ = Box::from_raw(mappings);
+    }
+}
+
+/// Create a new `CMetadata`, copying `mappings` when not null.
+///
+/// `schema` and `dataset` are values of mbinary's `Schema` and `Dataset` enums, returns null
+/// when either is unknown.
+#[no_mangle]
+pub extern "C" fn create_metadata(
+    schema: u8,
+    dataset: u8,
+    start: u64,
+    end: u64,
+    mappings: *const CSymbolMap,
+) -> *mut CMetadata {
+    boxed(|| {
+        let schema = Schema::try_from(schema).map_err(|_| {
+            set_error(
+                MbinaryError::InvalidArgument,
+                format!("Unknown schema {}", schema),
+            )
+        })?;
+        let dataset = Dataset::try_from(dataset).map_err(|_| {
+            set_error(
+                MbinaryError::InvalidArgument,
+                format!("Unknown dataset {}", dataset),
+            )
+        })?;
+
+        let mappings = if mappings.is_null() {
+            SymbolMap::new()
+        } else {
+            unsafe { (*mappings).mappings.clone() }
+        };
+
+        let metadata = Metadata::new(schema, dataset, start, end, mappings);
+        Ok(CMetadata { metadata })
+    })
+}
+
+/// Decode the length-prefixed metadata header at the start of an in-memory buffer.
+#[no_mangle]
+pub extern "C" fn decode_metadata(source: *const u8, source_size: usize) -> *mut CMetadata {
+    boxed(|| {
+        not_null(source, "source")?;
+        let source_slice = unsafe { slice::from_raw_parts(source, source_size) };
+
+        match MetadataDecoder::new(source_slice).decode() {
+            Ok(Some(metadata)) => Ok(CMetadata { metadata }),
+            Ok(None) => Err(set_error(
+                MbinaryError::DecodeError,
+                "Source has no metadata header",
+            )),
+            Err(e) => Err(set_error(
+                MbinaryError::DecodeError,
+                format!("Failed to decode metadata: {}", e),
+            )),
+        }
+    })
+}
+
+/// Write the number of bytes the metadata header takes at the start of `source` to
+/// `header_size`, 0 when `source` holds records only.
+///
+/// The records follow the header unpadded, so `source + header_size` is where they must be
+/// 8-byte aligned for `create_borrowed_metadata_decoder`.
+#[no_mangle]
+pub extern "C" fn metadata_header_size(
+    source: *const u8,
+    source_size: usize,
+    header_size: *mut usize,
+) -> MbinaryError {
+    status(|| {
+        let source = as_slice(source, source_size, "source")?;
+        let header_size = as_mut(header_size, "header_size")?;
+
+        let mut cursor = Cursor::new(source);
+        read_metadata(&mut cursor).map_err(|e| {
+            set_error(
+                MbinaryError::DecodeError,
+                format!("Failed to decode metadata: {}", e),
+            )
+        })?;
+        *header_size = cursor.position() as usize;
+        Ok(())
+    })
+}
+
+/// Schema of the records, as the value of mbinary's `Schema` enum.
+#[no_mangle]
+pub extern "C" fn metadata_schema(metadata: *const CMetadata) -> u8 {
+    value(
+        0,
+        || Ok(as_ref(metadata, "metadata")?.metadata.schema as u8),
+    )
+}
+
+/// Dataset of the records, as the value of mbinary's `Dataset` enum.
+#[no_mangle]
+pub extern "C" fn metadata_dataset(metadata: *const CMetadata) -> u8 {
+    value(0, || {
+        Ok(as_ref(metadata, "metadata")?.metadata.dataset as u8)
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn metadata_start(metadata: *const CMetadata) -> u64 {
+    value(0, || Ok(as_ref(metadata, "metadata")?.metadata.start))
+}
+
+#[no_mangle]
+pub extern "C" fn metadata_end(metadata: *const CMetadata) -> u64 {
+    value(0, || Ok(as_ref(metadata, "metadata")?.metadata.end))
+}
+
+/// Copies the instrument ids in the symbol map into `output`, in ascending order.
+///
+/// With a null `output` only the number of ids is written to `output_size`.
+#[no_mangle]
+pub extern "C" fn metadata_instrument_ids(
+    metadata: *const CMetadata,
+    output: *mut u32,
+    output_size: *mut usize,
+) -> MbinaryError {
+    status(|| {
+        let metadata = as_ref(metadata, "metadata")?;
+        let mut ids: Vec<u32> = metadata.metadata.mappings.map.keys().copied().collect();
+        ids.sort_unstable();
+        copy_slice(&ids, output, output_size)
+    })
+}
+
+/// Copies the ticker mapped to `instrument_id` into `output` as a NUL-terminated string.
+///
+/// With a null `output` only the required size is written to `output_size`. Returns
+/// `NotFound` when the instrument is not in the symbol map.
+#[no_mangle]
+pub extern "C" fn metadata_get_ticker(
+    metadata: *const CMetadata,
+    instrument_id: u32,
+    output: *mut c_char,
+    output_size: *mut usize,
+) -> MbinaryError {
+    status(|| {
+        let metadata = as_ref(metadata, "metadata")?;
+        match metadata.metadata.mappings.map.get(&instrument_id) {
+            Some(ticker) => copy_string(ticker, output, output_size),
+            None => Err(set_error(
+                MbinaryError::NotFound,
+                format!("Instrument {} is not in the symbol map", instrument_id),
+            )),
+        }
+    })
+}
+
+/// Destroy the `CMetadata`, null is ignored like `free`.
+#[no_mangle]
+pub extern "C" fn destroy_metadata(metadata: *mut CMetadata) {
+    if metadata.is_null() {
+        return;
+    }
+
+    unsafe {
+        let _ = Box::from_raw(metadata);
+    }
+}
@@ -2,6 +2,9 @@ use mbinary::enums::RType;
 use mbinary::record_enum::RecordEnum;
 use mbinary::record_ref::RecordRef;
 use mbinary::records::{BboMsg, Mbp1Msg, OhlcvMsg, Record, RecordHeader, TradeMsg};
+use std::ptr;
+
+use crate::error::{as_ref, value};
 
 // Gives polymorphic behaviour to the CRecordEnum.data field.
 #[repr(C)]
@@ -68,52 +71,68 @@ impl From<RecordEnum> for CRecordEnum {
 
 #[no_mangle]
 pub extern "C" fn output(record: *const CRecordEnum) {
-    unsafe {
-        match &(*record).rtype {
-            RType::Mbp1 => println!("{:?}", (*record).data.mbp1),
-            RType::Tbbo => println!("{:?}", (*record).data.tbbo),
-            RType::Trades => println!("{:?}", (*record).data.trade),
-            RType::Ohlcv => println!("{:?}", (*record).data.ohlcv),
-            RType::Bbo => println!("{:?}", (*record).data.bbo),
+    value((), || {
+        let record = as_ref(record, "record")?;
+        unsafe {
+            match &record.rtype {
+                RType::Mbp1 => println!("{:?}", record.data.mbp1),
+                RType::Tbbo => println!("{:?}", record.data.tbbo),
+                RType::Trades => println!("{:?}", record.data.trade),
+                RType::Ohlcv => println!("{:?}", record.data.ohlcv),
+                RType::Bbo => println!("{:?}", record.data.bbo),
+            }
         }
-    }
+        Ok(())
+    });
 }
 
+/// Header of the record, or null when `record` is null.
 #[no_mangle]
 pub extern "C" fn get_header(record: *const CRecordEnum) -> *const RecordHeader {
-    unsafe {
-        match &(*record).rtype {
-            RType::Mbp1 => return (*record).data.mbp1.header(),
-            RType::Tbbo => return (*record).data.tbbo.header(),
-            RType::Trades => return (*record).data.trade.header(),
-            RType::Ohlcv => return (*record).data.ohlcv.header(),
-            RType::Bbo => return (*record).data.bbo.header(),
+    value(ptr::null(), || {
+        let record = as_ref(record, "record")?;
+        unsafe {
+            Ok(match &record.rtype {
+                RType::Mbp1 => record.data.mbp1.header(),
+                RType::Tbbo => record.data.tbbo.header(),
+                RType::Trades => record.data.trade.header(),
+                RType::Ohlcv => record.data.ohlcv.header(),
+                RType::Bbo => record.data.bbo.header(),
+            } as *const RecordHeader)
         }
-    }
+    })
 }
 
+/// Timestamp of the record, or 0 when `record` is null.
 #[no_mangle]
 pub extern "C" fn get_timestamp(record: *const CRecordEnum) -> u64 {
-    unsafe {
-        match &(*record).rtype {
-            RType::Mbp1 => return (*record).data.mbp1.timestamp(),
-            RType::Tbbo => return (*record).data.tbbo.timestamp(),
-            RType::Trades => return (*record).data.trade.timestamp(),
-            RType::Ohlcv => return (*record).data.ohlcv.timestamp(),
-            RType::Bbo => return (*record).data.bbo.timestamp(),
+    value(0, || {
+        let record = as_ref(record, "record")?;
+        unsafe {
+            Ok(match &record.rtype {
+                RType::Mbp1 => record.data.mbp1.timestamp(),
+                RType::Tbbo => record.data.tbbo.timestamp(),
+                RType::Trades => record.data.trade.timestamp(),
+                RType::Ohlcv => record.data.ohlcv.timestamp(),
+                RType::Bbo => record.data.bbo.timestamp(),
+            })
         }
-    }
+    })
 }
 
+/// Price of the record, or 0 when `record` is null.
 #[no_mangle]
 pub extern "C" fn get_price(record: *const CRecordEnum) -> i64 {
-    unsafe {
-        match &(*record).rtype {
-            RType::Mbp1 => return (*record).data.mbp1.price(),
-            RType::Tbbo => return (*record).data.tbbo.price(),
-            RType::Trades => return (*record).data.trade.price(),
-            RType::Ohlcv => return (*record).data.ohlcv.price(),
-            RType::Bbo => return (*record).data.bbo.price(),
+    value(0, || {
+        let record = as_ref(record, "record")?;
+        unsafe {
+            Ok(match &record.rtype {
+                RType::Mbp1 => record.data.mbp1.price(),
+                RType::Tbbo => record.data.tbbo.price(),
+                RType::Trades => record.data.trade.price(),
+                RType::Ohlcv => record.data.ohlcv.price(),
+                RType::Bbo => record.data.bbo.price(),
+            })
         }
-    }
+    })
 }
@@ -0,0 +1,239 @@
+#include <gtest/gtest.h>
+
+#include <cstdint>
+#include <cstring>
+#include <string>
+#include <vector>
+
+#include "mbinary.h"
+
+namespace {
+
+const char* TICKERS[] = {"HE.n.0", "AAPL"};
+
+CParameters test_parameters() {
+  return CParameters{
+      .strategy_name = "Testing",
+      .capital = 10000,
+      .schema = "Ohlcv-1s",
+      .data_type = "BAR",
+      .start = 1730160814000000000,
+      .end = 1730160814000000000,
+      .tickers = TICKERS,
+      .ticker_count = 2,
+  };
+}
+
+CTrade test_trade() {
+  return CTrade{
+      .trade_id = 1,
+      .signal_id = 1,
+      .timestamp = 1704903000,
+      .ticker = "AAPL",
+      .quantity = 4000000000,
+      .avg_price = 13074000000000,
+      .trade_value = -52296000000000,
+      .trade_cost = -52296000000000,
+      .action = TradeAction::Buy,
+      .fees = 100,
+  };
+}
+
+CSignalInstruction test_instruction() {
+  return CSignalInstruction{
+      .ticker = "AAPL",
+      .order_type = OrderType::Limit,
+      .action = TradeAction::Buy,
+      .signal_id = 1,
+      .weight = 13213432,
+      .quantity = 4,
+      .limit_price = 13074000000000,
+      .aux_price = MBINARY_UNDEF_PRICE,
+  };
+}
+
+AccountSnapshot test_snapshot(int64_t timestamp, int64_t net_liquidation) {
+  return AccountSnapshot{
+      .timestamp = timestamp,
+      .buying_power = net_liquidation,
+      .excess_liquidity = net_liquidation,
+      .full_available_funds = net_liquidation,
+      .full_init_margin_req = 0,
+      .full_maint_margin_req = 0,
+      .futures_pnl = 0,
+      .net_liquidation = net_liquidation,
+      .total_cash_balance = net_liquidation,
+      .unrealized_pnl = 0,
+  };
+}
+
+std::vector<uint8_t> encoded_backtest(const CBacktest* backtest) {
+  size_t size = 0;
+  EXPECT_EQ(encode_backtest(backtest, nullptr, &size), Success);
+  std::vector<uint8_t> bytes(size);
+  EXPECT_EQ(encode_backtest(backtest, bytes.data(), &size), Success);
+  return bytes;
+}
+
+}  // namespace
+
+TEST(BacktestTests, test_backtest_round_trip) {
+  CParameters parameters = test_parameters();
+  StaticStats stats{};
+  stats.total_trades = 1;
+  stats.sharpe_ratio = 1500000000;
+
+  CBacktest* backtest = create_backtest(7, "cpp_engine", &parameters, nullptr);
+  ASSERT_NE(backtest, nullptr) << mbinary_last_error();
+
+  std::vector<TimeseriesStats> period = {{1704903000, 10000, 0, 0, 0},
+                                         {1704903060, 10100, 0, 10000000, 10000000}};
+  CTrade trade = test_trade();
+  CSignalInstruction instruction = test_instruction();
+  CSignal signal{.timestamp = 1704903000, .instructions = &instruction, .instruction_count = 1};
+
+  EXPECT_EQ(backtest_set_static_stats(backtest, &stats), Success);
+  EXPECT_EQ(backtest_add_period_timeseries(backtest, period.data(), period.size()), Success);
+  EXPECT_EQ(backtest_add_daily_timeseries(backtest, period.data(), 1), Success);
+  EXPECT_EQ(backtest_add_trades(backtest, &trade, 1), Success);
+  EXPECT_EQ(backtest_add_signals(backtest, &signal, 1), Success);
+
+  // Test
+  std::vector<uint8_t> bytes = encoded_backtest(backtest);
+  CBacktest* decoded = decode_backtest(bytes.data(), bytes.size());
+  ASSERT_NE(decoded, nullptr) << mbinary_last_error();
+
+  // Validate
+  EXPECT_EQ(backtest_get_id(decoded), 7);
+  EXPECT_STREQ(backtest_name(decoded), "cpp_engine");
+
+  const CParameters* decoded_parameters = backtest_parameters(decoded);
+  EXPECT_STREQ(decoded_parameters->strategy_name, "Testing");
+  EXPECT_EQ(decoded_parameters->capital, 10000);
+  ASSERT_EQ(decoded_parameters->ticker_count, 2);
+  EXPECT_STREQ(decoded_parameters->tickers[1], "AAPL");
+
+  EXPECT_EQ(backtest_static_stats(decoded)->sharpe_ratio, 1500000000);
+
+  size_t count = 0;
+  const TimeseriesStats* decoded_period = backtest_period_timeseries(decoded, &count);
+  ASSERT_EQ(count, 2);
+  EXPECT_EQ(decoded_period[1].equity_value, 10100);
+  backtest_daily_timeseries(decoded, &count);
+  EXPECT_EQ(count, 1);
+
+  const CTrade* trades = backtest_trades(decoded, &count);
+  ASSERT_EQ(count, 1);
+  EXPECT_STREQ(trades[0].ticker, "AAPL");
+  EXPECT_EQ(trades[0].action, TradeAction::Buy);
+  EXPECT_EQ(trades[0].avg_price, 13074000000000);
+
+  const CSignal* signals = backtest_signals(decoded, &count);
+  ASSERT_EQ(count, 1);
+  ASSERT_EQ(signals[0].instruction_count, 1);
+  EXPECT_EQ(signals[0].instructions[0].order_type, OrderType::Limit);
+  EXPECT_EQ(signals[0].instructions[0].limit_price, 13074000000000);
+  EXPECT_EQ(signals[0].instructions[0].aux_price, MBINARY_UNDEF_PRICE);
+
+  destroy_backtest(decoded);
+  destroy_backtest(backtest);
+}
+
+TEST(BacktestTests, test_backtest_file) {
+  CParameters parameters = test_parameters();
+  CBacktest* backtest = create_backtest(UINT16_MAX, "file", &parameters, nullptr);
+  CTrade trade = test_trade();
+  ASSERT_EQ(backtest_add_trades(backtest, &trade, 1), Success);
+
+  // Test
+  ASSERT_EQ(write_backtest_file(backtest, "../tests/test_backtest.bin"), Success);
+  CBacktest* decoded = read_backtest_file("../tests/test_backtest.bin");
+
+  // Validate
+  ASSERT_NE(decoded, nullptr) << mbinary_last_error();
+  size_t count = 0;
+  backtest_trades(decoded, &count);
+  EXPECT_EQ(count, 1);
+  EXPECT_EQ(encoded_backtest(decoded), encoded_backtest(backtest));
+
+  destroy_backtest(decoded);
+  destroy_backtest(backtest);
+}
+
+TEST(BacktestTests, test_backtest_invalid) {
+  CParameters parameters = test_parameters();
+  CBacktest* backtest = create_backtest(1, "invalid", &parameters, nullptr);
+  CTrade trades[] = {test_trade(), test_trade()};
+  trades[1].action = 99;
+
+  // Test
+  MbinaryError result = backtest_add_trades(backtest, trades, 2);
+
+  // Validate
+  EXPECT_EQ(result, InvalidArgument);
+  EXPECT_NE(std::string(mbinary_last_error()).find("TradeAction"), std::string::npos);
+  size_t count = 0;
+  backtest_trades(backtest, &count);
+  EXPECT_EQ(count, 0);
+
+  uint8_t garbage[] = {'M', 'B', 'B', 'T', 99};
+  EXPECT_EQ(decode_backtest(garbage, sizeof(garbage)), nullptr);
+  EXPECT_EQ(mbinary_last_error_code(), DecodeError);
+
+  destroy_backtest(backtest);
+}
+
+TEST(LiveDataTests, test_live_round_trip) {
+  CParameters parameters = test_parameters();
+  CAccountSummary account{
+      .currency = "USD",
+      .start = test_snapshot(1704903000, 100000),
+      .end = test_snapshot(1704904000, 100000),
+  };
+
+  CLiveData* live = create_live_data(UINT16_MAX, &parameters, &account);
+  ASSERT_NE(live, nullptr) << mbinary_last_error();
+
+  CTrade trade = test_trade();
+  CSignalInstruction instruction = test_instruction();
+  CSignal signal{.timestamp = 1704903000, .instructions = &instruction, .instruction_count = 1};
+  std::vector<AccountSnapshot> snapshots = {test_snapshot(1704903000, 100000),
+                                            test_snapshot(1704903500, 100500),
+                                            test_snapshot(1704904000, 101000)};
+
+  EXPECT_EQ(live_data_add_trades(live, &trade, 1), Success);
+  EXPECT_EQ(live_data_add_signals(live, &signal, 1), Success);
+  EXPECT_EQ(live_data_add_account_snapshots(live, snapshots.data(), snapshots.size()), Success);
+
+  // Test
+  size_t size = 0;
+  ASSERT_EQ(encode_live_data(live, nullptr, &size), Success);
+  std::vector<uint8_t> bytes(size);
+  ASSERT_EQ(encode_live_data(live, bytes.data(), &size), Success);
+  CLiveData* decoded = decode_live_data(bytes.data(), bytes.size());
+  ASSERT_NE(decoded, nullptr) << mbinary_last_error();
+
+  // Validate
+  EXPECT_EQ(bytes[0], LIVE_FORMAT_VERSION);
+  EXPECT_EQ(live_data_get_id(decoded), UINT16_MAX);
+  EXPECT_STREQ(live_data_parameters(decoded)->schema, "Ohlcv-1s");
+
+  size_t count = 0;
+  const CTrade* trades = live_data_trades(decoded, &count);
+  ASSERT_EQ(count, 1);
+  EXPECT_EQ(trades[0].quantity, 4000000000);
+  live_data_signals(decoded, &count);
+  EXPECT_EQ(count, 1);
+
+  const AccountSnapshot* decoded_snapshots = live_data_account_snapshots(decoded, &count);
+  ASSERT_EQ(count, 3);
+  EXPECT_EQ(decoded_snapshots[1].net_liquidation, 100500);
+
+  const CAccountSummary* summary = live_data_account_summary(decoded);
+  EXPECT_STREQ(summary->currency, "USD");
+  EXPECT_EQ(summary->start.net_liquidation, 100000);
+  EXPECT_EQ(summary->end.net_liquidation, 101000);
+
+  destroy_live_data(decoded);
+  destroy_live_data(live);
+}
@@ -0,0 +1,211 @@
+#include <gtest/gtest.h>
+
+#include <cstdint>
+#include <cstring>
+#include <vector>
+
+#include "mbinary.h"
+#include "mbinary.hpp"
+
+namespace {
+
+RecordData mbp1_data(uint32_t instrument_id, int64_t price) {
+  uint8_t length = sizeof(Mbp1Msg) / METADATA_LENGTH_MULTIPLIER;
+  return RecordData{.mbp1 = {length, RType::Mbp1, instrument_id, 1622471124, 0, price,
+                             10, 'T', 'A', 0, 0, 123456789098765,
+                             12345, 123456, 0, 1, 2, 2,
+                             2, 1, 3}};
+}
+
+RecordData ohlcv_data(uint32_t instrument_id) {
+  uint8_t length = sizeof(OhlcvMsg) / METADATA_LENGTH_MULTIPLIER;
+  return RecordData{.ohlcv = {length, RType::Ohlcv, instrument_id, 1622471124, 0, 100, 200,
+                              50, 150, 1000}};
+}
+
+// Encoded records copied into 8-byte aligned storage, as borrowed decoding requires.
+class AlignedBuffer {
+ public:
+  explicit AlignedBuffer(const std::vector<uint8_t>& bytes, size_t shift = 0)
+      : words_((bytes.size() + shift + 7) / 8), size_(bytes.size()), shift_(shift) {
+    std::memcpy(data(), bytes.data(), bytes.size());
+  }
+
+  uint8_t* data() { return reinterpret_cast<uint8_t*>(words_.data()) + shift_; }
+  size_t size() const { return size_; }
+
+ private:
+  std::vector<uint64_t> words_;
+  size_t size_;
+  size_t shift_;
+};
+
+std::vector<uint8_t> encoded(const std::vector<RecordData>& records) {
+  mbinary::RecordEncoder encoder;
+  encoder.append(records);
+  return encoder.data();
+}
+
+}  // namespace
+
+TEST(BorrowedDecoderTests, test_records_in_place) {
+  AlignedBuffer buffer(encoded({mbp1_data(1, 1000), ohlcv_data(2), mbp1_data(3, 1002)}));
+
+  // Test
+  CBorrowedDecoder* decoder = create_borrowed_decoder(buffer.data(), buffer.size());
+  ASSERT_NE(decoder, nullptr) << mbinary_last_error();
+
+  std::vector<const RecordHeader*> records;
+  const RecordHeader* header = nullptr;
+  while (borrowed_decoder_next(decoder, &header)) {
+    records.push_back(header);
+  }
+
+  // Validate
+  EXPECT_EQ(mbinary_last_error_code(), Success);
+  ASSERT_EQ(records.size(), 3);
+  EXPECT_EQ(reinterpret_cast<const uint8_t*>(records[0]), buffer.data());
+  EXPECT_EQ(reinterpret_cast<const uint8_t*>(records[1]), buffer.data() + sizeof(Mbp1Msg));
+  EXPECT_EQ(records[1]->rtype, RType::Ohlcv);
+  EXPECT_EQ(reinterpret_cast<const OhlcvMsg*>(records[1])->volume, 1000);
+  EXPECT_EQ(reinterpret_cast<const Mbp1Msg*>(records[2])->price, 1002);
+  EXPECT_EQ(borrowed_decoder_offset(decoder), buffer.size());
+
+  destroy_borrowed_decoder(decoder);
+}
+
+TEST(BorrowedDecoderTests, test_fill_batches) {
+  std::vector<RecordData> data;
+  for (uint32_t i = 0; i < 5; i++) {
+    data.push_back(mbp1_data(i, 1000 + i));
+  }
+  AlignedBuffer buffer(encoded(data));
+  CBorrowedDecoder* decoder = create_borrowed_decoder(buffer.data(), buffer.size());
+
+  // Test
+  const RecordHeader* batch[2];
+  std::vector<size_t> counts;
+  std::vector<int64_t> prices;
+  size_t count = 0;
+  do {
+    ASSERT_EQ(borrowed_decoder_fill(decoder, batch, 2, &count), Success);
+    counts.push_back(count);
+    for (size_t i = 0; i < count; i++) {
+      prices.push_back(reinterpret_cast<const Mbp1Msg*>(batch[i])->price);
+    }
+  } while (count > 0);
+
+  // Validate
+  EXPECT_EQ(counts, (std::vector<size_t>{2, 2, 1, 0}));
+  EXPECT_EQ(prices, (std::vector<int64_t>{1000, 1001, 1002, 1003, 1004}));
+
+  destroy_borrowed_decoder(decoder);
+}
+
+TEST(BorrowedDecoderTests, test_invalid_source) {
+  std::vector<uint8_t> bytes = encoded({mbp1_data(1, 1000), mbp1_data(2, 1001)});
+
+  // Test
+  AlignedBuffer misaligned(bytes, 1);
+  EXPECT_EQ(create_borrowed_decoder(misaligned.data(), misaligned.size()), nullptr);
+  EXPECT_EQ(mbinary_last_error_code(), InvalidArgument);
+
+  AlignedBuffer truncated(bytes);
+  CBorrowedDecoder* decoder = create_borrowed_decoder(truncated.data(), truncated.size() - 4);
+  const RecordHeader* batch[4];
+  size_t count = 0;
+  MbinaryError result = borrowed_decoder_fill(decoder, batch, 4, &count);
+
+  // Validate
+  EXPECT_EQ(result, DecodeError);
+  EXPECT_NE(std::string(mbinary_last_error()).find("offset"), std::string::npos);
+  EXPECT_EQ(count, 1);
+  EXPECT_EQ(borrowed_decoder_offset(decoder), sizeof(Mbp1Msg));
+
+  destroy_borrowed_decoder(decoder);
+}
+
+TEST(BorrowedDecoderTests, test_decoder_fill) {
+  std::vector<uint8_t> bytes = encoded({mbp1_data(1, 1000), ohlcv_data(2), mbp1_data(3, 1002)});
+  CRecordDecoder* decoder = create_buffer_decoder(bytes.data(), bytes.size());
+
+  // Test
+  CRecordEnum batch[2];
+  size_t first = 0;
+  size_t second = 0;
+  size_t last = 0;
+  ASSERT_EQ(decoder_fill(decoder, batch, 2, &first), Success);
+  EXPECT_EQ(batch[1].rtype, RType::Ohlcv);
+  ASSERT_EQ(decoder_fill(decoder, batch, 2, &second), Success);
+  EXPECT_EQ(batch[0].data.mbp1.price, 1002);
+  ASSERT_EQ(decoder_fill(decoder, batch, 2, &last), Success);
+
+  // Validate
+  EXPECT_EQ(first, 2);
+  EXPECT_EQ(second, 1);
+  EXPECT_EQ(last, 0);
+
+  destroy_record_decoder(decoder);
+}
+
+TEST(BorrowedDecoderTests, test_metadata_header_size) {
+  mbinary::SymbolMap mappings;
+  mappings.add("AAPL", 1).add("TSLA", 2);
+  mbinary::RecordEncoder encoder;
+  encoder.encode_metadata(mbinary::Metadata(2, 2, 1, 2, mappings)).append({mbp1_data(1, 1000)});
+  std::vector<uint8_t> bytes = encoder.data();
+  std::vector<uint8_t> records = encoded({mbp1_data(1, 1000)});
+
+  // Test
+  size_t header_size = 0;
+  ASSERT_EQ(metadata_header_size(bytes.data(), bytes.size(), &header_size), Success);
+  size_t records_header_size = 1;
+  ASSERT_EQ(metadata_header_size(records.data(), records.size(), &records_header_size), Success);
+
+  // Validate
+  EXPECT_EQ(header_size, bytes.size() - sizeof(Mbp1Msg));
+  EXPECT_EQ(records_header_size, 0);
+  EXPECT_EQ(metadata_header_size(bytes.data(), bytes.size(), nullptr), NullPointer);
+}
+
+TEST(BorrowedDecoderTests, test_wrapper) {
+  mbinary::SymbolMap mappings;
+  mappings.add("AAPL", 1);
+  mbinary::RecordEncoder encoder;
+  encoder.encode_metadata(mbinary::Metadata(2, 2, 1, 2, mappings))
+      .append({mbp1_data(1, 1000), ohlcv_data(1)});
+  std::vector<uint8_t> bytes = encoder.data();
+  size_t header_size = mbinary::Metadata::header_size(bytes.data(), bytes.size());
+
+  // Test
+  AlignedBuffer unpadded(bytes);
+  if (header_size % 8 != 0) {
+    try {
+      mbinary::BorrowedDecoder::from_buffer_with_metadata(unpadded.data(), unpadded.size());
+      FAIL() << "Expected mbinary::Error";
+    } catch (const mbinary::Error& e) {
+      EXPECT_EQ(e.code(), InvalidArgument);
+    }
+  }
+
+  // Shifted so the records after the header are aligned
+  AlignedBuffer buffer(bytes, (8 - header_size % 8) % 8);
+  mbinary::BorrowedDecoder decoder =
+      mbinary::BorrowedDecoder::from_buffer_with_metadata(buffer.data(), buffer.size());
+
+  // Validate
+  ASSERT_TRUE(decoder.metadata().has_value());
+  EXPECT_EQ(decoder.metadata()->ticker(1), std::optional<std::string>("AAPL"));
+  EXPECT_EQ(decoder.offset(), header_size);
+
+  std::vector<mbinary::RecordView> views;
+  for (const mbinary::RecordView& view : decoder) {
+    views.push_back(view);
+  }
+  ASSERT_EQ(views.size(), 2);
+  ASSERT_NE(views[0].mbp1(), nullptr);
+  EXPECT_EQ(views[0].mbp1()->price, 1000);
+  EXPECT_EQ(views[0].ohlcv(), nullptr);
+  EXPECT_EQ(views[1].rtype(), Ohlcv);
+  EXPECT_EQ(views[1].ohlcv()->close, 150);
+}
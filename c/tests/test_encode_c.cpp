@@ -1,5 +1,8 @@
 #include <gtest/gtest.h>
 
+#include <fcntl.h>
+#include <unistd.h>
+
 #include <cstdint>
 #include <cstring>
 #include <vector>
@@ -84,3 +87,135 @@ TEST(EncoderTests, test_encode_to_file) {
   // Destroy the encoder
   destroy_record_encoder(encoder);
 }
+
+namespace {
+
+RecordData mbp1_record(uint32_t instrument_id, int64_t price) {
+  uint8_t length = sizeof(Mbp1Msg) / METADATA_LENGTH_MULTIPLIER;
+  CRecordEnum record{
+      .rtype = RType::Mbp1,
+      .data = RecordData{
+          .mbp1 = {length, RType::Mbp1, instrument_id, 1622471124, 0, price,
+                   10,     1,           1,             0,          0, 123456789098765,
+                   12345,  123456,      0,             1,          2, 2,
+                   2,      1,           3}}};
+  return record.data;
+}
+
+CMetadata* test_metadata() {
+  CSymbolMap* mappings = create_symbol_map();
+  symbol_map_add_instrument(mappings, "AAPL", 1);
+  symbol_map_add_instrument(mappings, "TSLA", 2);
+
+  // Schema::Mbp1, Dataset::Equities
+  CMetadata* metadata = create_metadata(1, 2, 1622471124, 1622471999, mappings);
+  destroy_symbol_map(mappings);
+  return metadata;
+}
+
+size_t count_records(CRecordDecoder* decoder) {
+  CRecordEnum record;
+  size_t count = 0;
+  while (decoder_iter(decoder, &record)) {
+    ++count;
+  }
+  return count;
+}
+
+}  // namespace
+
+TEST(EncoderTests, test_create_metadata) {
+  // Test
+  CMetadata* metadata = test_metadata();
+  ASSERT_NE(metadata, nullptr) << "Failed to create CMetadata";
+
+  // Validate
+  EXPECT_EQ(metadata_schema(metadata), 1);
+  EXPECT_EQ(metadata_dataset(metadata), 2);
+  size_t ticker_size = 0;
+  EXPECT_EQ(metadata_get_ticker(metadata, 2, nullptr, &ticker_size), 0);
+  EXPECT_EQ(ticker_size, 5);
+  EXPECT_EQ(create_metadata(0, 2, 0, 0, nullptr), nullptr);
+
+  destroy_metadata(metadata);
+}
+
+TEST(EncoderTests, test_append_with_metadata) {
+  CMetadata* metadata = test_metadata();
+  std::vector<RecordData> first = {mbp1_record(1, 1000)};
+  std::vector<RecordData> second = {mbp1_record(2, 2000), mbp1_record(1, 1001)};
+
+  // Test
+  CRecordEncoder* encoder = create_record_encoder();
+  ASSERT_EQ(encode_metadata(encoder, metadata), 0);
+  ASSERT_EQ(append_records(encoder, first.data(), first.size()), 0);
+  ASSERT_EQ(append_records(encoder, second.data(), second.size()), 0);
+  ASSERT_EQ(flush_encoder(encoder), 0);
+
+  size_t encoded_size = 0;
+  get_encoded_data(encoder, nullptr, &encoded_size);
+  std::vector<uint8_t> encoded_data(encoded_size);
+  get_encoded_data(encoder, encoded_data.data(), &encoded_size);
+  destroy_record_encoder(encoder);
+
+  // Validate
+  CRecordDecoder* decoder =
+      create_metadata_buffer_decoder(encoded_data.data(), encoded_size);
+  ASSERT_NE(decoder, nullptr);
+  const CMetadata* decoded = decoder_get_metadata(decoder);
+  ASSERT_NE(decoded, nullptr);
+  EXPECT_EQ(metadata_start(decoded), 1622471124);
+  EXPECT_EQ(count_records(decoder), 3);
+
+  destroy_record_decoder(decoder);
+  destroy_metadata(metadata);
+}
+
+TEST(EncoderTests, test_stream_to_path) {
+  CMetadata* metadata = test_metadata();
+  std::vector<RecordData> records = {mbp1_record(1, 1000), mbp1_record(2, 2000)};
+  const char* file = "../tests/test_stream_path.bin";
+
+  // Test
+  CRecordEncoder* encoder = create_file_encoder(file, false);
+  ASSERT_NE(encoder, nullptr) << "Failed to open file";
+  ASSERT_EQ(encode_metadata(encoder, metadata), 0);
+  ASSERT_EQ(append_records(encoder, records.data(), records.size()), 0);
+  ASSERT_EQ(append_records(encoder, records.data(), records.size()), 0);
+  ASSERT_EQ(flush_encoder(encoder), 0);
+  destroy_record_encoder(encoder);
+
+  // Validate
+  CRecordDecoder* decoder = create_metadata_file_decoder(file);
+  ASSERT_NE(decoder, nullptr);
+  ASSERT_NE(decoder_get_metadata(decoder), nullptr);
+  EXPECT_EQ(count_records(decoder), 4);
+
+  destroy_record_decoder(decoder);
+  destroy_metadata(metadata);
+}
+
+TEST(EncoderTests, test_stream_to_fd) {
+  CMetadata* metadata = test_metadata();
+  std::vector<RecordData> records = {mbp1_record(1, 1000)};
+  const char* file = "../tests/test_stream_fd.bin";
+  int fd = open(file, O_WRONLY | O_CREAT | O_TRUNC, 0644);
+  ASSERT_GT(fd, 0) << "Failed to open file";
+
+  // Test
+  CRecordEncoder* encoder = create_fd_encoder(fd);
+  ASSERT_NE(encoder, nullptr);
+  ASSERT_EQ(encode_metadata(encoder, metadata), 0);
+  ASSERT_EQ(append_records(encoder, records.data(), records.size()), 0);
+  destroy_record_encoder(encoder);
+  close(fd);
+
+  // Validate
+  CRecordDecoder* decoder = create_metadata_file_decoder(file);
+  ASSERT_NE(decoder, nullptr);
+  ASSERT_NE(decoder_get_metadata(decoder), nullptr);
+  EXPECT_EQ(count_records(decoder), 1);
+
+  destroy_record_decoder(decoder);
+  destroy_metadata(metadata);
+}
@@ -0,0 +1,57 @@
+#include <gtest/gtest.h>
+
+#include <cstdint>
+#include <string>
+
+#include "mbinary.h"
+
+TEST(ErrorTests, test_missing_file) {
+  // Test
+  CRecordDecoder* decoder = create_file_decoder("../tests/missing_file.bin");
+
+  // Validate
+  EXPECT_EQ(decoder, nullptr);
+  EXPECT_EQ(mbinary_last_error_code(), IoError);
+  ASSERT_NE(mbinary_last_error(), nullptr);
+  std::string message = mbinary_last_error();
+  EXPECT_NE(message.find("missing_file.bin"), std::string::npos) << message;
+}
+
+TEST(ErrorTests, test_null_argument) {
+  // Test
+  MbinaryError result = encode_records(nullptr, nullptr, 0);
+
+  // Validate
+  EXPECT_EQ(result, NullPointer);
+  EXPECT_EQ(mbinary_last_error_code(), NullPointer);
+  ASSERT_NE(mbinary_last_error(), nullptr);
+  EXPECT_NE(std::string(mbinary_last_error()).find("encoder"), std::string::npos);
+}
+
+TEST(ErrorTests, test_success_clears_error) {
+  create_file_decoder(nullptr);
+  ASSERT_EQ(mbinary_last_error_code(), NullPointer);
+
+  // Test
+  CRecordEncoder* encoder = create_record_encoder();
+
+  // Validate
+  EXPECT_EQ(mbinary_last_error_code(), Success);
+  EXPECT_EQ(mbinary_last_error(), nullptr);
+
+  size_t size = 0;
+  EXPECT_EQ(get_encoded_data(encoder, nullptr, &size), Success);
+  EXPECT_EQ(size, 0);
+
+  destroy_record_encoder(encoder);
+}
+
+TEST(ErrorTests, test_invalid_metadata) {
+  // Test
+  CMetadata* metadata = create_metadata(255, 0, 0, 0, nullptr);
+
+  // Validate
+  EXPECT_EQ(metadata, nullptr);
+  EXPECT_EQ(mbinary_last_error_code(), InvalidArgument);
+  EXPECT_NE(std::string(mbinary_last_error()).find("schema"), std::string::npos);
+}
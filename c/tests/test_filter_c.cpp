@@ -0,0 +1,116 @@
+#include <gtest/gtest.h>
+
+#include <cstdint>
+#include <string>
+#include <vector>
+
+#include "mbinary.h"
+#include "mbinary.hpp"
+
+namespace {
+
+RecordData ohlcv_data(uint32_t instrument_id, uint64_t ts_event) {
+  uint8_t length = sizeof(OhlcvMsg) / METADATA_LENGTH_MULTIPLIER;
+  return RecordData{.ohlcv = {length, RType::Ohlcv, instrument_id, ts_event, 0, 100, 200, 50,
+                              150, 1000}};
+}
+
+RecordData trade_data(uint32_t instrument_id, uint64_t ts_event) {
+  uint8_t length = sizeof(TradeMsg) / METADATA_LENGTH_MULTIPLIER;
+  return RecordData{.trade = {length, RType::Trades, instrument_id, ts_event, 0, 1000, 10, 'T',
+                              'A', 0, 0, ts_event, 0, 1}};
+}
+
+std::vector<uint8_t> encoded_with_metadata() {
+  mbinary::SymbolMap mappings;
+  mappings.add("AAPL", 1).add("TSLA", 2);
+  mbinary::Metadata metadata(2, 2, 0, 100, mappings);
+
+  mbinary::RecordEncoder encoder;
+  encoder.encode_metadata(metadata).append(
+      {ohlcv_data(1, 10), ohlcv_data(2, 20), trade_data(1, 30), ohlcv_data(1, 40)});
+  return encoder.data();
+}
+
+}  // namespace
+
+TEST(FilterTests, test_decoder_filter) {
+  std::vector<uint8_t> bytes = encoded_with_metadata();
+  CRecordDecoder* decoder = create_metadata_buffer_decoder(bytes.data(), bytes.size());
+
+  CRecordFilter* filter = create_record_filter();
+  uint32_t instrument_ids[] = {1};
+  uint8_t rtypes[] = {RType::Ohlcv};
+  ASSERT_EQ(record_filter_instrument_ids(filter, instrument_ids, 1), Success);
+  ASSERT_EQ(record_filter_rtypes(filter, rtypes, 1), Success);
+  ASSERT_EQ(record_filter_end(filter, 50), Success);
+
+  // Test
+  ASSERT_EQ(decoder_set_filter(decoder, filter), Success);
+  destroy_record_filter(filter);
+
+  std::vector<uint64_t> timestamps;
+  CRecordEnum record;
+  while (decoder_iter(decoder, &record)) {
+    timestamps.push_back(record.data.ohlcv.hd.ts_event);
+  }
+
+  // Validate
+  EXPECT_EQ(mbinary_last_error_code(), Success);
+  EXPECT_EQ(timestamps, (std::vector<uint64_t>{10, 40}));
+
+  const CMetadata* metadata = decoder_get_metadata(decoder);
+  size_t count = 0;
+  ASSERT_EQ(metadata_instrument_ids(metadata, nullptr, &count), Success);
+  EXPECT_EQ(count, 1);
+  EXPECT_EQ(metadata_end(metadata), 50);
+
+  destroy_record_decoder(decoder);
+}
+
+TEST(FilterTests, test_filter_fill_and_matches) {
+  std::vector<uint8_t> bytes = encoded_with_metadata();
+  CRecordDecoder* decoder = create_metadata_buffer_decoder(bytes.data(), bytes.size());
+  CRecordFilter* filter = create_record_filter();
+  ASSERT_EQ(record_filter_start(filter, 20), Success);
+  ASSERT_EQ(decoder_set_filter(decoder, filter), Success);
+
+  // Test
+  CRecordEnum batch[4];
+  size_t count = 0;
+  ASSERT_EQ(decoder_fill(decoder, batch, 4, &count), Success);
+
+  // Validate
+  ASSERT_EQ(count, 3);
+  EXPECT_EQ(batch[0].data.ohlcv.hd.instrument_id, 2);
+  EXPECT_EQ(batch[1].rtype, RType::Trades);
+  EXPECT_TRUE(record_filter_matches(filter, &batch[2].data.ohlcv.hd));
+  RecordHeader early = batch[0].data.ohlcv.hd;
+  early.ts_event = 19;
+  EXPECT_FALSE(record_filter_matches(filter, &early));
+
+  uint8_t invalid[] = {RType::Mbp1, 99};
+  EXPECT_EQ(record_filter_rtypes(filter, invalid, 2), InvalidArgument);
+  EXPECT_NE(std::string(mbinary_last_error()).find("99"), std::string::npos);
+  EXPECT_TRUE(record_filter_matches(filter, &batch[1].data.trade.hd));
+
+  destroy_record_filter(filter);
+  destroy_record_decoder(decoder);
+}
+
+TEST(FilterTests, test_wrapper_filter) {
+  mbinary::RecordFilter filter;
+  filter.instrument_ids({2}).rtypes({RType::Ohlcv, RType::Trades});
+
+  // Test
+  mbinary::RecordDecoder decoder =
+      mbinary::RecordDecoder::from_buffer_with_metadata(encoded_with_metadata());
+  std::vector<mbinary::Record> records = decoder.filter(filter).decode_all();
+
+  // Validate
+  ASSERT_EQ(records.size(), 1);
+  EXPECT_EQ(records[0].instrument_id(), 2);
+  EXPECT_EQ(records[0].timestamp(), 20);
+  EXPECT_EQ(decoder.metadata()->instrument_ids(), (std::vector<uint32_t>{2}));
+  EXPECT_FALSE(decoder.metadata()->ticker(1).has_value());
+}
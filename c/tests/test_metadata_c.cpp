@@ -0,0 +1,143 @@
+#include <gtest/gtest.h>
+
+#include <cstdint>
+#include <cstring>
+#include <string>
+#include <vector>
+
+#include "mbinary.h"
+
+namespace {
+
+template <typename T>
+void push_le(std::vector<uint8_t>& bytes, T value) {
+  for (size_t i = 0; i < sizeof(T); ++i) {
+    bytes.push_back(static_cast<uint8_t>(value >> (8 * i)));
+  }
+}
+
+// Length-prefixed metadata header for Ohlcv1S equities with AAPL as instrument 1
+std::vector<uint8_t> metadata_bytes() {
+  std::vector<uint8_t> payload;
+  payload.push_back(2);  // Schema::Ohlcv1S
+  payload.push_back(2);  // Dataset::Equities
+  push_le<uint64_t>(payload, 1234567898765);
+  push_le<uint64_t>(payload, 1234567898999);
+  push_le<uint32_t>(payload, 1);
+  push_le<uint32_t>(payload, 1);
+  push_le<uint32_t>(payload, 4);
+  for (char c : std::string("AAPL")) {
+    payload.push_back(static_cast<uint8_t>(c));
+  }
+
+  std::vector<uint8_t> bytes;
+  push_le<uint16_t>(bytes, static_cast<uint16_t>(payload.size()));
+  bytes.insert(bytes.end(), payload.begin(), payload.end());
+  return bytes;
+}
+
+std::vector<uint8_t> record_bytes() {
+  uint8_t length = sizeof(Mbp1Msg) / METADATA_LENGTH_MULTIPLIER;
+  CRecordEnum record{
+      .rtype = RType::Mbp1,
+      .data = RecordData{
+          .mbp1 = {length, RType::Mbp1, 1,   1622471124, 0, 1000,
+                   10,     'T',         'A', 0,          0, 123456789098765,
+                   12345,  123456,      0,   1,          2, 2,
+                   2,      1,           3}}};
+
+  CRecordEncoder* encoder = create_record_encoder();
+  encode_records(encoder, &record.data, 1);
+
+  size_t encoded_size = 0;
+  get_encoded_data(encoder, nullptr, &encoded_size);
+  std::vector<uint8_t> encoded_data(encoded_size);
+  get_encoded_data(encoder, encoded_data.data(), &encoded_size);
+  destroy_record_encoder(encoder);
+
+  return encoded_data;
+}
+
+}  // namespace
+
+TEST(MetadataTests, test_decode_metadata) {
+  std::vector<uint8_t> bytes = metadata_bytes();
+
+  // Test
+  CMetadata* metadata = decode_metadata(bytes.data(), bytes.size());
+  ASSERT_NE(metadata, nullptr) << "Failed to decode metadata";
+
+  // Validate
+  EXPECT_EQ(metadata_schema(metadata), 2);
+  EXPECT_EQ(metadata_dataset(metadata), 2);
+  EXPECT_EQ(metadata_start(metadata), 1234567898765);
+  EXPECT_EQ(metadata_end(metadata), 1234567898999);
+
+  size_t id_count = 0;
+  ASSERT_EQ(metadata_instrument_ids(metadata, nullptr, &id_count), 0);
+  ASSERT_EQ(id_count, 1);
+  std::vector<uint32_t> ids(id_count);
+  ASSERT_EQ(metadata_instrument_ids(metadata, ids.data(), &id_count), 0);
+  EXPECT_EQ(ids[0], 1);
+
+  size_t ticker_size = 0;
+  ASSERT_EQ(metadata_get_ticker(metadata, 1, nullptr, &ticker_size), 0);
+  ASSERT_EQ(ticker_size, 5);
+  std::vector<char> ticker(ticker_size);
+  ASSERT_EQ(metadata_get_ticker(metadata, 1, ticker.data(), &ticker_size), 0);
+  EXPECT_STREQ(ticker.data(), "AAPL");
+
+  size_t small_size = 2;
+  char small[2];
+  EXPECT_EQ(metadata_get_ticker(metadata, 1, small, &small_size), BufferTooSmall);
+  EXPECT_EQ(metadata_get_ticker(metadata, 99, nullptr, &ticker_size), NotFound);
+
+  destroy_metadata(metadata);
+}
+
+TEST(MetadataTests, test_metadata_decoder) {
+  std::vector<uint8_t> bytes = metadata_bytes();
+  std::vector<uint8_t> records = record_bytes();
+  bytes.insert(bytes.end(), records.begin(), records.end());
+
+  // Test
+  CRecordDecoder* decoder = create_metadata_buffer_decoder(bytes.data(), bytes.size());
+  ASSERT_NE(decoder, nullptr) << "Failed to create decoder";
+
+  // Validate
+  const CMetadata* metadata = decoder_get_metadata(decoder);
+  ASSERT_NE(metadata, nullptr) << "Metadata was not read";
+  EXPECT_EQ(metadata_start(metadata), 1234567898765);
+
+  CRecordEnum record;
+  size_t count = 0;
+  while (decoder_iter(decoder, &record)) {
+    EXPECT_EQ(record.data.mbp1.hd.instrument_id, 1);
+    EXPECT_EQ(record.data.mbp1.price, 1000);
+    ++count;
+  }
+  EXPECT_EQ(count, 1);
+
+  destroy_record_decoder(decoder);
+}
+
+TEST(MetadataTests, test_metadata_decoder_records_only) {
+  std::vector<uint8_t> records = record_bytes();
+
+  // Test
+  CRecordDecoder* decoder = create_metadata_buffer_decoder(records.data(), records.size());
+  ASSERT_NE(decoder, nullptr) << "Failed to create decoder";
+
+  // Validate
+  EXPECT_EQ(decoder_get_metadata(decoder), nullptr);
+
+  CRecordEnum record;
+  size_t count = 0;
+  while (decoder_iter(decoder, &record)) {
+    EXPECT_EQ(record.data.mbp1.price, 1000);
+    ++count;
+  }
+  EXPECT_EQ(count, 1);
+
+  destroy_record_decoder(decoder);
+}
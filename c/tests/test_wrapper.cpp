@@ -0,0 +1,173 @@
+#include <gtest/gtest.h>
+
+#include <cstdint>
+#include <string>
+#include <variant>
+#include <vector>
+
+#include "mbinary.hpp"
+
+namespace {
+
+RecordData mbp1_data(uint32_t instrument_id, int64_t price) {
+  uint8_t length = sizeof(Mbp1Msg) / METADATA_LENGTH_MULTIPLIER;
+  return RecordData{.mbp1 = {length, RType::Mbp1, instrument_id, 1622471124, 0, price,
+                             10, 'T', 'A', 0, 0, 123456789098765,
+                             12345, 123456, 0, 1, 2, 2,
+                             2, 1, 3}};
+}
+
+RecordData ohlcv_data(uint32_t instrument_id) {
+  uint8_t length = sizeof(OhlcvMsg) / METADATA_LENGTH_MULTIPLIER;
+  return RecordData{.ohlcv = {length, RType::Ohlcv, instrument_id, 1622471124, 0, 100, 200,
+                              50, 150, 1000}};
+}
+
+template <class... Ts>
+struct overloaded : Ts... {
+  using Ts::operator()...;
+};
+template <class... Ts>
+overloaded(Ts...) -> overloaded<Ts...>;
+
+}  // namespace
+
+TEST(WrapperTests, test_encode_decode_range) {
+  mbinary::RecordEncoder encoder;
+  encoder.append({mbp1_data(1, 1000), ohlcv_data(2)});
+
+  // Test
+  mbinary::RecordDecoder decoder = mbinary::RecordDecoder::from_buffer(encoder.data());
+
+  std::vector<mbinary::Record> records;
+  for (const mbinary::Record& record : decoder) {
+    records.push_back(record);
+  }
+
+  // Validate
+  ASSERT_EQ(records.size(), 2);
+  EXPECT_EQ(records[0].rtype(), Mbp1);
+  EXPECT_EQ(records[0].instrument_id(), 1);
+  EXPECT_EQ(records[0].price(), 1000);
+  ASSERT_NE(records[0].mbp1(), nullptr);
+  EXPECT_EQ(records[0].mbp1()->size, 10);
+  EXPECT_EQ(records[0].ohlcv(), nullptr);
+
+  EXPECT_EQ(records[1].rtype(), Ohlcv);
+  ASSERT_NE(records[1].ohlcv(), nullptr);
+  EXPECT_EQ(records[1].ohlcv()->volume, 1000);
+  EXPECT_EQ(records[1].timestamp(), 1622471124);
+  EXPECT_FALSE(decoder.next().has_value());
+}
+
+TEST(WrapperTests, test_record_variant) {
+  mbinary::RecordEncoder encoder;
+  encoder.append({mbp1_data(1, 1000), ohlcv_data(2)});
+  std::vector<mbinary::Record> records =
+      mbinary::RecordDecoder::from_buffer(encoder.data()).decode_all();
+  ASSERT_EQ(records.size(), 2);
+
+  // Test
+  int64_t total = 0;
+  for (const mbinary::Record& record : records) {
+    mbinary::RecordVariant variant = record.variant();
+    EXPECT_EQ(variant.index() + 1, record.rtype());
+    std::visit(overloaded{[&](const Mbp1Msg& msg) { total += msg.price; },
+                          [&](const OhlcvMsg& msg) { total += msg.close; },
+                          [](const auto&) {}},
+               variant);
+  }
+
+  // Validate
+  EXPECT_EQ(total, 1150);
+  EXPECT_TRUE(std::holds_alternative<Mbp1Msg>(records[0].variant()));
+  EXPECT_EQ(std::get<OhlcvMsg>(records[1].variant()).high, 200);
+}
+
+TEST(WrapperTests, test_metadata_round_trip) {
+  mbinary::SymbolMap mappings;
+  mappings.add("AAPL", 1).add("TSLA", 2);
+  mbinary::Metadata metadata(2, 2, 1234567898765, 1234567898999, mappings);
+
+  mbinary::RecordEncoder encoder;
+  encoder.encode_metadata(metadata).append(mbinary::Record(CRecordEnum{
+      .rtype = RType::Mbp1, .data = mbp1_data(2, 1000)}));
+
+  // Test
+  mbinary::RecordDecoder decoder =
+      mbinary::RecordDecoder::from_buffer_with_metadata(encoder.data());
+
+  // Validate
+  std::optional<mbinary::MetadataView> view = decoder.metadata();
+  ASSERT_TRUE(view.has_value());
+  EXPECT_EQ(view->schema(), 2);
+  EXPECT_EQ(view->start(), 1234567898765);
+  EXPECT_EQ(view->instrument_ids(), (std::vector<uint32_t>{1, 2}));
+  EXPECT_EQ(view->ticker(2), std::optional<std::string>("TSLA"));
+  EXPECT_FALSE(view->ticker(99).has_value());
+
+  std::vector<mbinary::Record> records = decoder.decode_all();
+  ASSERT_EQ(records.size(), 1);
+  EXPECT_EQ(view->ticker(records[0].instrument_id()), std::optional<std::string>("TSLA"));
+}
+
+TEST(WrapperTests, test_errors_throw) {
+  // Test
+  try {
+    mbinary::RecordDecoder::open("../tests/missing_file.bin");
+    FAIL() << "Expected mbinary::Error";
+  } catch (const mbinary::Error& e) {
+    // Validate
+    EXPECT_EQ(e.code(), IoError);
+    EXPECT_NE(std::string(e.what()).find("missing_file.bin"), std::string::npos);
+  }
+
+  try {
+    mbinary::Metadata metadata(255, 0, 0, 0, mbinary::SymbolMap());
+    FAIL() << "Expected mbinary::Error";
+  } catch (const mbinary::Error& e) {
+    EXPECT_EQ(e.code(), InvalidArgument);
+  }
+}
+
+TEST(WrapperTests, test_file_stream) {
+  std::string path = "../tests/test_wrapper_stream.bin";
+  {
+    mbinary::RecordEncoder encoder = mbinary::RecordEncoder::open(path);
+    encoder.append({mbp1_data(1, 1000), mbp1_data(1, 1001)});
+  }  // Flushed when destroyed
+
+  // Test
+  mbinary::RecordDecoder decoder = mbinary::RecordDecoder::open(path);
+
+  // Validate
+  std::vector<int64_t> prices;
+  for (const mbinary::Record& record : decoder) {
+    prices.push_back(record.price());
+  }
+  EXPECT_EQ(prices, (std::vector<int64_t>{1000, 1001}));
+  EXPECT_FALSE(decoder.metadata().has_value());
+}
+
+TEST(WrapperTests, test_backtest) {
+  const char* tickers[] = {"AAPL"};
+  CParameters parameters{"Testing", 10000, "Ohlcv-1s", "BAR", 1, 2, tickers, 1};
+  CTrade trade{1, 1, 1704903000, "AAPL", 4, 13074, -52296, -52296, TradeAction::Buy, 100};
+
+  mbinary::Backtest backtest(UINT16_MAX, "wrapper", parameters);
+  backtest.add_trades({trade}).add_period_timeseries({{1704903000, 10000, 0, 0, 0}});
+
+  // Test
+  mbinary::Backtest decoded = mbinary::Backtest::decode(backtest.encode());
+
+  // Validate
+  EXPECT_EQ(decoded.name(), "wrapper");
+  EXPECT_STREQ(decoded.parameters().tickers[0], "AAPL");
+  EXPECT_EQ(decoded.period_timeseries().size, 1);
+  int64_t fees = 0;
+  for (const CTrade& decoded_trade : decoded.trades()) {
+    fees += decoded_trade.fees;
+  }
+  EXPECT_EQ(fees, 100);
+  EXPECT_TRUE(decoded.signals().empty());
+}
@@ -1,7 +1,8 @@
 # lib.pyi
-from typing import Dict, List, Optional, Any
+from typing import Awaitable, Dict, List, Optional, Any
 from enum import Enum
 from typing import SupportsBytes
+import numpy
 import pandas
 
 
@@ -84,6 +85,28 @@ class RType(Enum):
     @classmethod
     def from_str(cls, value: str) -> "RType": ...
 
+class TradeAction(Enum):
+    BUY = "BUY"
+    SELL = "SELL"
+    SHORT = "SHORT"
+    COVER = "COVER"
+
+    @classmethod
+    def from_str(cls, value: str) -> "TradeAction": ...
+    @classmethod
+    def from_int(cls, value: int) -> "TradeAction": ...
+
+class OrderType(Enum):
+    MARKET = "MKT"
+    LIMIT = "LMT"
+    STOP_MARKET = "STP"
+    STOP_LIMIT = "STP_LMT"
+
+    @classmethod
+    def from_str(cls, value: str) -> "OrderType": ...
+    @classmethod
+    def from_int(cls, value: int) -> "OrderType": ...
+
 class SymbolMap:
     def __init__(self, map: Dict[int, str]) -> None: ...
 
@@ -418,9 +441,44 @@ class BufferStore(SupportsBytes):
     def write_to_file(self, file_path: str) -> None: ...
     @staticmethod
     def from_file(file_path: str) -> "BufferStore": ...
+    def decode_to_columns(
+        self, pretty_ts: bool = False, pretty_px: bool = False
+    ) -> Dict[str, Dict[str, numpy.ndarray]]: ...
     def decode_to_df(self, pretty_ts: bool, pretty_px: bool) -> pandas.DataFrame: ...
     def replay(self) -> Optional[RecordMsg]: ...
 
+class RecordFilter:
+    def __init__(self) -> None: ...
+    def instrument_ids(self, instrument_ids: List[int]) -> "RecordFilter": ...
+    def rtypes(self, rtypes: List[RType]) -> "RecordFilter": ...
+    def start(self, start: int) -> "RecordFilter": ...
+    def end(self, end: int) -> "RecordFilter": ...
+    def matches(self, header: RecordHeader) -> bool: ...
+    def filter_metadata(self, metadata: Metadata) -> Metadata: ...
+
+class PyFileDecoder:
+    def __init__(
+        self, file_path: str, filter: Optional[RecordFilter] = None
+    ) -> None: ...
+    @property
+    def metadata(self) -> Optional[Metadata]: ...
+    def __iter__(self) -> "PyFileDecoder": ...
+    def __next__(self) -> RecordMsg: ...
+    def read_batch(self, n: int) -> List[RecordMsg]: ...
+
+class PyAsyncDecoder:
+    @staticmethod
+    def from_file(
+        file_path: str, filter: Optional[RecordFilter] = None
+    ) -> "PyAsyncDecoder": ...
+    @staticmethod
+    def from_socket(
+        socket_path: str, filter: Optional[RecordFilter] = None
+    ) -> "PyAsyncDecoder": ...
+    def metadata(self) -> Awaitable[Optional[Metadata]]: ...
+    def __aiter__(self) -> "PyAsyncDecoder": ...
+    def __anext__(self) -> Awaitable[RecordMsg]: ...
+
 class PyMetadataEncoder:
     def __init__(self) -> None: ...
     def encode_metadata(self, metadata: Metadata) -> None: ...
@@ -431,18 +489,28 @@ class PyRecordEncoder:
     def encode_records(self, records: List[Mbp1Msg]) -> None: ...
     def get_encoded_data(self) -> bytes: ...
 
+class PyCombinedEncoder:
+    def __init__(self) -> None: ...
+    def encode_metadata(self, metadata: Metadata) -> None: ...
+    def encode_record(self, record: RecordMsg) -> None: ...
+    def encode_records(self, records: List[RecordMsg]) -> None: ...
+    def encode(self, metadata: Metadata, records: List[RecordMsg]) -> None: ...
+    def clear(self) -> None: ...
+    def write_to_file(self, file_path: str, append: bool = False) -> None: ...
+    def get_encoded_data(self) -> bytes: ...
+
 # -- Trading -- 
 class SignalInstructions:
     def __init__(
         self,
         ticker: str,
-        order_type: str,
-        action: str,
+        order_type: OrderType,
+        action: TradeAction,
         signal_id: int,
         weight: int,
         quantity: int,
-        limit_price: str,
-        aux_price: str,
+        limit_price: Optional[int] = None,
+        aux_price: Optional[int] = None,
     ) -> None: ...
     def to_dict(self) -> Dict: ...
 
@@ -465,7 +533,7 @@ class Trades:
         avg_price: int,
         trade_value: int,
         trade_cost: int,
-        action: str,
+        action: TradeAction,
         fees: int,
     ) -> None: ...
     def to_dict(self) -> Dict: ...
@@ -481,6 +549,20 @@ class TimeseriesStats:
     ) -> None: ...
     def to_dict(self) -> Dict: ...
 
+class PositionStats:
+    def __init__(
+        self,
+        timestamp: int,
+        ticker: str,
+        quantity: int,
+        avg_cost: int,
+        market_price: int,
+        market_value: int,
+        realized_pnl: int,
+        unrealized_pnl: int,
+    ) -> None: ...
+    def to_dict(self) -> Dict: ...
+
 class StaticStats:
     def __init__(
         self,
@@ -544,7 +626,8 @@ class BacktestData:
         period_timeseries_stats: List[TimeseriesStats],
         daily_timeseries_stats: List[TimeseriesStats],
         trades: List[Trades],
-        signals: List[Signals]
+        signals: List[Signals],
+        position_timeseries: List[PositionStats] = [],
     ) -> None: ...
     def to_dict(self) -> Dict: ...
     @property
@@ -553,11 +636,139 @@ class BacktestData:
     def period_timeseries_stats(self) -> List[TimeseriesStats]: ...
     @property
     def daily_timeseries_stats(self) -> List[TimeseriesStats]: ...
+    @property
+    def position_timeseries(self) -> List[PositionStats]: ...
+
+class StatDelta:
+    @property
+    def field(self) -> str: ...
+    @property
+    def values(self) -> List[int]: ...
+    @property
+    def deltas(self) -> List[int]: ...
+
+class EquityPoint:
+    @property
+    def timestamp(self) -> int: ...
+    @property
+    def equity_values(self) -> List[Optional[int]]: ...
+
+class TradeDiff:
+    @property
+    def signal_id(self) -> int: ...
+    @property
+    def timestamp(self) -> int: ...
+    @property
+    def ticker(self) -> str: ...
+    @property
+    def trades(self) -> List[Optional[Trades]]: ...
+
+class BacktestComparison:
+    def __init__(self, backtests: List[BacktestData]) -> None: ...
+    @property
+    def backtest_names(self) -> List[str]: ...
+    @property
+    def static_stats(self) -> List[StatDelta]: ...
+    @property
+    def period_equity(self) -> List[EquityPoint]: ...
+    @property
+    def daily_equity(self) -> List[EquityPoint]: ...
+    @property
+    def trade_diffs(self) -> List[TradeDiff]: ...
+    def to_json(self) -> str: ...
+
+class UnmatchedSignal:
+    @property
+    def timestamp(self) -> int: ...
+    @property
+    def instruction(self) -> SignalInstructions: ...
+
+class FillMatch:
+    @property
+    def ticker(self) -> str: ...
+    @property
+    def action(self) -> TradeAction: ...
+    @property
+    def backtest_timestamp(self) -> int: ...
+    @property
+    def live_timestamp(self) -> int: ...
+    @property
+    def backtest_quantity(self) -> int: ...
+    @property
+    def live_quantity(self) -> int: ...
+    @property
+    def backtest_avg_price(self) -> int: ...
+    @property
+    def live_avg_price(self) -> int: ...
+    @property
+    def slippage(self) -> int: ...
+    @property
+    def slippage_cost(self) -> int: ...
+    @property
+    def backtest_fees(self) -> int: ...
+    @property
+    def live_fees(self) -> int: ...
+    @property
+    def fee_difference(self) -> int: ...
+
+class PnlAttribution:
+    @property
+    def ticker(self) -> str: ...
+    @property
+    def backtest_pnl(self) -> int: ...
+    @property
+    def live_pnl(self) -> int: ...
+    @property
+    def difference(self) -> int: ...
+    @property
+    def slippage(self) -> int: ...
+    @property
+    def fees(self) -> int: ...
+    @property
+    def unexplained(self) -> int: ...
+
+class Reconciliation:
+    def __init__(
+        self, live: LiveData, backtest: BacktestData, tolerance: int = 0
+    ) -> None: ...
+    @property
+    def strategy_name(self) -> str: ...
+    @property
+    def tolerance(self) -> int: ...
+    @property
+    def missed_signals(self) -> List[UnmatchedSignal]: ...
+    @property
+    def extra_signals(self) -> List[UnmatchedSignal]: ...
+    @property
+    def fills(self) -> List[FillMatch]: ...
+    @property
+    def unmatched_backtest_trades(self) -> List[Trades]: ...
+    @property
+    def unmatched_live_trades(self) -> List[Trades]: ...
+    @property
+    def pnl_attribution(self) -> List[PnlAttribution]: ...
+    @property
+    def total_slippage_cost(self) -> int: ...
+    @property
+    def total_fee_difference(self) -> int: ...
+    def to_json(self) -> str: ...
 
 class PyBacktestEncoder:
     def __init__(self) -> None: ...
     def encode_backtest(self, backtest: BacktestData) -> bytes: ...
 
+class PyBacktestDecoder:
+    def __init__(self) -> None: ...
+    def decode_backtest(self, data: bytes) -> BacktestData: ...
+
+class PyLiveEncoder:
+    def __init__(self) -> None: ...
+    def encode_live(self, live: LiveData) -> bytes: ...
+
+class PyLiveDecoder:
+    def __init__(self) -> None: ...
+    def decode_live(self, data: bytes) -> LiveData: ...
+
 class AccountSummary:
     def __init__(self,        
         currency: str,
@@ -583,6 +794,28 @@ class AccountSummary:
         end_unrealized_pnl: int,
     ) -> None: ...
     def to_dict(self) -> Dict: ...
+    @staticmethod
+    def from_snapshots(
+        currency: str, start: AccountSnapshot, end: AccountSnapshot
+    ) -> AccountSummary: ...
+    def start(self) -> AccountSnapshot: ...
+    def end(self) -> AccountSnapshot: ...
+
+class AccountSnapshot:
+    def __init__(
+        self,
+        timestamp: int,
+        buying_power: int,
+        excess_liquidity: int,
+        full_available_funds: int,
+        full_init_margin_req: int,
+        full_maint_margin_req: int,
+        futures_pnl: int,
+        net_liquidation: int,
+        total_cash_balance: int,
+        unrealized_pnl: int,
+    ) -> None: ...
+    def to_dict(self) -> Dict: ...
 
 
 class LiveData:
@@ -593,8 +826,13 @@ class LiveData:
         trades: List[Trades],
         signals: List[Signals],
         account: AccountSummary,
+        account_snapshots: List[AccountSnapshot] = [],
     ) -> None: ...
     def to_dict(self) -> Dict: ...
+    def push_account_snapshot(self, snapshot: AccountSnapshot) -> None: ...
+    def account_summary(self) -> AccountSummary: ...
     @property
     def account(self) -> AccountSummary: ...
+    @property
+    def account_snapshots(self) -> List[AccountSnapshot]: ...
 
@@ -1,16 +1,24 @@
 use mbinary::{
     backtest::{
-        BacktestData, BacktestMetaData, Parameters, SignalInstructions, Signals, StaticStats,
-        TimeseriesStats, Trades,
+        BacktestData, BacktestMetaData, Parameters, PositionStats, SignalInstructions, Signals,
+        StaticStats, TimeseriesStats, Trades,
     },
-    enums::{Action, Dataset, RType, Schema, Side, Stype},
-    live::{AccountSummary, LiveData},
+    compare::{BacktestComparison, EquityPoint, StatDelta, TradeDiff},
+    enums::{Action, Dataset, OrderType, RType, Schema, Side, Stype, TradeAction},
+    filter::RecordFilter,
+    live::{AccountSnapshot, AccountSummary, LiveData},
     metadata::Metadata,
     params::RetrieveParams,
+    python::async_decode::PyAsyncDecoder,
     python::backest_encode::PyBacktestEncoder,
+    python::backtest_decode::PyBacktestDecoder,
     python::buffer::BufferStore,
-    python::encode::{PyMetadataEncoder, PyRecordEncoder},
+    python::decode::PyFileDecoder,
+    python::encode::{PyCombinedEncoder, PyMetadataEncoder, PyRecordEncoder},
+    python::live_decode::PyLiveDecoder,
+    python::live_encode::PyLiveEncoder,
     python::records::RecordMsg,
+    reconcile::{FillMatch, PnlAttribution, Reconciliation, UnmatchedSignal},
     records::{BboMsg, BidAskPair, Mbp1Msg, OhlcvMsg, RecordHeader, TbboMsg, TradeMsg},
     symbols::SymbolMap,
     vendors::Vendors,
@@ -34,6 +42,8 @@ fn python_mbinary(_py: Python<'_>, m: &Bound<PyModule>) -> PyResult<()> {
     checked_add_class::<Action>(m)?;
     checked_add_class::<Schema>(m)?;
     checked_add_class::<RType>(m)?;
+    checked_add_class::<TradeAction>(m)?;
+    checked_add_class::<OrderType>(m)?;
     checked_add_class::<SymbolMap>(m)?;
     checked_add_class::<Metadata>(m)?;
     checked_add_class::<RecordHeader>(m)?;
@@ -45,20 +55,37 @@ fn python_mbinary(_py: Python<'_>, m: &Bound<PyModule>) -> PyResult<()> {
     checked_add_class::<BidAskPair>(m)?;
     checked_add_class::<RetrieveParams>(m)?;
     checked_add_class::<BufferStore>(m)?;
+    checked_add_class::<RecordFilter>(m)?;
+    checked_add_class::<PyFileDecoder>(m)?;
+    checked_add_class::<PyAsyncDecoder>(m)?;
     checked_add_class::<RecordMsg>(m)?;
     checked_add_class::<BacktestData>(m)?;
     checked_add_class::<BacktestMetaData>(m)?;
     checked_add_class::<StaticStats>(m)?;
     checked_add_class::<Parameters>(m)?;
     checked_add_class::<TimeseriesStats>(m)?;
+    checked_add_class::<PositionStats>(m)?;
     checked_add_class::<Trades>(m)?;
     checked_add_class::<Signals>(m)?;
     checked_add_class::<SignalInstructions>(m)?;
+    checked_add_class::<BacktestComparison>(m)?;
+    checked_add_class::<StatDelta>(m)?;
+    checked_add_class::<EquityPoint>(m)?;
+    checked_add_class::<TradeDiff>(m)?;
+    checked_add_class::<Reconciliation>(m)?;
+    checked_add_class::<UnmatchedSignal>(m)?;
+    checked_add_class::<FillMatch>(m)?;
+    checked_add_class::<PnlAttribution>(m)?;
     checked_add_class::<LiveData>(m)?;
     checked_add_class::<AccountSummary>(m)?;
+    checked_add_class::<AccountSnapshot>(m)?;
     checked_add_class::<PyRecordEncoder>(m)?;
     checked_add_class::<PyMetadataEncoder>(m)?;
+    checked_add_class::<PyCombinedEncoder>(m)?;
     checked_add_class::<PyBacktestEncoder>(m)?;
+    checked_add_class::<PyBacktestDecoder>(m)?;
+    checked_add_class::<PyLiveEncoder>(m)?;
+    checked_add_class::<PyLiveDecoder>(m)?;
     let _ = m.add("PRICE_SCALE", PRICE_SCALE);
     let _ = m.add("QUANTITY_SCALE", QUANTITY_SCALE);
 
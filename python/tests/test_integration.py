@@ -1,3 +1,6 @@
+import os
+import asyncio
+import tempfile
 import unittest
 import json
 from mbinary import (
@@ -8,6 +11,9 @@ from mbinary import (
     SymbolMap,
     Metadata,
     BufferStore,
+    PyFileDecoder,
+    RecordFilter,
+    PyAsyncDecoder,
     BidAskPair,
     RecordMsg,
     OhlcvMsg,
@@ -20,14 +26,25 @@ from mbinary import (
     Signals,
     Parameters,
     TimeseriesStats,
+    PositionStats,
     StaticStats,
     SignalInstructions,
     AccountSummary,
+    AccountSnapshot,
     LiveData,
     PyRecordEncoder,
     PyMetadataEncoder,
+    PyCombinedEncoder,
+    PyLiveEncoder,
+    PyLiveDecoder,
+    PyBacktestEncoder,
+    PyBacktestDecoder,
+    BacktestComparison,
+    Reconciliation,
     Vendors,
     Dataset,
+    TradeAction,
+    OrderType,
     Stype,
     RetrieveParams,
 )
@@ -129,7 +146,7 @@ class IntegrationTests(unittest.TestCase):
             avg_price=2345432,
             trade_value=12343234,
             trade_cost=12343234,
-            action="BUY",
+            action=TradeAction.BUY,
             fees=2343,
         )
 
@@ -142,7 +159,7 @@ class IntegrationTests(unittest.TestCase):
             avg_price=2345432,
             trade_value=12343234,
             trade_cost=12343234,
-            action="BUY",
+            action=TradeAction.BUY,
             fees=2343,
         )
 
@@ -150,13 +167,13 @@ class IntegrationTests(unittest.TestCase):
 
         self.instructions = SignalInstructions(
             ticker="AAPL",
-            order_type="MKT",
-            action="BUY",
+            order_type=OrderType.MARKET,
+            action=TradeAction.BUY,
             signal_id=1,
             weight=13213432,
             quantity=2343,
-            limit_price="",
-            aux_price="",
+            limit_price=None,
+            aux_price=None,
         )
 
         self.signal1 = Signals(1234532345, [self.instructions])
@@ -234,6 +251,7 @@ class IntegrationTests(unittest.TestCase):
                 "end_total_cash_balance": 76693599,
                 "end_unrealized_pnl": -2899,
             },
+            "account_snapshots": [],
             "trades": [
                 {
                     "trade_id": 1,
@@ -271,8 +289,8 @@ class IntegrationTests(unittest.TestCase):
                             "signal_id": 1,
                             "weight": 13213432,
                             "quantity": 2343,
-                            "limit_price": "",
-                            "aux_price": "",
+                            "limit_price": None,
+                            "aux_price": None,
                         }
                     ],
                 },
@@ -286,8 +304,8 @@ class IntegrationTests(unittest.TestCase):
                             "signal_id": 1,
                             "weight": 13213432,
                             "quantity": 2343,
-                            "limit_price": "",
-                            "aux_price": "",
+                            "limit_price": None,
+                            "aux_price": None,
                         }
                     ],
                 },
@@ -295,6 +313,100 @@ class IntegrationTests(unittest.TestCase):
         }
         self.assertDictEqual(expected, live.to_dict())
 
+    def test_reconciliation(self):
+        backtest = BacktestData(
+            BacktestMetaData(1, "base", self.parameters, self.static_stats),
+            self.period_list,
+            self.daily_list,
+            self.trade_list,
+            self.signals_list,
+        )
+        live = LiveData(
+            None,
+            self.parameters,
+            [self.trade1],
+            self.signals_list,
+            self.account_summary,
+        )
+
+        # Test
+        reconciliation = Reconciliation(live, backtest, tolerance=1000)
+
+        # Validate
+        self.assertEqual(reconciliation.strategy_name, "Testing")
+        self.assertEqual(reconciliation.missed_signals, [])
+        self.assertEqual(len(reconciliation.fills), 1)
+        self.assertEqual(reconciliation.fills[0].slippage, 0)
+        self.assertEqual(len(reconciliation.unmatched_backtest_trades), 1)
+        self.assertEqual(reconciliation.pnl_attribution[0].ticker, "AAPL")
+        self.assertEqual(json.loads(reconciliation.to_json())["tolerance"], 1000)
+
+        live.parameters = Parameters(
+            strategy_name="Other",
+            capital=10000,
+            schema="Ohlcv-1s",
+            data_type="BAR",
+            start=1730160814000000000,
+            end=1730160814000000000,
+            tickers=["test", "test2"],
+        )
+        with self.assertRaises(ValueError):
+            Reconciliation(live, backtest)
+
+    def test_live_account_snapshots(self):
+        live = LiveData(
+            None,
+            self.parameters,
+            self.trade_list,
+            self.signals_list,
+            self.account_summary,
+            [self.account_summary.start()],
+        )
+        snapshot = AccountSnapshot(
+            timestamp=1704903500,
+            buying_power=2557000,
+            excess_liquidity=767000,
+            full_available_funds=767000,
+            full_init_margin_req=2823000,
+            full_maint_margin_req=2823000,
+            futures_pnl=-464000,
+            net_liquidation=767600000,
+            total_cash_balance=-11292000,
+            unrealized_pnl=100,
+        )
+
+        # Test
+        live.push_account_snapshot(snapshot)
+        summary = live.account_summary()
+
+        # Validate
+        self.assertEqual(len(live.account_snapshots), 2)
+        self.assertEqual(summary.start_timestamp, 1704903000)
+        self.assertEqual(summary.end_timestamp, 1704903500)
+        self.assertEqual(summary.end_net_liquidation, 767600000)
+        self.assertEqual(summary.end().to_dict(), snapshot.to_dict())
+
+        encoded = PyLiveEncoder().encode_live(live)
+        decoded = PyLiveDecoder().decode_live(encoded)
+        self.assertDictEqual(live.to_dict(), decoded.to_dict())
+
+    def test_live_encode_decode(self):
+        live = LiveData(
+            12,
+            self.parameters,
+            self.trade_list,
+            self.signals_list,
+            self.account_summary,
+        )
+
+        # Test
+        encoded = PyLiveEncoder().encode_live(live)
+        decoded = PyLiveDecoder().decode_live(encoded)
+
+        # Validate
+        self.assertIsInstance(encoded, bytes)
+        self.assertDictEqual(live.to_dict(), decoded.to_dict())
+
     # -- Backtest --
     def test_backtest_data(self):
         metadata = BacktestMetaData(
@@ -423,8 +535,8 @@ class IntegrationTests(unittest.TestCase):
                             "signal_id": 1,
                             "weight": 13213432,
                             "quantity": 2343,
-                            "limit_price": "",
-                            "aux_price": "",
+                            "limit_price": None,
+                            "aux_price": None,
                         }
                     ],
                 },
@@ -438,15 +550,44 @@ class IntegrationTests(unittest.TestCase):
                             "signal_id": 1,
                             "weight": 13213432,
                             "quantity": 2343,
-                            "limit_price": "",
-                            "aux_price": "",
+                            "limit_price": None,
+                            "aux_price": None,
                         }
                     ],
                 },
             ],
+            "position_timeseries": [],
         }
         self.assertEqual(expected, backtest.to_dict())
 
+    def test_position_stats(self):
+        position = PositionStats(
+            1704903000,
+            "AAPL",
+            4000000000,
+            130740000000,
+            131000000000,
+            524000000000,
+            -100000000,
+            1040000000,
+        )
+        backtest = BacktestData(
+            BacktestMetaData(1, "Name", self.parameters, self.static_stats),
+            [],
+            [],
+            [],
+            [],
+            position_timeseries=[position],
+        )
+
+        # Test
+        encoded = PyBacktestEncoder().encode_backtest(backtest)
+        decoded = PyBacktestDecoder().decode_backtest(encoded)
+
+        # Validate
+        self.assertEqual(decoded.position_timeseries[0].to_dict(), position.to_dict())
+        self.assertEqual(position.to_dict()["unrealized_pnl"], 1040000000)
+
     def test_parameters(self):
         strategy_name = "Testing"
         capital = 10000
@@ -601,7 +742,7 @@ class IntegrationTests(unittest.TestCase):
         avg_price = 2345432
         trade_value = 12343234
         trade_cost = 12343234
-        action = "BUY"
+        action = TradeAction.BUY
         fees = 2343
 
         # Test
@@ -635,13 +776,13 @@ class IntegrationTests(unittest.TestCase):
 
     def test_signal(self):
         ticker = "AAPL"
-        order_type = "MKT"
-        action = "BUY"
+        order_type = OrderType.MARKET
+        action = TradeAction.BUY
         signal_id = 1
         weight = 13213432
         quantity = 2343
-        limit_price = ""
-        aux_price = ""
+        limit_price = None
+        aux_price = None
         timestamp = 1234532345
 
         instructions = SignalInstructions(
@@ -669,8 +810,8 @@ class IntegrationTests(unittest.TestCase):
                     "signal_id": 1,
                     "weight": 13213432,
                     "quantity": 2343,
-                    "limit_price": "",
-                    "aux_price": "",
+                    "limit_price": None,
+                    "aux_price": None,
                 }
             ],
         }
@@ -678,13 +819,13 @@ class IntegrationTests(unittest.TestCase):
 
     def test_signalinstructions(self):
         ticker = "AAPL"
-        order_type = "MKT"
-        action = "BUY"
+        order_type = OrderType.MARKET
+        action = TradeAction.BUY
         signal_id = 1
         weight = 13213432
         quantity = 2343
-        limit_price = ""
-        aux_price = ""
+        limit_price = None
+        aux_price = None
 
         # Test
         instructions = SignalInstructions(
@@ -706,11 +847,64 @@ class IntegrationTests(unittest.TestCase):
             "signal_id": 1,
             "weight": 13213432,
             "quantity": 2343,
-            "limit_price": "",
-            "aux_price": "",
+            "limit_price": None,
+            "aux_price": None,
         }
         self.assertEqual(expected, instructions.to_dict())
 
+    def test_backtest_encode_decode(self):
+        metadata = BacktestMetaData(
+            1234,
+            "Name",
+            self.parameters,
+            self.static_stats,
+        )
+        backtest = BacktestData(
+            metadata,
+            self.period_list,
+            self.daily_list,
+            self.trade_list,
+            self.signals_list,
+        )
+
+        # Test
+        encoded = PyBacktestEncoder().encode_backtest(backtest)
+        decoded = PyBacktestDecoder().decode_backtest(encoded)
+
+        # Validate
+        self.assertDictEqual(backtest.to_dict(), decoded.to_dict())
+
+    def test_backtest_comparison(self):
+        base = BacktestData(
+            BacktestMetaData(1, "base", self.parameters, self.static_stats),
+            self.period_list,
+            self.daily_list,
+            self.trade_list,
+            self.signals_list,
+        )
+        other = BacktestData(
+            BacktestMetaData(2, "other", self.parameters, self.static_stats),
+            self.period_list,
+            self.daily_list,
+            [],
+            self.signals_list,
+        )
+
+        # Test
+        comparison = BacktestComparison([base, other])
+
+        # Validate
+        self.assertEqual(comparison.backtest_names, ["base", "other"])
+        self.assertTrue(all(d.deltas == [0, 0] for d in comparison.static_stats))
+        self.assertEqual(len(comparison.trade_diffs), len(self.trade_list))
+        self.assertIsNone(comparison.trade_diffs[0].trades[1])
+        self.assertEqual(
+            json.loads(comparison.to_json())["backtest_names"], ["base", "other"]
+        )
+
+        with self.assertRaises(ValueError):
+            BacktestComparison([base])
+
     # -- Records --
     def test_side(self):
         # Direct instantiation
@@ -754,6 +948,37 @@ class IntegrationTests(unittest.TestCase):
         with self.assertRaises(ValueError):
             Action.from_str("dj")
 
+    def test_trade_action(self):
+        # from str, including legacy spellings
+        self.assertEqual(TradeAction.from_str("SELL"), TradeAction.SELL)
+        self.assertEqual(TradeAction.from_str("long"), TradeAction.BUY)
+
+        # from int
+        self.assertEqual(TradeAction.from_int(3), TradeAction.SHORT)
+
+        # value
+        self.assertEqual(TradeAction.COVER.value, "COVER")
+
+        # Error
+        with self.assertRaises(ValueError):
+            TradeAction.from_str("HOLD")
+
+    def test_order_type(self):
+        # from str, including legacy spellings
+        self.assertEqual(OrderType.from_str("MKT"), OrderType.MARKET)
+        self.assertEqual(OrderType.from_str("STP LMT"), OrderType.STOP_LIMIT)
+
+        # from int
+        self.assertEqual(OrderType.from_int(2), OrderType.LIMIT)
+
+        # value
+        self.assertEqual(OrderType.STOP_MARKET.value, "STP")
+        self.assertEqual(OrderType.STOP_MARKET.name, "STOP_MARKET")
+
+        # Error
+        with self.assertRaises(ValueError):
+            OrderType.from_str("MOC")
+
     def test_schema(self):
         # instantiation
         mbp_1 = Schema.MBP1
@@ -1207,6 +1432,54 @@ class IntegrationTests(unittest.TestCase):
 
         self.assertTrue(len(binary) > 0)
 
+    def test_combined_encoder(self):
+        symbol_map = SymbolMap({1: "AAPL"})
+        metadata = Metadata(
+            Schema.from_str("mbp-1"),
+            Dataset.from_str("equities"),
+            1234567654321,
+            987654345676543456,
+            symbol_map,
+        )
+        pair = BidAskPair(1, 2, 3, 4, 5, 6)
+        mbp = Mbp1Msg(
+            1, 123456765432, 0, 1, 2, Action.ADD, Side.ASK, 0, 0, 3, 4, 5, 0, [pair]
+        )
+        trade = TradeMsg(
+            1, 123456765433, 0, 1, 2, Action.TRADE, Side.ASK, 0, 0, 3, 4, 5
+        )
+        bbo = BboMsg(1, 123456765434, 0, [pair])
+        ohlcv = OhlcvMsg(1, 123456765435, 0, 1, 2, 3, 4, 100000)
+        file_path = "tests/combined.bin"
+
+        # Test
+        encoder = PyCombinedEncoder()
+        encoder.encode_metadata(metadata)
+        encoder.encode_records([mbp, trade])
+        encoder.write_to_file(file_path)
+
+        encoder.clear()
+        encoder.encode_record(bbo)
+        encoder.encode_record(ohlcv)
+        encoder.write_to_file(file_path, append=True)
+
+        buffer_obj = BufferStore.from_file(file_path)
+        records = buffer_obj.decode_to_array()
+
+        # Validate
+        self.assertEqual(buffer_obj.metadata.mappings.get_ticker(1), "AAPL")
+        self.assertEqual(len(records), 4)
+        self.assertIsInstance(records[0], Mbp1Msg)
+        self.assertIsInstance(records[1], TradeMsg)
+        self.assertIsInstance(records[2], BboMsg)
+        self.assertIsInstance(records[3], OhlcvMsg)
+
+        with self.assertRaises(ValueError):
+            encoder.encode_metadata(metadata)
+
+        # Cleanup
+        os.remove(file_path)
+
     def test_retrieve_params_json(self):
         # Create an instance
         params = RetrieveParams(
@@ -1296,6 +1569,126 @@ class IntegrationTests(unittest.TestCase):
         # Valdiate
         self.assertIsInstance(df, pandas.DataFrame)
 
+    def test_decode_to_columns(self):
+        file_path = "tests/mbp_w_metadata.bin"
+        buffer = read_file_into_buffer(file_path)
+
+        # Test
+        buffer_obj = BufferStore(bytes(buffer))
+        columns = buffer_obj.decode_to_columns(pretty_ts=False, pretty_px=True)
+
+        # Validate
+        self.assertEqual(list(columns.keys()), [RType.MBP1.value])
+        mbp = columns[RType.MBP1.value]
+        self.assertEqual(mbp["instrument_id"][0], 1)
+        self.assertEqual(mbp["ts_event"][0], 1622471124)
+        self.assertEqual(len(mbp["price"]), len(mbp["symbol"]))
+        self.assertIsInstance(mbp["price"][0], float)
+        self.assertEqual(len(mbp["action"][0]), 1)
+
+    def test_file_decoder(self):
+        file_path = "tests/mbp_w_metadata.bin"
+        expected = BufferStore.from_file(file_path).decode_to_array()
+
+        # Test
+        decoder = PyFileDecoder(file_path)
+        batch = decoder.read_batch(1)
+        rest = [record for record in decoder]
+
+        # Validate
+        self.assertEqual(decoder.metadata.schema, Schema.MBP1)
+        self.assertEqual(len(batch), 1)
+        self.assertEqual(len(batch) + len(rest), len(expected))
+        self.assertEqual(batch[0].ts_event, expected[0].ts_event)
+        self.assertEqual(rest[-1].ts_event, expected[-1].ts_event)
+        self.assertEqual(decoder.read_batch(10), [])
+
+    def test_file_decoder_large_batch(self):
+        file_path = "tests/mbp_w_metadata.bin"
+        expected = BufferStore.from_file(file_path).decode_to_array()
+
+        # Test
+        decoder = PyFileDecoder(file_path)
+        batch = decoder.read_batch(2**62)
+
+        # Validate
+        self.assertEqual(len(batch), len(expected))
+        self.assertEqual(decoder.read_batch(2**62), [])
+
+    def test_file_decoder_filter(self):
+        file_path = "tests/mbp_w_metadata.bin"
+        expected = BufferStore.from_file(file_path).decode_to_array()
+        ts_event = expected[0].ts_event
+        record_filter = (
+            RecordFilter()
+            .instrument_ids([1])
+            .rtypes([RType.MBP1])
+            .start(ts_event)
+            .end(ts_event + 1)
+        )
+
+        # Test
+        decoder = PyFileDecoder(file_path, record_filter)
+        records = [record for record in decoder]
+
+        # Validate
+        self.assertEqual(len(records), len(expected))
+        self.assertTrue(all(record_filter.matches(r.hd) for r in records))
+        self.assertEqual(decoder.metadata.mappings.map, {1: "AAPL"})
+        self.assertEqual(decoder.metadata.end, ts_event + 1)
+
+        for other in [
+            RecordFilter().instrument_ids([2]),
+            RecordFilter().rtypes([RType.OHLCV]),
+            RecordFilter().start(ts_event + 1),
+        ]:
+            self.assertEqual([r for r in PyFileDecoder(file_path, other)], [])
+
+    def test_async_decoder_file(self):
+        file_path = "tests/mbp_w_metadata.bin"
+        expected = BufferStore.from_file(file_path).decode_to_array()
+
+        async def collect():
+            decoder = PyAsyncDecoder.from_file(file_path)
+            metadata = await decoder.metadata()
+            records = [record async for record in decoder]
+            return metadata, records
+
+        # Test
+        metadata, records = asyncio.run(collect())
+
+        # Validate
+        self.assertEqual(metadata.schema, Schema.MBP1)
+        self.assertEqual(len(records), len(expected))
+        self.assertEqual(records[0].ts_event, expected[0].ts_event)
+
+    def test_async_decoder_socket(self):
+        file_path = "tests/mbp_w_metadata.bin"
+        data = read_file_into_buffer(file_path)
+        expected = BufferStore(bytes(data)).decode_to_array()
+        socket_path = os.path.join(tempfile.mkdtemp(), "mbinary.sock")
+
+        async def serve(_reader, writer):
+            writer.write(bytes(data))
+            await writer.drain()
+            writer.close()
+
+        async def collect():
+            server = await asyncio.start_unix_server(serve, path=socket_path)
+            async with server:
+                decoder = PyAsyncDecoder.from_socket(socket_path)
+                return [record async for record in decoder]
+
+        # Test
+        records = asyncio.run(collect())
+
+        # Validate
+        self.assertEqual(len(records), len(expected))
+        self.assertEqual(records[-1].ts_event, expected[-1].ts_event)
+
+        # Cleanup
+        os.remove(socket_path)
+
     def test_decode_replay(self):
         file_path = "tests/mbp_w_metadata.bin"
         buffer = read_file_into_buffer(file_path)
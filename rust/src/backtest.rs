@@ -1,6 +1,8 @@
-use crate::{Error, Result};
+use crate::enums::{deserialize_from_str, OrderType, TradeAction};
+use crate::{Error, Result, PRICE_SCALE};
 use bytemuck;
-use serde::{Deserialize, Serialize};
+use dbn::UNDEF_PRICE;
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::FromRow;
 use std::{io::Read, u16};
 
@@ -8,14 +10,14 @@ use std::{io::Read, u16};
 use pyo3::pyclass;
 
 /// Helper to write a string as 2-byte length-prefixed UTF-8
-fn write_string(buffer: &mut Vec<u8>, string: &str) {
+pub(crate) fn write_string(buffer: &mut Vec<u8>, string: &str) {
     let length = string.len() as u16; // Convert length to u16
     buffer.extend(&length.to_le_bytes()); // Write the 2-byte length
     buffer.extend(string.as_bytes()); // Write the UTF-8 bytes
 }
 
 /// Helper to read a 2-byte length-prefixed UTF-8 string
-fn read_string<R: Read>(cursor: &mut R) -> Result<String> {
+pub(crate) fn read_string<R: Read>(cursor: &mut R) -> Result<String> {
     let mut len_buf = [0u8; 2]; // Buffer to store the 2-byte length
     cursor
         .read_exact(&mut len_buf)
@@ -31,7 +33,7 @@ fn read_string<R: Read>(cursor: &mut R) -> Result<String> {
 }
 
 /// Helper function to read fixed-size data (e.g., i32, i64) from the cursor.
-fn read_fixed<T: Sized + Copy, R: Read>(cursor: &mut R) -> Result<T>
+pub(crate) fn read_fixed<T: Sized + Copy, R: Read>(cursor: &mut R) -> Result<T>
 where
     T: bytemuck::Pod + bytemuck::Zeroable,
 {
@@ -63,6 +65,8 @@ pub struct BacktestData {
     pub daily_timeseries_stats: Vec<TimeseriesStats>,
     pub trades: Vec<Trades>,
     pub signals: Vec<Signals>,
+    #[serde(default)]
+    pub position_timeseries: Vec<PositionStats>,
 }
 
 #[repr(C)]
@@ -199,7 +203,7 @@ impl<R: Read> Decode<R> for Parameters {
     feature = "python",
     pyclass(get_all, set_all, dict, module = "mbinary")
 )]
-#[derive(Deserialize, Serialize, FromRow, Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, FromRow, Debug, Clone, PartialEq, Default)]
 pub struct StaticStats {
     pub total_trades: i32,
     pub total_winning_trades: i32,
@@ -292,6 +296,52 @@ impl<R: Read> Decode<R> for TimeseriesStats {
     }
 }
 
+/// Position in one ticker at a point in time, see `ledger::position_timeseries`.
+#[repr(C)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(get_all, set_all, dict, module = "mbinary")
+)]
+#[derive(Deserialize, Serialize, FromRow, Debug, Clone, PartialEq, Eq)]
+pub struct PositionStats {
+    pub timestamp: i64,
+    pub ticker: String,
+    pub quantity: i64,       // Scaled by 1e9
+    pub avg_cost: i64,       // Scaled by 1e9
+    pub market_price: i64,   // Scaled by 1e9
+    pub market_value: i64,   // Scaled by 1e9
+    pub realized_pnl: i64,   // Scaled by 1e9
+    pub unrealized_pnl: i64, // Scaled by 1e9
+}
+
+impl Encode for PositionStats {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend(&self.timestamp.to_le_bytes());
+        write_string(buffer, &self.ticker);
+        buffer.extend(&self.quantity.to_le_bytes());
+        buffer.extend(&self.avg_cost.to_le_bytes());
+        buffer.extend(&self.market_price.to_le_bytes());
+        buffer.extend(&self.market_value.to_le_bytes());
+        buffer.extend(&self.realized_pnl.to_le_bytes());
+        buffer.extend(&self.unrealized_pnl.to_le_bytes());
+    }
+}
+
+impl<R: Read> Decode<R> for PositionStats {
+    fn decode(cursor: &mut R) -> Result<Self> {
+        Ok(Self {
+            timestamp: read_fixed(cursor)?,
+            ticker: read_string(cursor)?,
+            quantity: read_fixed(cursor)?,
+            avg_cost: read_fixed(cursor)?,
+            market_price: read_fixed(cursor)?,
+            market_value: read_fixed(cursor)?,
+            realized_pnl: read_fixed(cursor)?,
+            unrealized_pnl: read_fixed(cursor)?,
+        })
+    }
+}
+
 #[repr(C)]
 #[cfg_attr(
     feature = "python",
@@ -307,7 +357,9 @@ pub struct Trades {
     pub avg_price: i64,   // Scaled by 1e9
     pub trade_value: i64, // Scaled by 1e9
     pub trade_cost: i64,  // Scaled by 1e9
-    pub action: String,
+    #[sqlx(try_from = "String")]
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub action: TradeAction,
     pub fees: i64, // Scaled by 1e9
 }
 
@@ -321,7 +373,7 @@ impl Encode for Trades {
         buffer.extend(&self.avg_price.to_le_bytes());
         buffer.extend(&self.trade_value.to_le_bytes());
         buffer.extend(&self.trade_cost.to_le_bytes());
-        write_string(buffer, &self.action);
+        buffer.push(self.action.into());
         buffer.extend(&self.fees.to_le_bytes());
     }
 }
@@ -336,7 +388,7 @@ impl<R: Read> Decode<R> for Trades {
         let avg_price: i64 = read_fixed(cursor)?;
         let trade_value: i64 = read_fixed(cursor)?;
         let trade_cost: i64 = read_fixed(cursor)?;
-        let action: String = read_string(cursor)?;
+        let action = read_trade_action(cursor)?;
         let fees: i64 = read_fixed(cursor)?;
 
         Ok(Self {
@@ -407,37 +459,45 @@ impl<R: Read> Decode<R> for Signals {
 #[derive(Deserialize, Serialize, FromRow, Debug, Clone, PartialEq, Eq)]
 pub struct SignalInstructions {
     pub ticker: String,
-    pub order_type: String,
-    pub action: String,
+    #[sqlx(try_from = "String")]
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub order_type: OrderType,
+    #[sqlx(try_from = "String")]
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub action: TradeAction,
     pub signal_id: i32,
     pub weight: i64, // Scaled by 1e9
     pub quantity: i32,
-    pub limit_price: String, // Maybe int scale by 1e9
-    pub aux_price: String,   // Myabe int scale by 1e9
+    #[serde(deserialize_with = "deserialize_price")]
+    pub limit_price: Option<i64>, // Scaled by 1e9
+    #[serde(deserialize_with = "deserialize_price")]
+    pub aux_price: Option<i64>, // Scaled by 1e9
 }
 
 impl Encode for SignalInstructions {
     fn encode(&self, buffer: &mut Vec<u8>) {
         write_string(buffer, &self.ticker);
-        write_string(buffer, &self.order_type);
-        write_string(buffer, &self.action);
+        buffer.push(self.order_type.into());
+        buffer.push(self.action.into());
         buffer.extend(&self.signal_id.to_le_bytes());
         buffer.extend(&self.weight.to_le_bytes());
         buffer.extend(&self.quantity.to_le_bytes());
-        write_string(buffer, &self.limit_price);
-        write_string(buffer, &self.aux_price);
+        write_price(buffer, self.limit_price);
+        write_price(buffer, self.aux_price);
     }
 }
 impl<R: Read> Decode<R> for SignalInstructions {
     fn decode(cursor: &mut R) -> Result<Self> {
         let ticker: String = read_string(cursor)?;
-        let order_type: String = read_string(cursor)?;
-        let action: String = read_string(cursor)?;
+        let order_type: u8 = read_fixed(cursor)?;
+        let order_type = OrderType::try_from(order_type)
+            .map_err(|_| Error::Decode(format!("Unknown OrderType value: {}", order_type)))?;
+        let action = read_trade_action(cursor)?;
         let signal_id: i32 = read_fixed(cursor)?;
         let weight: i64 = read_fixed(cursor)?;
         let quantity: i32 = read_fixed(cursor)?;
-        let limit_price: String = read_string(cursor)?;
-        let aux_price: String = read_string(cursor)?;
+        let limit_price = read_price(cursor)?;
+        let aux_price = read_price(cursor)?;
 
         Ok(Self {
             ticker,
@@ -452,6 +512,188 @@ impl<R: Read> Decode<R> for SignalInstructions {
     }
 }
 
+/// Helper to write an optional fixed-point price, `None` as `UNDEF_PRICE`
+pub(crate) fn write_price(buffer: &mut Vec<u8>, price: Option<i64>) {
+    buffer.extend(&price.unwrap_or(UNDEF_PRICE).to_le_bytes());
+}
+
+/// Helper to read an optional fixed-point price written by `write_price`
+pub(crate) fn read_price<R: Read>(cursor: &mut R) -> Result<Option<i64>> {
+    let price: i64 = read_fixed(cursor)?;
+    Ok((price != UNDEF_PRICE).then_some(price))
+}
+
+fn read_trade_action<R: Read>(cursor: &mut R) -> Result<TradeAction> {
+    let action: u8 = read_fixed(cursor)?;
+    TradeAction::try_from(action)
+        .map_err(|_| Error::Decode(format!("Unknown TradeAction value: {}", action)))
+}
+
+/// Parses a legacy price string, empty meaning no price.
+///
+/// The legacy fields were free-form strings, they are assumed to hold decimal prices in whole
+/// currency units ("101.25") and are scaled to the 1e9 fixed-point convention exactly. Strings
+/// already scaled by 1e9 cannot be told apart and end up scaled twice.
+fn parse_legacy_price(price: &str) -> Result<Option<i64>> {
+    let price = price.trim();
+    if price.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || Error::Decode(format!("Invalid legacy price: '{}'", price));
+
+    let (negative, digits) = match price.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, price.strip_prefix('+').unwrap_or(price)),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if (integer.is_empty() && fraction.is_empty()) || !is_digits(integer) || !is_digits(fraction) {
+        return Err(invalid());
+    }
+
+    // Digits past the 1e9 scale are only accepted when they are zero
+    let (fraction, rest) = fraction.split_at(fraction.len().min(9));
+    if rest.bytes().any(|b| b != b'0') {
+        return Err(invalid());
+    }
+    let integer: i64 = if integer.is_empty() {
+        0
+    } else {
+        integer.parse().map_err(|_| invalid())?
+    };
+    let fraction: i64 = format!("{:0<9}", fraction).parse().map_err(|_| invalid())?;
+
+    let value = integer
+        .checked_mul(PRICE_SCALE)
+        .and_then(|v| v.checked_add(fraction))
+        .ok_or_else(invalid)?;
+    let value = if negative { -value } else { value };
+    if value == UNDEF_PRICE {
+        return Err(invalid());
+    }
+    Ok(Some(value))
+}
+
+/// Deserializes an optional price, also from the legacy JSON strings read by
+/// `parse_legacy_price`, "" meaning no price.
+fn deserialize_price<'de, D>(deserializer: D) -> std::result::Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Price {
+        Fixed(i64),
+        Legacy(String),
+    }
+
+    match Option::<Price>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Price::Fixed(price)) => Ok(Some(price)),
+        Some(Price::Legacy(price)) => parse_legacy_price(&price).map_err(serde::de::Error::custom),
+    }
+}
+
+/// Decodes the string-based layout written before `OrderType`/`TradeAction` were typed.
+pub(crate) struct Legacy<T>(pub T);
+
+impl<R: Read> Decode<R> for Legacy<Trades> {
+    fn decode(cursor: &mut R) -> Result<Self> {
+        Ok(Legacy(Trades {
+            trade_id: read_fixed(cursor)?,
+            signal_id: read_fixed(cursor)?,
+            timestamp: read_fixed(cursor)?,
+            ticker: read_string(cursor)?,
+            quantity: read_fixed(cursor)?,
+            avg_price: read_fixed(cursor)?,
+            trade_value: read_fixed(cursor)?,
+            trade_cost: read_fixed(cursor)?,
+            action: read_string(cursor)?.parse()?,
+            fees: read_fixed(cursor)?,
+        }))
+    }
+}
+
+impl<R: Read> Decode<R> for Legacy<SignalInstructions> {
+    fn decode(cursor: &mut R) -> Result<Self> {
+        Ok(Legacy(SignalInstructions {
+            ticker: read_string(cursor)?,
+            order_type: read_string(cursor)?.parse()?,
+            action: read_string(cursor)?.parse()?,
+            signal_id: read_fixed(cursor)?,
+            weight: read_fixed(cursor)?,
+            quantity: read_fixed(cursor)?,
+            limit_price: parse_legacy_price(&read_string(cursor)?)?,
+            aux_price: parse_legacy_price(&read_string(cursor)?)?,
+        }))
+    }
+}
+
+impl<R: Read> Decode<R> for Legacy<Signals> {
+    fn decode(cursor: &mut R) -> Result<Self> {
+        let timestamp: i64 = read_fixed(cursor)?;
+        let instruction_len: u32 = read_fixed(cursor)?;
+
+        let mut trade_instructions = Vec::new();
+        for _ in 0..instruction_len {
+            trade_instructions.push(Legacy::<SignalInstructions>::decode(cursor)?.0);
+        }
+
+        Ok(Legacy(Signals {
+            timestamp,
+            trade_instructions,
+        }))
+    }
+}
+
+// Writes the legacy string layout so tests can produce files from older versions
+#[cfg(test)]
+impl Encode for Legacy<Trades> {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        let trade = &self.0;
+        buffer.extend(&trade.trade_id.to_le_bytes());
+        buffer.extend(&trade.signal_id.to_le_bytes());
+        buffer.extend(&trade.timestamp.to_le_bytes());
+        write_string(buffer, &trade.ticker);
+        buffer.extend(&trade.quantity.to_le_bytes());
+        buffer.extend(&trade.avg_price.to_le_bytes());
+        buffer.extend(&trade.trade_value.to_le_bytes());
+        buffer.extend(&trade.trade_cost.to_le_bytes());
+        write_string(buffer, trade.action.as_str());
+        buffer.extend(&trade.fees.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+impl Encode for Legacy<Signals> {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        let price = |p: Option<i64>| {
+            p.map(|p| {
+                let sign = if p < 0 { "-" } else { "" };
+                let (integer, fraction) = (
+                    p.unsigned_abs() / 1_000_000_000,
+                    p.unsigned_abs() % 1_000_000_000,
+                );
+                format!("{}{}.{:09}", sign, integer, fraction)
+            })
+            .unwrap_or_default()
+        };
+
+        buffer.extend(&self.0.timestamp.to_le_bytes());
+        buffer.extend(&(self.0.trade_instructions.len() as u32).to_le_bytes());
+        for t in &self.0.trade_instructions {
+            write_string(buffer, &t.ticker);
+            write_string(buffer, t.order_type.as_str());
+            write_string(buffer, t.action.as_str());
+            buffer.extend(&t.signal_id.to_le_bytes());
+            buffer.extend(&t.weight.to_le_bytes());
+            buffer.extend(&t.quantity.to_le_bytes());
+            write_string(buffer, &price(t.limit_price));
+            write_string(buffer, &price(t.aux_price));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -606,7 +848,7 @@ mod tests {
             avg_price: 13074,
             trade_value: -52296,
             trade_cost: -52296,
-            action: "BUY".to_string(),
+            action: TradeAction::Buy,
             fees: 100,
         };
 
@@ -637,13 +879,13 @@ mod tests {
     fn signal_instructions_encode_decode() -> anyhow::Result<()> {
         let instructions = SignalInstructions {
             ticker: "AAPL".to_string(),
-            order_type: "MKT".to_string(),
-            action: "BUY".to_string(),
+            order_type: OrderType::Market,
+            action: TradeAction::Buy,
             signal_id: 1,
             weight: 13213432,
             quantity: 2343,
-            limit_price: "12341".to_string(),
-            aux_price: "1233212".to_string(),
+            limit_price: Some(12341),
+            aux_price: Some(1233212),
         };
 
         let vec: Vec<SignalInstructions> = vec![instructions.clone(), instructions.clone()];
@@ -673,13 +915,13 @@ mod tests {
     fn signals_encode_decode() -> anyhow::Result<()> {
         let instructions = SignalInstructions {
             ticker: "AAPL".to_string(),
-            order_type: "MKT".to_string(),
-            action: "BUY".to_string(),
+            order_type: OrderType::Market,
+            action: TradeAction::Buy,
             signal_id: 1,
             weight: 13213432,
             quantity: 2343,
-            limit_price: "12341".to_string(),
-            aux_price: "1233212".to_string(),
+            limit_price: Some(12341),
+            aux_price: Some(1233212),
         };
 
         let vec: Vec<SignalInstructions> = vec![instructions.clone(), instructions.clone()];
@@ -765,4 +1007,94 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn legacy_signals_decode() -> anyhow::Result<()> {
+        let signal = Signals {
+            timestamp: 1234565432345,
+            trade_instructions: vec![SignalInstructions {
+                ticker: "AAPL".to_string(),
+                order_type: OrderType::StopLimit,
+                action: TradeAction::Short,
+                signal_id: 1,
+                weight: 13213432,
+                quantity: 2343,
+                limit_price: Some(150_500_000_000),
+                aux_price: None,
+            }],
+        };
+        let mut buffer = Vec::new();
+        Legacy(signal.clone()).encode(&mut buffer);
+
+        // Test
+        let decoded = Legacy::<Signals>::decode(&mut buffer.as_slice())?.0;
+
+        // Validate
+        assert_eq!(signal, decoded);
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_json() -> anyhow::Result<()> {
+        let trade = r#"{"trade_id": 1, "signal_id": 1, "timestamp": 1704903000,
+            "ticker": "AAPL", "quantity": 4, "avg_price": 13074, "trade_value": -52296,
+            "trade_cost": -52296, "action": "buy", "fees": 100}"#;
+        let instruction = r#"{"ticker": "AAPL", "order_type": "stp lmt", "action": "LONG",
+            "signal_id": 2, "weight": 13213432, "quantity": 2343, "limit_price": "",
+            "aux_price": "101.25"}"#;
+
+        // Test
+        let trade: Trades = serde_json::from_str(trade)?;
+        let instruction: SignalInstructions = serde_json::from_str(instruction)?;
+
+        // Validate
+        assert_eq!(trade.action, TradeAction::Buy);
+        assert_eq!(instruction.order_type, OrderType::StopLimit);
+        assert_eq!(instruction.action, TradeAction::Buy);
+        assert_eq!(instruction.limit_price, None);
+        assert_eq!(instruction.aux_price, Some(101_250_000_000));
+
+        let round_trip: SignalInstructions =
+            serde_json::from_str(&serde_json::to_string(&instruction)?)?;
+        assert_eq!(round_trip, instruction);
+        let invalid = r#"{"ticker": "AAPL", "order_type": "MKT", "action": "BUY",
+            "signal_id": 2, "weight": 1, "quantity": 1, "limit_price": "NaN", "aux_price": null}"#;
+        assert!(serde_json::from_str::<SignalInstructions>(invalid).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn legacy_price_strings() -> anyhow::Result<()> {
+        // Test
+        let prices = ["12341", "101.25", "", " "].map(parse_legacy_price);
+
+        // Validate
+        assert_eq!(prices[0].as_ref().unwrap(), &Some(12341 * PRICE_SCALE));
+        assert_eq!(prices[1].as_ref().unwrap(), &Some(101_250_000_000));
+        assert_eq!(prices[2].as_ref().unwrap(), &None);
+        assert_eq!(prices[3].as_ref().unwrap(), &None);
+        assert!(parse_legacy_price("abc").is_err());
+        assert_eq!(
+            parse_legacy_price("9223372035.854775807")?,
+            Some(9_223_372_035_854_775_807)
+        );
+        assert_eq!(parse_legacy_price("-0.000000001")?, Some(-1));
+        assert_eq!(parse_legacy_price("1.5000000000")?, Some(1_500_000_000));
+        assert_eq!(parse_legacy_price(".5")?, Some(500_000_000));
+        for invalid in [
+            "NaN",
+            "inf",
+            "-inf",
+            "1e3",
+            ".",
+            "-",
+            "1.0000000001",
+            "9223372037",
+            "1.2.3",
+        ] {
+            assert!(parse_legacy_price(invalid).is_err(), "{}", invalid);
+        }
+        assert!(parse_legacy_price("9223372036.854775807").is_err());
+        Ok(())
+    }
 }
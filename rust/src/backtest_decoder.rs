@@ -1,13 +1,20 @@
+use crate::backtest::BacktestData;
 use crate::backtest::BacktestMetaData;
 use crate::backtest::Decode;
+use crate::backtest::Legacy;
+use crate::backtest::PositionStats;
 use crate::backtest::Signals;
 use crate::backtest::TimeseriesStats;
 use crate::backtest::Trades;
+use crate::backtest_encode::{
+    BacktestSection, BACKTEST_FORMAT_VERSION, BACKTEST_MAGIC, STRING_FIELDS_VERSION,
+};
+use crate::backtest_stream::BacktestFooter;
 use crate::{Error, Result};
-use std::io::Read;
+use std::io::{ErrorKind, Read};
 
 /// Helper function to decode a vector with length prepended
-fn decode_vector<T, R>(reader: &mut R) -> Result<Vec<T>>
+pub(crate) fn decode_vector<T, R>(reader: &mut R) -> Result<Vec<T>>
 where
     R: Read,      // Now works with anything implementing Read
     T: Decode<R>, // T must implement Decode<R>
@@ -19,8 +26,8 @@ where
         .map_err(|_| Error::CustomError("Failed to read vector length".to_string()))?;
     let length = u32::from_le_bytes(length_buf) as usize;
 
-    // Decode each element in the vector
-    let mut result = Vec::with_capacity(length);
+    // Decode each element in the vector, the length is untrusted so capacity is capped
+    let mut result = Vec::with_capacity(length.min(1024));
     for _ in 0..length {
         result.push(T::decode(reader)?);
     }
@@ -30,11 +37,28 @@ where
 
 pub struct BacktestDecoder<R: Read> {
     cursor: R,
+    version: u8,
 }
 
 impl<R: Read> BacktestDecoder<R> {
+    /// Decoder for payloads in the current `BACKTEST_FORMAT_VERSION`.
+    ///
+    /// `decode_backtest` detects the layout itself, the per-section methods need
+    /// `with_version(reader, STRING_FIELDS_VERSION)` for bytes written before the framed format.
     pub fn new(reader: R) -> Self {
-        BacktestDecoder { cursor: reader }
+        BacktestDecoder::with_version(reader, BACKTEST_FORMAT_VERSION)
+    }
+
+    /// Decoder for payloads written by an older `BACKTEST_FORMAT_VERSION`.
+    pub fn with_version(reader: R, version: u8) -> Self {
+        BacktestDecoder {
+            cursor: reader,
+            version,
+        }
+    }
+
+    fn is_legacy(&self) -> bool {
+        self.version <= STRING_FIELDS_VERSION
     }
 
     pub fn decode_metadata(&mut self) -> Result<BacktestMetaData> {
@@ -46,12 +70,185 @@ impl<R: Read> BacktestDecoder<R> {
     }
 
     pub fn decode_trades(&mut self) -> Result<Vec<Trades>> {
+        if self.is_legacy() {
+            let trades: Vec<Legacy<Trades>> = decode_vector(&mut self.cursor)?;
+            return Ok(trades.into_iter().map(|t| t.0).collect());
+        }
         decode_vector(&mut self.cursor)
     }
 
     pub fn decode_signals(&mut self) -> Result<Vec<Signals>> {
+        if self.is_legacy() {
+            let signals: Vec<Legacy<Signals>> = decode_vector(&mut self.cursor)?;
+            return Ok(signals.into_iter().map(|s| s.0).collect());
+        }
+        decode_vector(&mut self.cursor)
+    }
+
+    pub fn decode_trade(&mut self) -> Result<Trades> {
+        if self.is_legacy() {
+            return Ok(Legacy::<Trades>::decode(&mut self.cursor)?.0);
+        }
+        Trades::decode(&mut self.cursor)
+    }
+
+    pub fn decode_signal(&mut self) -> Result<Signals> {
+        if self.is_legacy() {
+            return Ok(Legacy::<Signals>::decode(&mut self.cursor)?.0);
+        }
+        Signals::decode(&mut self.cursor)
+    }
+
+    pub fn decode_positions(&mut self) -> Result<Vec<PositionStats>> {
         decode_vector(&mut self.cursor)
     }
+
+    pub fn decode_position(&mut self) -> Result<PositionStats> {
+        PositionStats::decode(&mut self.cursor)
+    }
+
+    pub fn decode_timeseries_point(&mut self) -> Result<TimeseriesStats> {
+        TimeseriesStats::decode(&mut self.cursor)
+    }
+
+    pub fn decode_footer(&mut self) -> Result<BacktestFooter> {
+        BacktestFooter::decode(&mut self.cursor)
+    }
+
+    /// Reads the next section header, returning `None` at the end of the stream.
+    pub fn decode_section_header(&mut self) -> Result<Option<(u8, u32)>> {
+        let mut id = [0u8; 1];
+        loop {
+            match self.cursor.read(&mut id) {
+                Ok(0) => return Ok(None),
+                Ok(_) => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let mut length_buf = [0u8; 4];
+        self.cursor
+            .read_exact(&mut length_buf)
+            .map_err(|_| Error::Decode("Failed to read section length".to_string()))?;
+        Ok(Some((id[0], u32::from_le_bytes(length_buf))))
+    }
+
+    /// Reads a section payload of `length` bytes.
+    pub fn decode_section_payload(&mut self, length: u32) -> Result<Vec<u8>> {
+        // The length is untrusted, so the buffer only grows with the bytes actually read
+        let mut payload = Vec::new();
+        (&mut self.cursor)
+            .take(length as u64)
+            .read_to_end(&mut payload)
+            .map_err(|_| Error::Decode("Failed to read section payload".to_string()))?;
+        if payload.len() < length as usize {
+            return Err(Error::Decode(format!(
+                "Section payload truncated: expected {} bytes, got {}",
+                length,
+                payload.len()
+            )));
+        }
+        Ok(payload)
+    }
+
+    /// Decodes a complete backtest, either a framed container or the legacy unframed layout.
+    ///
+    /// Sections with unknown ids are skipped.
+    pub fn decode_backtest(&mut self) -> Result<BacktestData> {
+        let mut magic = [0u8; 4];
+        let mut filled = 0;
+        while filled < magic.len() {
+            match self.cursor.read(&mut magic[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+
+        if filled < magic.len() || &magic != BACKTEST_MAGIC {
+            // Legacy layout, replay the bytes consumed while probing for the magic
+            let mut legacy = BacktestDecoder::with_version(
+                (&magic[..filled]).chain(&mut self.cursor),
+                STRING_FIELDS_VERSION,
+            );
+            return Ok(BacktestData {
+                metadata: legacy.decode_metadata()?,
+                period_timeseries_stats: legacy.decode_timeseries()?,
+                daily_timeseries_stats: legacy.decode_timeseries()?,
+                trades: legacy.decode_trades()?,
+                signals: legacy.decode_signals()?,
+                position_timeseries: Vec::new(),
+            });
+        }
+
+        let mut version = [0u8; 1];
+        self.cursor
+            .read_exact(&mut version)
+            .map_err(|_| Error::Decode("Failed to read backtest format version".to_string()))?;
+        if version[0] > BACKTEST_FORMAT_VERSION {
+            return Err(Error::Decode(format!(
+                "Unsupported backtest format version: {}",
+                version[0]
+            )));
+        }
+
+        let mut metadata = None;
+        let mut period_timeseries_stats = Vec::new();
+        let mut daily_timeseries_stats = Vec::new();
+        let mut trades = Vec::new();
+        let mut signals = Vec::new();
+        let mut position_timeseries = Vec::new();
+        let mut footer = None;
+
+        while let Some((id, length)) = self.decode_section_header()? {
+            let payload = self.decode_section_payload(length)?;
+            let mut section = BacktestDecoder::with_version(payload.as_slice(), version[0]);
+
+            match BacktestSection::try_from(id) {
+                Ok(BacktestSection::Metadata) => metadata = Some(section.decode_metadata()?),
+                Ok(BacktestSection::PeriodTimeseries) => {
+                    period_timeseries_stats = section.decode_timeseries()?
+                }
+                Ok(BacktestSection::DailyTimeseries) => {
+                    daily_timeseries_stats = section.decode_timeseries()?
+                }
+                Ok(BacktestSection::Trades) => trades = section.decode_trades()?,
+                Ok(BacktestSection::Signals) => signals = section.decode_signals()?,
+                Ok(BacktestSection::PeriodPoint) => {
+                    period_timeseries_stats.push(section.decode_timeseries_point()?)
+                }
+                Ok(BacktestSection::DailyPoint) => {
+                    daily_timeseries_stats.push(section.decode_timeseries_point()?)
+                }
+                Ok(BacktestSection::Trade) => trades.push(section.decode_trade()?),
+                Ok(BacktestSection::Signal) => signals.push(section.decode_signal()?),
+                Ok(BacktestSection::Footer) => footer = Some(section.decode_footer()?),
+                Ok(BacktestSection::Positions) => {
+                    position_timeseries = section.decode_positions()?
+                }
+                Ok(BacktestSection::PositionPoint) => {
+                    position_timeseries.push(section.decode_position()?)
+                }
+                Err(_) => continue, // Section from a newer writer
+            }
+        }
+
+        let mut metadata =
+            metadata.ok_or_else(|| Error::Decode("Backtest is missing metadata".to_string()))?;
+        if let Some(footer) = footer {
+            // Streamed backtests only know their stats once the run is over
+            metadata.static_stats = footer.static_stats;
+        }
+
+        Ok(BacktestData {
+            metadata,
+            period_timeseries_stats,
+            daily_timeseries_stats,
+            trades,
+            signals,
+            position_timeseries,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -59,10 +256,239 @@ mod tests {
 
     use super::*;
     use crate::{
-        backtest::{BacktestData, Parameters, SignalInstructions, StaticStats},
-        backtest_encode::BacktestEncoder,
+        backtest::{Parameters, SignalInstructions, StaticStats},
+        backtest_encode::{encode_vector, BacktestEncoder},
+        enums::{OrderType, TradeAction},
     };
 
+    fn legacy<T: Clone>(items: &[T]) -> Vec<Legacy<T>> {
+        items.iter().cloned().map(Legacy).collect()
+    }
+
+    fn backtest_data() -> BacktestData {
+        let params = Parameters {
+            strategy_name: "Testing".to_string(),
+            capital: 10000,
+            schema: "Ohlcv-1s".to_string(),
+            data_type: "BAR".to_string(),
+            start: 1730160814000000000,
+            end: 1730160814000000000,
+            tickers: vec!["HE.n.0".to_string(), "AAPL".to_string()],
+        };
+        let static_stats = StaticStats {
+            total_trades: 100,
+            total_winning_trades: 50,
+            total_losing_trades: 50,
+            net_profit: 1234323,
+            sharpe_ratio: 23432343,
+            ..Default::default()
+        };
+        let timeseries = TimeseriesStats {
+            timestamp: 123700000000000,
+            equity_value: 9999999,
+            percent_drawdown: 2343234,
+            cumulative_return: 2343234,
+            period_return: 2345432345,
+        };
+        let trade = Trades {
+            trade_id: 1,
+            signal_id: 1,
+            timestamp: 1704903000,
+            ticker: "AAPL".to_string(),
+            quantity: 4,
+            avg_price: 13074,
+            trade_value: -52296,
+            trade_cost: -52296,
+            action: TradeAction::Buy,
+            fees: 100,
+        };
+        let instructions = SignalInstructions {
+            ticker: "AAPL".to_string(),
+            order_type: OrderType::Market,
+            action: TradeAction::Buy,
+            signal_id: 2,
+            weight: 13213432,
+            quantity: 2343,
+            limit_price: Some(12341),
+            aux_price: Some(1233212),
+        };
+
+        BacktestData {
+            metadata: BacktestMetaData::new(Some(3), "testing", params, static_stats),
+            period_timeseries_stats: vec![timeseries.clone(), timeseries.clone()],
+            daily_timeseries_stats: vec![timeseries],
+            trades: vec![trade],
+            signals: vec![Signals {
+                timestamp: 1234565432345,
+                trade_instructions: vec![instructions],
+            }],
+            position_timeseries: vec![],
+        }
+    }
+
+    #[test]
+    fn decode_backtest_framed() -> anyhow::Result<()> {
+        let backtest = backtest_data();
+        let mut bytes = Vec::new();
+        BacktestEncoder::new(&mut bytes).encode_backtest(&backtest);
+
+        // Test
+        let decoded = BacktestDecoder::new(bytes.as_slice()).decode_backtest()?;
+
+        // Validate
+        assert_eq!(backtest, decoded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_backtest_skips_unknown_sections() -> anyhow::Result<()> {
+        let backtest = backtest_data();
+        let mut bytes = Vec::new();
+        let mut encoder = BacktestEncoder::new(&mut bytes);
+        encoder.encode_backtest(&backtest);
+
+        // Append a section a future writer might add
+        bytes.push(200);
+        bytes.extend(&3u32.to_le_bytes());
+        bytes.extend([1, 2, 3]);
+
+        // Test
+        let decoded = BacktestDecoder::new(bytes.as_slice()).decode_backtest()?;
+
+        // Validate
+        assert_eq!(backtest, decoded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_backtest_legacy() -> anyhow::Result<()> {
+        let backtest = backtest_data();
+        let mut bytes = Vec::new();
+        let mut encoder = BacktestEncoder::new(&mut bytes);
+        encoder.encode_metadata(&backtest.metadata);
+        encoder.encode_timeseries(&backtest.period_timeseries_stats);
+        encoder.encode_timeseries(&backtest.daily_timeseries_stats);
+        encode_vector(&legacy(&backtest.trades), &mut bytes);
+        encode_vector(&legacy(&backtest.signals), &mut bytes);
+
+        // Test
+        let decoded = BacktestDecoder::new(bytes.as_slice()).decode_backtest()?;
+
+        // Validate
+        assert_eq!(backtest, decoded);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_sections_legacy() -> anyhow::Result<()> {
+        let backtest = backtest_data();
+        let mut bytes = Vec::new();
+        let mut encoder = BacktestEncoder::new(&mut bytes);
+        encoder.encode_metadata(&backtest.metadata);
+        encoder.encode_timeseries(&backtest.period_timeseries_stats);
+        encoder.encode_timeseries(&backtest.daily_timeseries_stats);
+        encode_vector(&legacy(&backtest.trades), &mut bytes);
+        encode_vector(&legacy(&backtest.signals), &mut bytes);
+
+        // Test
+        let mut decoder = BacktestDecoder::with_version(bytes.as_slice(), STRING_FIELDS_VERSION);
+        let metadata = decoder.decode_metadata()?;
+        let period = decoder.decode_timeseries()?;
+        let daily = decoder.decode_timeseries()?;
+        let trades = decoder.decode_trades()?;
+        let signals = decoder.decode_signals()?;
+
+        // Validate
+        assert_eq!(metadata, backtest.metadata);
+        assert_eq!(period, backtest.period_timeseries_stats);
+        assert_eq!(daily, backtest.daily_timeseries_stats);
+        assert_eq!(trades, backtest.trades);
+        assert_eq!(signals, backtest.signals);
+        Ok(())
+    }
+
+    #[test]
+    fn decode_backtest_framed_v1() -> anyhow::Result<()> {
+        let backtest = backtest_data();
+        let mut bytes = BACKTEST_MAGIC.to_vec();
+        bytes.push(STRING_FIELDS_VERSION);
+        let mut encoder = BacktestEncoder::new(&mut bytes);
+        encoder.encode_section(BacktestSection::Metadata, |e| {
+            e.encode_metadata(&backtest.metadata)
+        });
+        encoder.encode_section(BacktestSection::Trade, |e| {
+            e.encode_item(&legacy(&backtest.trades)[0])
+        });
+        encoder.encode_section(BacktestSection::Signal, |e| {
+            e.encode_item(&legacy(&backtest.signals)[0])
+        });
+
+        // Test
+        let decoded = BacktestDecoder::new(bytes.as_slice()).decode_backtest()?;
+
+        // Validate
+        assert_eq!(decoded.trades, backtest.trades);
+        assert_eq!(decoded.signals, backtest.signals);
+        Ok(())
+    }
+
+    #[test]
+    fn decode_backtest_positions() -> anyhow::Result<()> {
+        let mut backtest = backtest_data();
+        backtest.position_timeseries = vec![PositionStats {
+            timestamp: 1704903000,
+            ticker: "AAPL".to_string(),
+            quantity: 4,
+            avg_cost: 13074,
+            market_price: 13100,
+            market_value: 52400,
+            realized_pnl: -100,
+            unrealized_pnl: 104,
+        }];
+        let mut bytes = Vec::new();
+        BacktestEncoder::new(&mut bytes).encode_backtest(&backtest);
+
+        // Test
+        let decoded = BacktestDecoder::new(bytes.as_slice()).decode_backtest()?;
+
+        // Validate
+        assert_eq!(backtest, decoded);
+        Ok(())
+    }
+
+    #[test]
+    fn decode_backtest_missing_metadata() -> anyhow::Result<()> {
+        let mut bytes = BACKTEST_MAGIC.to_vec();
+        bytes.push(BACKTEST_FORMAT_VERSION);
+
+        // Test
+        let result = BacktestDecoder::new(bytes.as_slice()).decode_backtest();
+
+        // Validate
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_backtest_truncated_section() -> anyhow::Result<()> {
+        let mut bytes = BACKTEST_MAGIC.to_vec();
+        bytes.push(BACKTEST_FORMAT_VERSION);
+        bytes.push(BacktestSection::Metadata as u8);
+        bytes.extend(&u32::MAX.to_le_bytes());
+        bytes.extend([1, 2, 3]);
+
+        // Test
+        let result = BacktestDecoder::new(bytes.as_slice()).decode_backtest();
+
+        // Validate
+        assert!(result.unwrap_err().to_string().contains("truncated"));
+        Ok(())
+    }
+
     #[test]
     fn backtestencoder() -> anyhow::Result<()> {
         let params = Parameters {
@@ -122,7 +548,7 @@ mod tests {
             avg_price: 13074,
             trade_value: -52296,
             trade_cost: -52296,
-            action: "BUY".to_string(),
+            action: TradeAction::Buy,
             fees: 100,
         };
 
@@ -130,13 +556,13 @@ mod tests {
 
         let instructions = SignalInstructions {
             ticker: "AAPL".to_string(),
-            order_type: "MKT".to_string(),
-            action: "BUY".to_string(),
+            order_type: OrderType::Market,
+            action: TradeAction::Buy,
             signal_id: 2,
             weight: 13213432,
             quantity: 2343,
-            limit_price: "12341".to_string(),
-            aux_price: "1233212".to_string(),
+            limit_price: Some(12341),
+            aux_price: Some(1233212),
         };
 
         let vec: Vec<SignalInstructions> = vec![instructions.clone(), instructions.clone()];
@@ -152,6 +578,7 @@ mod tests {
             period_timeseries_stats: stats,
             trades,
             signals,
+            position_timeseries: vec![],
         };
 
         // Encode
@@ -179,6 +606,7 @@ mod tests {
             daily_timeseries_stats: daily_stats,
             trades,
             signals,
+            position_timeseries: vec![],
         };
 
         // Validate
@@ -1,11 +1,45 @@
+use crate::backtest::BacktestData;
 use crate::backtest::BacktestMetaData;
 use crate::backtest::Encode;
+use crate::backtest::PositionStats;
 use crate::backtest::Signals;
 use crate::backtest::TimeseriesStats;
 use crate::backtest::Trades;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// Leading bytes of a framed backtest container.
+pub const BACKTEST_MAGIC: &[u8; 4] = b"MBBT";
+
+/// Version of the framed backtest layout, written after `BACKTEST_MAGIC`.
+pub const BACKTEST_FORMAT_VERSION: u8 = 2;
+
+/// Last backtest format version storing trade actions, order types and signal prices as
+/// strings, also the layout of unframed backtests written before `BACKTEST_MAGIC`.
+pub const STRING_FIELDS_VERSION: u8 = 1;
+
+/// Tags of the sections in a framed backtest, each followed by a u32 payload length.
+///
+/// `PeriodPoint` through `Footer` and `PositionPoint` hold single items appended by the
+/// streaming writer.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TryFromPrimitive, IntoPrimitive)]
+pub enum BacktestSection {
+    Metadata = 1,
+    PeriodTimeseries = 2,
+    DailyTimeseries = 3,
+    Trades = 4,
+    Signals = 5,
+    PeriodPoint = 6,
+    DailyPoint = 7,
+    Trade = 8,
+    Signal = 9,
+    Footer = 10,
+    Positions = 11,
+    PositionPoint = 12,
+}
 
 /// Helper function to encode a vector with length prepended
-fn encode_vector<T: Encode>(vec: &[T], buffer: &mut Vec<u8>) {
+pub(crate) fn encode_vector<T: Encode>(vec: &[T], buffer: &mut Vec<u8>) {
     // Prepend vector length as u32
     let length = vec.len() as u32;
     buffer.extend(&length.to_le_bytes());
@@ -39,11 +73,63 @@ impl<'a> BacktestEncoder<'a> {
     pub fn encode_signals(&mut self, vec: &Vec<Signals>) {
         encode_vector(vec, self.buffer);
     }
+
+    pub fn encode_positions(&mut self, positions: &[PositionStats]) {
+        encode_vector(positions, self.buffer);
+    }
+
+    /// Encodes a single item, used for the sections appended by the streaming writer.
+    pub fn encode_item<T: Encode>(&mut self, item: &T) {
+        item.encode(self.buffer);
+    }
+
+    /// Writes a section tag and length around whatever `encode` appends to the buffer.
+    pub fn encode_section<F>(&mut self, section: BacktestSection, encode: F)
+    where
+        F: FnOnce(&mut BacktestEncoder),
+    {
+        self.buffer.push(section.into());
+        let length_at = self.buffer.len();
+        self.buffer.extend(&0u32.to_le_bytes());
+
+        encode(self);
+
+        let length = (self.buffer.len() - length_at - 4) as u32;
+        self.buffer[length_at..length_at + 4].copy_from_slice(&length.to_le_bytes());
+    }
+
+    /// Encodes a complete backtest as a framed container.
+    pub fn encode_backtest(&mut self, backtest: &BacktestData) {
+        self.buffer.extend(BACKTEST_MAGIC);
+        self.buffer.push(BACKTEST_FORMAT_VERSION);
+
+        self.encode_section(BacktestSection::Metadata, |e| {
+            e.encode_metadata(&backtest.metadata)
+        });
+        self.encode_section(BacktestSection::PeriodTimeseries, |e| {
+            e.encode_timeseries(&backtest.period_timeseries_stats)
+        });
+        self.encode_section(BacktestSection::DailyTimeseries, |e| {
+            e.encode_timeseries(&backtest.daily_timeseries_stats)
+        });
+        self.encode_section(BacktestSection::Trades, |e| {
+            e.encode_trades(&backtest.trades)
+        });
+        self.encode_section(BacktestSection::Signals, |e| {
+            e.encode_signals(&backtest.signals)
+        });
+        if !backtest.position_timeseries.is_empty() {
+            self.encode_section(BacktestSection::Positions, |e| {
+                e.encode_positions(&backtest.position_timeseries)
+            });
+        }
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::backtest::{BacktestData, Parameters, SignalInstructions, StaticStats};
+    use crate::enums::{OrderType, TradeAction};
 
     #[test]
     fn backtestencoder() -> anyhow::Result<()> {
@@ -104,7 +190,7 @@ mod tests {
             avg_price: 13074,
             trade_value: -52296,
             trade_cost: -52296,
-            action: "BUY".to_string(),
+            action: TradeAction::Buy,
             fees: 100,
         };
 
@@ -112,13 +198,13 @@ mod tests {
 
         let instructions = SignalInstructions {
             ticker: "AAPL".to_string(),
-            order_type: "MKT".to_string(),
-            action: "BUY".to_string(),
+            order_type: OrderType::Market,
+            action: TradeAction::Buy,
             signal_id: 2,
             weight: 13213432,
             quantity: 2343,
-            limit_price: "12341".to_string(),
-            aux_price: "1233212".to_string(),
+            limit_price: Some(12341),
+            aux_price: Some(1233212),
         };
 
         let vec: Vec<SignalInstructions> = vec![instructions.clone(), instructions.clone()];
@@ -134,6 +220,7 @@ mod tests {
             period_timeseries_stats: stats,
             trades,
             signals,
+            position_timeseries: vec![],
         };
 
         // Encode
@@ -150,4 +237,42 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn backtestencoder_framed() -> anyhow::Result<()> {
+        let params = Parameters {
+            strategy_name: "Testing".to_string(),
+            capital: 10000,
+            schema: "Ohlcv-1s".to_string(),
+            data_type: "BAR".to_string(),
+            start: 1730160814000000000,
+            end: 1730160814000000000,
+            tickers: vec!["AAPL".to_string()],
+        };
+        let backtest = BacktestData {
+            metadata: BacktestMetaData::new(None, "testing", params, StaticStats::default()),
+            period_timeseries_stats: vec![],
+            daily_timeseries_stats: vec![],
+            trades: vec![],
+            signals: vec![],
+            position_timeseries: vec![],
+        };
+
+        // Test
+        let mut bytes = Vec::new();
+        let mut encoder = BacktestEncoder::new(&mut bytes);
+        encoder.encode_backtest(&backtest);
+
+        // Validate
+        assert_eq!(&bytes[..4], BACKTEST_MAGIC);
+        assert_eq!(bytes[4], BACKTEST_FORMAT_VERSION);
+        assert_eq!(bytes[5], u8::from(BacktestSection::Metadata));
+        let length = u32::from_le_bytes(bytes[6..10].try_into()?) as usize;
+        assert_eq!(
+            bytes[10 + length],
+            u8::from(BacktestSection::PeriodTimeseries)
+        );
+
+        Ok(())
+    }
 }
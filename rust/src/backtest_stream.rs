@@ -0,0 +1,576 @@
+use crate::backtest::{
+    read_fixed, BacktestMetaData, Decode, Encode, PositionStats, Signals, StaticStats,
+    TimeseriesStats, Trades,
+};
+use crate::backtest_decoder::BacktestDecoder;
+use crate::backtest_encode::{
+    BacktestEncoder, BacktestSection, BACKTEST_FORMAT_VERSION, BACKTEST_MAGIC,
+};
+use crate::{Error, Result};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Closing section of a streamed backtest, written once the run is over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestFooter {
+    pub period_count: u64,
+    pub daily_count: u64,
+    pub trade_count: u64,
+    pub signal_count: u64,
+    pub static_stats: StaticStats,
+}
+
+impl Encode for BacktestFooter {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend(&self.period_count.to_le_bytes());
+        buffer.extend(&self.daily_count.to_le_bytes());
+        buffer.extend(&self.trade_count.to_le_bytes());
+        buffer.extend(&self.signal_count.to_le_bytes());
+        self.static_stats.encode(buffer);
+    }
+}
+
+impl<R: Read> Decode<R> for BacktestFooter {
+    fn decode(cursor: &mut R) -> Result<Self> {
+        Ok(BacktestFooter {
+            period_count: read_fixed(cursor)?,
+            daily_count: read_fixed(cursor)?,
+            trade_count: read_fixed(cursor)?,
+            signal_count: read_fixed(cursor)?,
+            static_stats: StaticStats::decode(cursor)?,
+        })
+    }
+}
+
+/// Tracks what has been written so the footer can report it.
+#[derive(Debug, Default)]
+struct StreamCounts {
+    period_count: u64,
+    daily_count: u64,
+    trade_count: u64,
+    signal_count: u64,
+}
+
+impl StreamCounts {
+    fn footer(&self, static_stats: &StaticStats) -> BacktestFooter {
+        BacktestFooter {
+            period_count: self.period_count,
+            daily_count: self.daily_count,
+            trade_count: self.trade_count,
+            signal_count: self.signal_count,
+            static_stats: static_stats.clone(),
+        }
+    }
+}
+
+/// Encodes the magic, format version and metadata section that open every stream.
+fn stream_header(buffer: &mut Vec<u8>, metadata: &BacktestMetaData) {
+    buffer.extend(BACKTEST_MAGIC);
+    buffer.push(BACKTEST_FORMAT_VERSION);
+    BacktestEncoder::new(buffer)
+        .encode_section(BacktestSection::Metadata, |e| e.encode_metadata(metadata));
+}
+
+/// Replaces the contents of `buffer` with `item` framed as a single-item section.
+fn frame_item<T: Encode>(buffer: &mut Vec<u8>, section: BacktestSection, item: &T) {
+    buffer.clear();
+    BacktestEncoder::new(buffer).encode_section(section, |e| e.encode_item(item));
+}
+
+/// Appends backtest output to a writer as it is produced, so a long run never holds its
+/// trades, signals or timeseries in memory.
+///
+/// The result is a framed backtest readable by `BacktestDecoder::decode_backtest` or
+/// lazily by `BacktestStreamReader`. Wrap unbuffered writers in a `BufWriter`.
+pub struct BacktestStreamWriter<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+    counts: StreamCounts,
+}
+
+impl<W: Write> BacktestStreamWriter<W> {
+    /// Writes the stream header and metadata. The metadata `static_stats` are superseded
+    /// by the ones passed to `finish`.
+    pub fn new(mut writer: W, metadata: &BacktestMetaData) -> Result<Self> {
+        let mut buffer = Vec::new();
+        stream_header(&mut buffer, metadata);
+        writer.write_all(&buffer)?;
+
+        Ok(BacktestStreamWriter {
+            writer,
+            buffer,
+            counts: StreamCounts::default(),
+        })
+    }
+
+    fn write_item<T: Encode>(&mut self, section: BacktestSection, item: &T) -> Result<()> {
+        frame_item(&mut self.buffer, section, item);
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    pub fn write_period_point(&mut self, point: &TimeseriesStats) -> Result<()> {
+        self.write_item(BacktestSection::PeriodPoint, point)?;
+        self.counts.period_count += 1;
+        Ok(())
+    }
+
+    pub fn write_daily_point(&mut self, point: &TimeseriesStats) -> Result<()> {
+        self.write_item(BacktestSection::DailyPoint, point)?;
+        self.counts.daily_count += 1;
+        Ok(())
+    }
+
+    pub fn write_trade(&mut self, trade: &Trades) -> Result<()> {
+        self.write_item(BacktestSection::Trade, trade)?;
+        self.counts.trade_count += 1;
+        Ok(())
+    }
+
+    pub fn write_signal(&mut self, signal: &Signals) -> Result<()> {
+        self.write_item(BacktestSection::Signal, signal)?;
+        self.counts.signal_count += 1;
+        Ok(())
+    }
+
+    /// Appends a point of the position timeseries, which the footer does not count.
+    pub fn write_position(&mut self, position: &PositionStats) -> Result<()> {
+        self.write_item(BacktestSection::PositionPoint, position)
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Writes the footer with the item counts and final stats, returning the writer.
+    pub fn finish(mut self, static_stats: &StaticStats) -> Result<W> {
+        let footer = self.counts.footer(static_stats);
+        self.write_item(BacktestSection::Footer, &footer)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Async counterpart of `BacktestStreamWriter`.
+pub struct AsyncBacktestStreamWriter<W: AsyncWrite + Unpin> {
+    writer: W,
+    buffer: Vec<u8>,
+    counts: StreamCounts,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncBacktestStreamWriter<W> {
+    pub async fn new(mut writer: W, metadata: &BacktestMetaData) -> Result<Self> {
+        let mut buffer = Vec::new();
+        stream_header(&mut buffer, metadata);
+        writer.write_all(&buffer).await?;
+
+        Ok(AsyncBacktestStreamWriter {
+            writer,
+            buffer,
+            counts: StreamCounts::default(),
+        })
+    }
+
+    async fn write_item<T: Encode>(&mut self, section: BacktestSection, item: &T) -> Result<()> {
+        frame_item(&mut self.buffer, section, item);
+        self.writer.write_all(&self.buffer).await?;
+        Ok(())
+    }
+
+    pub async fn write_period_point(&mut self, point: &TimeseriesStats) -> Result<()> {
+        self.write_item(BacktestSection::PeriodPoint, point).await?;
+        self.counts.period_count += 1;
+        Ok(())
+    }
+
+    pub async fn write_daily_point(&mut self, point: &TimeseriesStats) -> Result<()> {
+        self.write_item(BacktestSection::DailyPoint, point).await?;
+        self.counts.daily_count += 1;
+        Ok(())
+    }
+
+    pub async fn write_trade(&mut self, trade: &Trades) -> Result<()> {
+        self.write_item(BacktestSection::Trade, trade).await?;
+        self.counts.trade_count += 1;
+        Ok(())
+    }
+
+    pub async fn write_signal(&mut self, signal: &Signals) -> Result<()> {
+        self.write_item(BacktestSection::Signal, signal).await?;
+        self.counts.signal_count += 1;
+        Ok(())
+    }
+
+    pub async fn write_position(&mut self, position: &PositionStats) -> Result<()> {
+        self.write_item(BacktestSection::PositionPoint, position)
+            .await
+    }
+
+    pub async fn flush(&mut self) -> Result<()> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    pub async fn finish(mut self, static_stats: &StaticStats) -> Result<W> {
+        let footer = self.counts.footer(static_stats);
+        self.write_item(BacktestSection::Footer, &footer).await?;
+        self.writer.flush().await?;
+        Ok(self.writer)
+    }
+}
+
+/// An item read back from a framed backtest.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BacktestItem {
+    PeriodPoint(TimeseriesStats),
+    DailyPoint(TimeseriesStats),
+    Trade(Trades),
+    Signal(Signals),
+    Position(PositionStats),
+    Footer(BacktestFooter),
+}
+
+/// Reads a framed backtest one section at a time, yielding items as they are decoded.
+///
+/// Works on streamed output and on containers written by `encode_backtest`, whose
+/// whole-vector sections are expanded into individual items. Unknown sections are skipped.
+pub struct BacktestStreamReader<R: Read> {
+    decoder: BacktestDecoder<R>,
+    version: u8,
+    metadata: BacktestMetaData,
+    pending: VecDeque<BacktestItem>,
+}
+
+impl<R: Read> BacktestStreamReader<R> {
+    /// Reads the stream header and metadata, leaving the remaining sections unread.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut magic = [0u8; 4];
+        reader
+            .read_exact(&mut magic)
+            .map_err(|_| Error::Decode("Failed to read backtest magic".to_string()))?;
+        if &magic != BACKTEST_MAGIC {
+            return Err(Error::Decode("Not a framed backtest".to_string()));
+        }
+
+        let version: u8 = read_fixed(&mut reader)?;
+        if version > BACKTEST_FORMAT_VERSION {
+            return Err(Error::Decode(format!(
+                "Unsupported backtest format version: {}",
+                version
+            )));
+        }
+
+        let mut decoder = BacktestDecoder::new(reader);
+        let metadata = match decoder.decode_section_header()? {
+            Some((id, length)) if id == u8::from(BacktestSection::Metadata) => {
+                let payload = decoder.decode_section_payload(length)?;
+                BacktestMetaData::decode(&mut payload.as_slice())?
+            }
+            _ => return Err(Error::Decode("Backtest is missing metadata".to_string())),
+        };
+
+        Ok(BacktestStreamReader {
+            decoder,
+            version,
+            metadata,
+            pending: VecDeque::new(),
+        })
+    }
+
+    pub fn metadata(&self) -> &BacktestMetaData {
+        &self.metadata
+    }
+
+    /// Decodes sections until at least one item is pending, returning `false` at the end.
+    fn fill_pending(&mut self) -> Result<bool> {
+        while self.pending.is_empty() {
+            let Some((id, length)) = self.decoder.decode_section_header()? else {
+                return Ok(false);
+            };
+            let payload = self.decoder.decode_section_payload(length)?;
+            let mut section = BacktestDecoder::with_version(payload.as_slice(), self.version);
+
+            match BacktestSection::try_from(id) {
+                Ok(BacktestSection::PeriodTimeseries) => self.pending.extend(
+                    section
+                        .decode_timeseries()?
+                        .into_iter()
+                        .map(BacktestItem::PeriodPoint),
+                ),
+                Ok(BacktestSection::DailyTimeseries) => self.pending.extend(
+                    section
+                        .decode_timeseries()?
+                        .into_iter()
+                        .map(BacktestItem::DailyPoint),
+                ),
+                Ok(BacktestSection::Trades) => self.pending.extend(
+                    section
+                        .decode_trades()?
+                        .into_iter()
+                        .map(BacktestItem::Trade),
+                ),
+                Ok(BacktestSection::Signals) => self.pending.extend(
+                    section
+                        .decode_signals()?
+                        .into_iter()
+                        .map(BacktestItem::Signal),
+                ),
+                Ok(BacktestSection::PeriodPoint) => self.pending.push_back(
+                    BacktestItem::PeriodPoint(section.decode_timeseries_point()?),
+                ),
+                Ok(BacktestSection::DailyPoint) => self
+                    .pending
+                    .push_back(BacktestItem::DailyPoint(section.decode_timeseries_point()?)),
+                Ok(BacktestSection::Trade) => self
+                    .pending
+                    .push_back(BacktestItem::Trade(section.decode_trade()?)),
+                Ok(BacktestSection::Signal) => self
+                    .pending
+                    .push_back(BacktestItem::Signal(section.decode_signal()?)),
+                Ok(BacktestSection::Positions) => self.pending.extend(
+                    section
+                        .decode_positions()?
+                        .into_iter()
+                        .map(BacktestItem::Position),
+                ),
+                Ok(BacktestSection::PositionPoint) => self
+                    .pending
+                    .push_back(BacktestItem::Position(section.decode_position()?)),
+                Ok(BacktestSection::Footer) => self
+                    .pending
+                    .push_back(BacktestItem::Footer(section.decode_footer()?)),
+                // A second metadata section or one from a newer writer
+                Ok(BacktestSection::Metadata) | Err(_) => continue,
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns the next item, or `None` once the stream is exhausted.
+    pub fn next_item(&mut self) -> Result<Option<BacktestItem>> {
+        if !self.fill_pending()? {
+            return Ok(None);
+        }
+        Ok(self.pending.pop_front())
+    }
+
+    /// Iterates only the trades, consuming the other items.
+    pub fn trades(&mut self) -> impl Iterator<Item = Result<Trades>> + '_ {
+        self.filter_map(|item| match item {
+            Ok(BacktestItem::Trade(trade)) => Some(Ok(trade)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Iterates only the signals, consuming the other items.
+    pub fn signals(&mut self) -> impl Iterator<Item = Result<Signals>> + '_ {
+        self.filter_map(|item| match item {
+            Ok(BacktestItem::Signal(signal)) => Some(Ok(signal)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Iterates only the period timeseries points, consuming the other items.
+    pub fn period_points(&mut self) -> impl Iterator<Item = Result<TimeseriesStats>> + '_ {
+        self.filter_map(|item| match item {
+            Ok(BacktestItem::PeriodPoint(point)) => Some(Ok(point)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Iterates only the daily timeseries points, consuming the other items.
+    pub fn daily_points(&mut self) -> impl Iterator<Item = Result<TimeseriesStats>> + '_ {
+        self.filter_map(|item| match item {
+            Ok(BacktestItem::DailyPoint(point)) => Some(Ok(point)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+}
+
+impl<R: Read> Iterator for BacktestStreamReader<R> {
+    type Item = Result<BacktestItem>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_item().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::{BacktestData, Parameters, SignalInstructions};
+    use crate::backtest_decoder::BacktestDecoder;
+    use crate::enums::{OrderType, TradeAction};
+
+    fn metadata() -> BacktestMetaData {
+        let params = Parameters {
+            strategy_name: "Testing".to_string(),
+            capital: 10000,
+            schema: "Ohlcv-1s".to_string(),
+            data_type: "BAR".to_string(),
+            start: 1730160814000000000,
+            end: 1730160814000000000,
+            tickers: vec!["AAPL".to_string()],
+        };
+        BacktestMetaData::new(None, "streamed", params, StaticStats::default())
+    }
+
+    fn point(timestamp: i64) -> TimeseriesStats {
+        TimeseriesStats {
+            timestamp,
+            equity_value: 9999999,
+            percent_drawdown: 2343234,
+            cumulative_return: 2343234,
+            period_return: 2345432345,
+        }
+    }
+
+    fn trade(trade_id: i32) -> Trades {
+        Trades {
+            trade_id,
+            signal_id: 1,
+            timestamp: 1704903000,
+            ticker: "AAPL".to_string(),
+            quantity: 4,
+            avg_price: 13074,
+            trade_value: -52296,
+            trade_cost: -52296,
+            action: TradeAction::Buy,
+            fees: 100,
+        }
+    }
+
+    fn signal() -> Signals {
+        Signals {
+            timestamp: 1234565432345,
+            trade_instructions: vec![SignalInstructions {
+                ticker: "AAPL".to_string(),
+                order_type: OrderType::Market,
+                action: TradeAction::Buy,
+                signal_id: 1,
+                weight: 13213432,
+                quantity: 2343,
+                limit_price: None,
+                aux_price: None,
+            }],
+        }
+    }
+
+    fn final_stats() -> StaticStats {
+        StaticStats {
+            total_trades: 2,
+            net_profit: 1234323,
+            ..Default::default()
+        }
+    }
+
+    fn streamed() -> anyhow::Result<Vec<u8>> {
+        let mut writer = BacktestStreamWriter::new(Vec::new(), &metadata())?;
+        writer.write_signal(&signal())?;
+        writer.write_trade(&trade(1))?;
+        writer.write_period_point(&point(1))?;
+        writer.write_trade(&trade(2))?;
+        writer.write_period_point(&point(2))?;
+        writer.write_daily_point(&point(2))?;
+        Ok(writer.finish(&final_stats())?)
+    }
+
+    #[test]
+    fn test_stream_reader() -> anyhow::Result<()> {
+        let bytes = streamed()?;
+
+        // Test
+        let mut reader = BacktestStreamReader::new(bytes.as_slice())?;
+        let items: Vec<BacktestItem> = reader.by_ref().collect::<Result<_>>()?;
+
+        // Validate
+        assert_eq!(reader.metadata().backtest_name, "streamed");
+        assert_eq!(items.len(), 7);
+        assert_eq!(items[1], BacktestItem::Trade(trade(1)));
+        assert_eq!(
+            items[6],
+            BacktestItem::Footer(BacktestFooter {
+                period_count: 2,
+                daily_count: 1,
+                trade_count: 2,
+                signal_count: 1,
+                static_stats: final_stats(),
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_reader_trades() -> anyhow::Result<()> {
+        let bytes = streamed()?;
+        let mut reader = BacktestStreamReader::new(bytes.as_slice())?;
+
+        // Test
+        let trades: Vec<Trades> = reader.trades().collect::<Result<_>>()?;
+
+        // Validate
+        assert_eq!(trades, vec![trade(1), trade(2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_streamed_backtest() -> anyhow::Result<()> {
+        let bytes = streamed()?;
+
+        // Test
+        let backtest = BacktestDecoder::new(bytes.as_slice()).decode_backtest()?;
+
+        // Validate
+        assert_eq!(backtest.metadata.static_stats, final_stats());
+        assert_eq!(backtest.period_timeseries_stats, vec![point(1), point(2)]);
+        assert_eq!(backtest.daily_timeseries_stats, vec![point(2)]);
+        assert_eq!(backtest.trades, vec![trade(1), trade(2)]);
+        assert_eq!(backtest.signals, vec![signal()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_reader_framed_container() -> anyhow::Result<()> {
+        let backtest = BacktestData {
+            metadata: metadata(),
+            period_timeseries_stats: vec![point(1), point(2)],
+            daily_timeseries_stats: vec![],
+            trades: vec![trade(1)],
+            signals: vec![],
+            position_timeseries: vec![],
+        };
+        let mut bytes = Vec::new();
+        BacktestEncoder::new(&mut bytes).encode_backtest(&backtest);
+
+        // Test
+        let mut reader = BacktestStreamReader::new(bytes.as_slice())?;
+        let points: Vec<TimeseriesStats> = reader.period_points().collect::<Result<_>>()?;
+
+        // Validate
+        assert_eq!(points, backtest.period_timeseries_stats);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_stream_writer() -> anyhow::Result<()> {
+        let mut writer = AsyncBacktestStreamWriter::new(Vec::new(), &metadata()).await?;
+        writer.write_trade(&trade(1)).await?;
+        writer.write_period_point(&point(1)).await?;
+
+        // Test
+        let bytes = writer.finish(&final_stats()).await?;
+
+        // Validate
+        let backtest = BacktestDecoder::new(bytes.as_slice()).decode_backtest()?;
+        assert_eq!(backtest.trades, vec![trade(1)]);
+        assert_eq!(backtest.period_timeseries_stats, vec![point(1)]);
+        assert_eq!(backtest.metadata.static_stats, final_stats());
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use mbinary::replay::{ReplayServer, ReplaySource, RECORDS_PATH};
+use std::net::TcpListener;
+use std::process::ExitCode;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(dir) = args.get(1) else {
+        eprintln!("Usage: mbinary_replay <data_dir> [address, default {DEFAULT_ADDR}]");
+        return ExitCode::FAILURE;
+    };
+    let addr = args.get(2).map(String::as_str).unwrap_or(DEFAULT_ADDR);
+
+    let source = match ReplaySource::open(dir) {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let listener = match TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to bind {}: {}", addr, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("Serving {} on http://{}{}", dir, addr, RECORDS_PATH);
+    if let Err(e) = ReplayServer::new(source).serve(listener) {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,374 @@
+use crate::backtest::{BacktestData, StaticStats, TimeseriesStats, Trades};
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "python")]
+use pyo3::pyclass;
+
+/// One `StaticStats` field across the compared backtests, with deltas against the first.
+#[cfg_attr(feature = "python", pyclass(get_all, dict, module = "mbinary"))]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StatDelta {
+    pub field: String,
+    pub values: Vec<i64>,
+    pub deltas: Vec<i64>,
+}
+
+/// Equity of every backtest at a timestamp, `None` where a backtest has no sample.
+#[cfg_attr(feature = "python", pyclass(get_all, dict, module = "mbinary"))]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct EquityPoint {
+    pub timestamp: i64,
+    pub equity_values: Vec<Option<i64>>,
+}
+
+/// Fills sharing a signal, timestamp and ticker that differ between backtests.
+#[cfg_attr(feature = "python", pyclass(get_all, dict, module = "mbinary"))]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TradeDiff {
+    pub signal_id: i32,
+    pub timestamp: i64,
+    pub ticker: String,
+    pub trades: Vec<Option<Trades>>,
+}
+
+#[cfg_attr(feature = "python", pyclass(get_all, dict, module = "mbinary"))]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BacktestComparison {
+    pub backtest_names: Vec<String>,
+    pub static_stats: Vec<StatDelta>,
+    pub period_equity: Vec<EquityPoint>,
+    pub daily_equity: Vec<EquityPoint>,
+    pub trade_diffs: Vec<TradeDiff>,
+}
+
+fn static_stats_fields(stats: &StaticStats) -> [(&'static str, i64); 24] {
+    [
+        ("total_trades", stats.total_trades as i64),
+        ("total_winning_trades", stats.total_winning_trades as i64),
+        ("total_losing_trades", stats.total_losing_trades as i64),
+        ("avg_profit", stats.avg_profit),
+        ("avg_profit_percent", stats.avg_profit_percent),
+        ("avg_gain", stats.avg_gain),
+        ("avg_gain_percent", stats.avg_gain_percent),
+        ("avg_loss", stats.avg_loss),
+        ("avg_loss_percent", stats.avg_loss_percent),
+        ("profitability_ratio", stats.profitability_ratio),
+        ("profit_factor", stats.profit_factor),
+        ("profit_and_loss_ratio", stats.profit_and_loss_ratio),
+        ("total_fees", stats.total_fees),
+        ("net_profit", stats.net_profit),
+        ("beginning_equity", stats.beginning_equity),
+        ("ending_equity", stats.ending_equity),
+        ("total_return", stats.total_return),
+        ("annualized_return", stats.annualized_return),
+        (
+            "daily_standard_deviation_percentage",
+            stats.daily_standard_deviation_percentage,
+        ),
+        (
+            "annual_standard_deviation_percentage",
+            stats.annual_standard_deviation_percentage,
+        ),
+        (
+            "max_drawdown_percentage_period",
+            stats.max_drawdown_percentage_period,
+        ),
+        (
+            "max_drawdown_percentage_daily",
+            stats.max_drawdown_percentage_daily,
+        ),
+        ("sharpe_ratio", stats.sharpe_ratio),
+        ("sortino_ratio", stats.sortino_ratio),
+    ]
+}
+
+fn compare_static_stats(backtests: &[BacktestData]) -> Result<Vec<StatDelta>> {
+    let fields: Vec<_> = backtests
+        .iter()
+        .map(|b| static_stats_fields(&b.metadata.static_stats))
+        .collect();
+
+    (0..fields[0].len())
+        .map(|i| {
+            let field = fields[0][i].0;
+            let values: Vec<i64> = fields.iter().map(|f| f[i].1).collect();
+            let deltas = values
+                .iter()
+                .map(|v| {
+                    v.checked_sub(values[0])
+                        .ok_or_else(|| Error::CustomError(format!("Delta of {} overflows", field)))
+                })
+                .collect::<Result<Vec<i64>>>()?;
+
+            Ok(StatDelta {
+                field: field.to_string(),
+                values,
+                deltas,
+            })
+        })
+        .collect()
+}
+
+/// Aligns equity curves on the union of their timestamps.
+fn align_equity<'a, F>(backtests: &'a [BacktestData], series: F) -> Vec<EquityPoint>
+where
+    F: Fn(&'a BacktestData) -> &'a Vec<TimeseriesStats>,
+{
+    let mut aligned: BTreeMap<i64, Vec<Option<i64>>> = BTreeMap::new();
+    for (i, backtest) in backtests.iter().enumerate() {
+        for stats in series(backtest) {
+            aligned
+                .entry(stats.timestamp)
+                .or_insert_with(|| vec![None; backtests.len()])[i] = Some(stats.equity_value);
+        }
+    }
+
+    aligned
+        .into_iter()
+        .map(|(timestamp, equity_values)| EquityPoint {
+            timestamp,
+            equity_values,
+        })
+        .collect()
+}
+
+/// Fills are the same if they differ only in `trade_id`, which is assigned per run.
+fn same_fill(a: &Trades, b: &Trades) -> bool {
+    a.quantity == b.quantity
+        && a.avg_price == b.avg_price
+        && a.trade_value == b.trade_value
+        && a.trade_cost == b.trade_cost
+        && a.action == b.action
+        && a.fees == b.fees
+}
+
+/// Matches trades by signal id, timestamp and ticker (and order within those), keeping the
+/// groups where a backtest is missing the fill or filled differently.
+fn compare_trades(backtests: &[BacktestData]) -> Vec<TradeDiff> {
+    type Key = (i32, i64, String, usize);
+
+    let keyed: Vec<BTreeMap<Key, &Trades>> = backtests
+        .iter()
+        .map(|backtest| {
+            let mut seen: BTreeMap<(i32, i64, String), usize> = BTreeMap::new();
+            backtest
+                .trades
+                .iter()
+                .map(|trade| {
+                    let base = (trade.signal_id, trade.timestamp, trade.ticker.clone());
+                    let occurrence = seen.entry(base).or_insert(0);
+                    *occurrence += 1;
+                    let key = (
+                        trade.signal_id,
+                        trade.timestamp,
+                        trade.ticker.clone(),
+                        *occurrence,
+                    );
+                    (key, trade)
+                })
+                .collect()
+        })
+        .collect();
+
+    let keys: BTreeSet<&Key> = keyed.iter().flat_map(|k| k.keys()).collect();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let trades: Vec<Option<&Trades>> = keyed.iter().map(|k| k.get(key).copied()).collect();
+            let identical = match trades[0] {
+                Some(first) => trades
+                    .iter()
+                    .all(|t| t.is_some_and(|t| same_fill(first, t))),
+                None => false,
+            };
+            (!identical).then(|| TradeDiff {
+                signal_id: key.0,
+                timestamp: key.1,
+                ticker: key.2.clone(),
+                trades: trades.into_iter().map(|t| t.cloned()).collect(),
+            })
+        })
+        .collect()
+}
+
+impl BacktestComparison {
+    /// Compares two or more backtests, using the first as the baseline for deltas.
+    pub fn new(backtests: &[BacktestData]) -> Result<Self> {
+        if backtests.len() < 2 {
+            return Err(Error::CustomError(
+                "At least two backtests are required for a comparison".to_string(),
+            ));
+        }
+
+        Ok(BacktestComparison {
+            backtest_names: backtests
+                .iter()
+                .map(|b| b.metadata.backtest_name.clone())
+                .collect(),
+            static_stats: compare_static_stats(backtests)?,
+            period_equity: align_equity(backtests, |b| &b.period_timeseries_stats),
+            daily_equity: align_equity(backtests, |b| &b.daily_timeseries_stats),
+            trade_diffs: compare_trades(backtests),
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| Error::Encode(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::{BacktestMetaData, Parameters};
+    use crate::enums::TradeAction;
+
+    fn backtest(
+        name: &str,
+        net_profit: i64,
+        equity: &[(i64, i64)],
+        trades: Vec<Trades>,
+    ) -> BacktestData {
+        let params = Parameters {
+            strategy_name: "Testing".to_string(),
+            capital: 10000,
+            schema: "Ohlcv-1s".to_string(),
+            data_type: "BAR".to_string(),
+            start: 1730160814000000000,
+            end: 1730160814000000000,
+            tickers: vec!["AAPL".to_string()],
+        };
+        let static_stats = StaticStats {
+            net_profit,
+            ..Default::default()
+        };
+        let timeseries: Vec<TimeseriesStats> = equity
+            .iter()
+            .map(|&(timestamp, equity_value)| TimeseriesStats {
+                timestamp,
+                equity_value,
+                percent_drawdown: 0,
+                cumulative_return: 0,
+                period_return: 0,
+            })
+            .collect();
+
+        BacktestData {
+            metadata: BacktestMetaData::new(None, name, params, static_stats),
+            period_timeseries_stats: timeseries.clone(),
+            daily_timeseries_stats: timeseries,
+            trades,
+            signals: vec![],
+            position_timeseries: vec![],
+        }
+    }
+
+    fn trade(trade_id: i32, signal_id: i32, timestamp: i64, avg_price: i64) -> Trades {
+        Trades {
+            trade_id,
+            signal_id,
+            timestamp,
+            ticker: "AAPL".to_string(),
+            quantity: 4,
+            avg_price,
+            trade_value: -4 * avg_price,
+            trade_cost: -4 * avg_price,
+            action: TradeAction::Buy,
+            fees: 100,
+        }
+    }
+
+    #[test]
+    fn test_compare() -> anyhow::Result<()> {
+        let base = backtest(
+            "base",
+            100,
+            &[(1, 1000), (2, 1100)],
+            vec![trade(1, 1, 1, 50), trade(2, 2, 2, 60)],
+        );
+        let other = backtest(
+            "other",
+            250,
+            &[(1, 1000), (3, 1200)],
+            vec![trade(7, 1, 1, 50), trade(8, 2, 2, 65), trade(9, 3, 3, 70)],
+        );
+
+        // Test
+        let comparison = BacktestComparison::new(&[base, other])?;
+
+        // Validate
+        assert_eq!(comparison.backtest_names, vec!["base", "other"]);
+
+        let net_profit = comparison
+            .static_stats
+            .iter()
+            .find(|s| s.field == "net_profit")
+            .unwrap();
+        assert_eq!(net_profit.values, vec![100, 250]);
+        assert_eq!(net_profit.deltas, vec![0, 150]);
+
+        assert_eq!(
+            comparison.period_equity,
+            vec![
+                EquityPoint {
+                    timestamp: 1,
+                    equity_values: vec![Some(1000), Some(1000)]
+                },
+                EquityPoint {
+                    timestamp: 2,
+                    equity_values: vec![Some(1100), None]
+                },
+                EquityPoint {
+                    timestamp: 3,
+                    equity_values: vec![None, Some(1200)]
+                },
+            ]
+        );
+
+        // Signal 1 matches despite different trade ids
+        let diff_signals: Vec<i32> = comparison.trade_diffs.iter().map(|d| d.signal_id).collect();
+        assert_eq!(diff_signals, vec![2, 3]);
+        assert!(comparison.trade_diffs[1].trades[0].is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_json() -> anyhow::Result<()> {
+        let base = backtest("base", 100, &[(1, 1000)], vec![]);
+        let other = backtest("other", 200, &[(1, 1100)], vec![]);
+        let comparison = BacktestComparison::new(&[base, other])?;
+
+        // Test
+        let json = comparison.to_json()?;
+        let decoded: BacktestComparison = serde_json::from_str(&json)?;
+
+        // Validate
+        assert_eq!(comparison, decoded);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_requires_two() {
+        let base = backtest("base", 100, &[(1, 1000)], vec![]);
+
+        // Test
+        let result = BacktestComparison::new(&[base]);
+
+        // Validate
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_compare_delta_overflow() {
+        let base = backtest("base", i64::MIN, &[(1, 1000)], vec![]);
+        let other = backtest("other", i64::MAX, &[(1, 1000)], vec![]);
+
+        // Test
+        let result = BacktestComparison::new(&[base, other]);
+
+        // Validate
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::decode_iterator::{AsyncDecoderIterator, DecoderIterator};
+use crate::filter::{AsyncFilteredDecoder, FilteredDecoder, RecordFilter};
 use crate::metadata::Metadata;
 use crate::record_enum::RecordEnum;
 use crate::record_ref::*;
@@ -38,6 +39,12 @@ impl<R: Read> Decoder<R> {
         self.decoder.decode_iterator()
     }
 
+    /// Only records whose header passes `filter` are decoded, with the metadata narrowed to
+    /// match.
+    pub fn filter(self, filter: RecordFilter) -> FilteredDecoder<R> {
+        FilteredDecoder::new(self.decoder, self.metadata, filter)
+    }
+
     /// Accepts PathBuf, Path and str for file_path
     pub fn from_file<P: AsRef<Path>>(
         file_path: P,
@@ -130,9 +137,36 @@ where
     }
 
     pub fn decode_ref(&mut self) -> std::io::Result<Option<RecordRef>> {
+        self.decode_ref_where(|_| true)
+    }
+
+    /// Decodes the next record whose header passes `predicate`, the others are skipped
+    /// without being converted.
+    pub fn decode_ref_where<F>(
+        &mut self,
+        mut predicate: F,
+    ) -> std::io::Result<Option<RecordRef<'_>>>
+    where
+        F: FnMut(&RecordHeader) -> bool,
+    {
+        while self.read_record()? {
+            if predicate(self.current().header()) {
+                return Ok(Some(self.current()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Only records whose header passes `filter` are decoded, see `FilteredDecoder`.
+    pub fn filter(self, filter: RecordFilter) -> FilteredDecoder<R> {
+        FilteredDecoder::new(self, None, filter)
+    }
+
+    /// Reads the next record into `read_buffer`, false at the end of the stream.
+    fn read_record(&mut self) -> std::io::Result<bool> {
         if let Err(err) = self.reader.read_exact(&mut self.read_buffer[..1]) {
             if err.kind() == std::io::ErrorKind::UnexpectedEof {
-                return Ok(None);
+                return Ok(false);
             } else {
                 return Err(std::io::Error::new(
                     err.kind(),
@@ -152,7 +186,7 @@ where
         }
         if let Err(err) = self.reader.read_exact(&mut self.read_buffer[1..length]) {
             if err.kind() == std::io::ErrorKind::UnexpectedEof {
-                return Ok(None);
+                return Ok(false);
             } else {
                 return Err(std::io::Error::new(
                     err.kind(),
@@ -160,8 +194,13 @@ where
                 ));
             }
         }
-        // Safety: `read_buffer` is resized to contain at least `length` bytes.
-        Ok(Some(unsafe { RecordRef::new(&self.read_buffer) }))
+        Ok(true)
+    }
+
+    /// The record last read by `read_record`.
+    fn current(&self) -> RecordRef<'_> {
+        // Safety: `read_buffer` is resized to contain at least the record's length in bytes.
+        unsafe { RecordRef::new(&self.read_buffer) }
     }
 
     pub fn from_file(file_path: &Path) -> std::io::Result<RecordDecoder<BufReader<std::fs::File>>> {
@@ -204,6 +243,12 @@ impl<R: AsyncBufRead + Unpin> AsyncDecoder<R> {
         self.decoder.decode_iterator()
     }
 
+    /// Only records whose header passes `filter` are decoded, with the metadata narrowed to
+    /// match.
+    pub fn filter(self, filter: RecordFilter) -> AsyncFilteredDecoder<R> {
+        AsyncFilteredDecoder::new(self.decoder, self.metadata, filter)
+    }
+
     /// Accepts PathBuf, Path and str for file_path
     pub async fn from_file<P: AsRef<Path>>(
         file_path: P,
@@ -288,9 +333,36 @@ where
     }
 
     pub async fn decode_ref(&mut self) -> tokio::io::Result<Option<RecordRef>> {
+        self.decode_ref_where(|_| true).await
+    }
+
+    /// Decodes the next record whose header passes `predicate`, the others are skipped
+    /// without being converted.
+    pub async fn decode_ref_where<F>(
+        &mut self,
+        mut predicate: F,
+    ) -> tokio::io::Result<Option<RecordRef<'_>>>
+    where
+        F: FnMut(&RecordHeader) -> bool,
+    {
+        while self.read_record().await? {
+            if predicate(self.current().header()) {
+                return Ok(Some(self.current()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Only records whose header passes `filter` are decoded, see `AsyncFilteredDecoder`.
+    pub fn filter(self, filter: RecordFilter) -> AsyncFilteredDecoder<R> {
+        AsyncFilteredDecoder::new(self, None, filter)
+    }
+
+    /// Reads the next record into `read_buffer`, false at the end of the stream.
+    async fn read_record(&mut self) -> tokio::io::Result<bool> {
         if let Err(err) = self.reader.read_exact(&mut self.read_buffer[..1]).await {
             if err.kind() == tokio::io::ErrorKind::UnexpectedEof {
-                return Ok(None);
+                return Ok(false);
             } else {
                 return Err(tokio::io::Error::new(
                     err.kind(),
@@ -314,7 +386,7 @@ where
             .await
         {
             if err.kind() == tokio::io::ErrorKind::UnexpectedEof {
-                return Ok(None);
+                return Ok(false);
             } else {
                 return Err(tokio::io::Error::new(
                     err.kind(),
@@ -322,8 +394,13 @@ where
                 ));
             }
         }
-        // Safety: `read_buffer` is resized to contain at least `length` bytes.
-        Ok(Some(unsafe { RecordRef::new(&self.read_buffer) }))
+        Ok(true)
+    }
+
+    /// The record last read by `read_record`.
+    fn current(&self) -> RecordRef<'_> {
+        // Safety: `read_buffer` is resized to contain at least the record's length in bytes.
+        unsafe { RecordRef::new(&self.read_buffer) }
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::error::{Error, Result};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::str::FromStr;
 
@@ -279,6 +279,155 @@ impl fmt::Display for Schema {
     }
 }
 
+/// Direction of a fill in backtest and live trade logs.
+#[cfg_attr(feature = "python", derive(strum::EnumIter, strum::AsRefStr))]
+#[cfg_attr(feature = "python", strum(serialize_all = "SCREAMING_SNAKE_CASE"))]
+#[cfg_attr(
+    feature = "python",
+    pyclass(module = "mbinary", rename_all = "SCREAMING_SNAKE_CASE", eq, eq_int)
+)]
+#[repr(u8)]
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+pub enum TradeAction {
+    #[serde(rename = "BUY", alias = "LONG")]
+    Buy = 1,
+    #[serde(rename = "SELL")]
+    Sell = 2,
+    #[serde(rename = "SHORT")]
+    Short = 3,
+    #[serde(rename = "COVER")]
+    Cover = 4,
+}
+
+impl TradeAction {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            TradeAction::Buy => "BUY",
+            TradeAction::Sell => "SELL",
+            TradeAction::Short => "SHORT",
+            TradeAction::Cover => "COVER",
+        }
+    }
+
+    /// Sign applied to the fill quantity, +1 for actions adding to a long position.
+    pub const fn direction(&self) -> i64 {
+        match self {
+            TradeAction::Buy | TradeAction::Cover => 1,
+            TradeAction::Sell | TradeAction::Short => -1,
+        }
+    }
+}
+
+impl FromStr for TradeAction {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "BUY" | "LONG" => Ok(TradeAction::Buy),
+            "SELL" => Ok(TradeAction::Sell),
+            "SHORT" => Ok(TradeAction::Short),
+            "COVER" => Ok(TradeAction::Cover),
+            _ => Err(Error::Conversion(format!(
+                "Unknown TradeAction value: '{}'",
+                value
+            ))),
+        }
+    }
+}
+
+// Used by sqlx to read the action from text columns
+impl TryFrom<String> for TradeAction {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        TradeAction::from_str(&value)
+    }
+}
+
+impl fmt::Display for TradeAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Deserializes an enum through its `FromStr`, which accepts the legacy spellings and any case
+/// that the derived implementation rejects.
+pub(crate) fn deserialize_from_str<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = Error>,
+{
+    let value = String::deserialize(deserializer)?;
+    T::from_str(&value).map_err(serde::de::Error::custom)
+}
+
+/// Order types a signal can instruct.
+#[cfg_attr(feature = "python", derive(strum::EnumIter, strum::AsRefStr))]
+#[cfg_attr(feature = "python", strum(serialize_all = "SCREAMING_SNAKE_CASE"))]
+#[cfg_attr(
+    feature = "python",
+    pyclass(module = "mbinary", rename_all = "SCREAMING_SNAKE_CASE", eq, eq_int)
+)]
+#[repr(u8)]
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive,
+)]
+pub enum OrderType {
+    #[serde(rename = "MKT")]
+    Market = 1,
+    #[serde(rename = "LMT")]
+    Limit = 2,
+    #[serde(rename = "STP")]
+    StopMarket = 3,
+    #[serde(rename = "STP_LMT")]
+    StopLimit = 4,
+}
+
+impl OrderType {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Market => "MKT",
+            OrderType::Limit => "LMT",
+            OrderType::StopMarket => "STP",
+            OrderType::StopLimit => "STP_LMT",
+        }
+    }
+}
+
+impl FromStr for OrderType {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_uppercase().as_str() {
+            "MKT" | "MARKET" => Ok(OrderType::Market),
+            "LMT" | "LIMIT" => Ok(OrderType::Limit),
+            "STP" | "STOP" | "STOP_MARKET" => Ok(OrderType::StopMarket),
+            "STP_LMT" | "STP LMT" | "STOP_LIMIT" => Ok(OrderType::StopLimit),
+            _ => Err(Error::Conversion(format!(
+                "Unknown OrderType value: '{}'",
+                value
+            ))),
+        }
+    }
+}
+
+// Used by sqlx to read the order type from text columns
+impl TryFrom<String> for OrderType {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        OrderType::from_str(&value)
+    }
+}
+
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Enums representing record types (RType) and schemas
 #[repr(u8)]
 #[cfg_attr(feature = "python", derive(strum::EnumIter, strum::AsRefStr))]
@@ -286,7 +435,7 @@ impl fmt::Display for Schema {
     feature = "python",
     pyclass(module = "mbinary", rename_all = "SCREAMING_SNAKE_CASE", eq, eq_int)
 )]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RType {
     Mbp1 = 0x01,
     Ohlcv = 0x02,
@@ -453,4 +602,38 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_trade_action_conv() -> anyhow::Result<()> {
+        let action = TradeAction::Short;
+
+        // u8
+        let action_int: u8 = action.into();
+        assert_eq!(TradeAction::try_from(action_int)?, action);
+
+        // str, including legacy spellings
+        assert_eq!(TradeAction::from_str(action.as_str())?, action);
+        assert_eq!(TradeAction::from_str("long")?, TradeAction::Buy);
+        assert!(TradeAction::from_str("HOLD").is_err());
+
+        // json matches the legacy string
+        assert_eq!(serde_json::to_string(&action)?, "\"SHORT\"");
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_type_conv() -> anyhow::Result<()> {
+        let order_type = OrderType::StopLimit;
+
+        // u8
+        let order_type_int: u8 = order_type.into();
+        assert_eq!(OrderType::try_from(order_type_int)?, order_type);
+
+        // str, including legacy spellings
+        assert_eq!(OrderType::from_str(order_type.as_str())?, order_type);
+        assert_eq!(OrderType::from_str("STP LMT")?, order_type);
+        assert_eq!(OrderType::from_str("mkt")?, OrderType::Market);
+        assert!(OrderType::from_str("MOC").is_err());
+        Ok(())
+    }
 }
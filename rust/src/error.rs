@@ -20,6 +20,12 @@ pub enum Error {
     InvalidRecordType(&'static str),
     #[error("Date error: {0}")]
     DateError(String),
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+    #[cfg(feature = "parquet")]
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
 }
 
 impl Error {
@@ -0,0 +1,360 @@
+use crate::decode::{AsyncRecordDecoder, RecordDecoder};
+use crate::enums::RType;
+use crate::metadata::Metadata;
+use crate::record_enum::RecordEnum;
+use crate::record_ref::RecordRef;
+use crate::records::RecordHeader;
+use std::collections::HashSet;
+use std::io::Read;
+use tokio::io::AsyncBufRead;
+
+#[cfg(feature = "python")]
+use pyo3::pyclass;
+
+/// Predicates on the `RecordHeader`, checked before a record is converted to `RecordEnum`.
+///
+/// Built up with the builder methods, an unset predicate matches every record.
+#[cfg_attr(feature = "python", pyclass(module = "mbinary"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordFilter {
+    instrument_ids: Option<HashSet<u32>>,
+    rtypes: Option<HashSet<u8>>,
+    start: Option<u64>,
+    end: Option<u64>,
+}
+
+impl RecordFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep records for `instrument_ids`, in addition to any instruments already kept.
+    pub fn instrument_ids(mut self, instrument_ids: impl IntoIterator<Item = u32>) -> Self {
+        self.instrument_ids
+            .get_or_insert_with(HashSet::new)
+            .extend(instrument_ids);
+        self
+    }
+
+    pub fn instrument_id(self, instrument_id: u32) -> Self {
+        self.instrument_ids([instrument_id])
+    }
+
+    /// Keep records of `rtypes`, in addition to any types already kept.
+    pub fn rtypes(mut self, rtypes: impl IntoIterator<Item = RType>) -> Self {
+        self.rtypes
+            .get_or_insert_with(HashSet::new)
+            .extend(rtypes.into_iter().map(|rtype| rtype as u8));
+        self
+    }
+
+    pub fn rtype(self, rtype: RType) -> Self {
+        self.rtypes([rtype])
+    }
+
+    /// Keep records with a `ts_event` at or after `start`.
+    pub fn start(mut self, start: u64) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Keep records with a `ts_event` before `end`.
+    pub fn end(mut self, end: u64) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn matches(&self, header: &RecordHeader) -> bool {
+        self.instrument_ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&header.instrument_id))
+            && self
+                .rtypes
+                .as_ref()
+                .is_none_or(|rtypes| rtypes.contains(&header.rtype))
+            && self.start.is_none_or(|start| header.ts_event >= start)
+            && self.end.is_none_or(|end| header.ts_event < end)
+    }
+
+    /// `metadata` narrowed to the records the filter keeps, dropping the mappings of other
+    /// instruments and clamping the period to the time window.
+    pub fn filter_metadata(&self, metadata: &Metadata) -> Metadata {
+        let mut filtered = metadata.clone();
+        if let Some(ids) = &self.instrument_ids {
+            filtered.mappings.map.retain(|id, _| ids.contains(id));
+        }
+        if let Some(start) = self.start {
+            filtered.start = filtered.start.max(start);
+        }
+        if let Some(end) = self.end {
+            filtered.end = filtered.end.min(end);
+        }
+        filtered
+    }
+}
+
+/// Decodes only the records passing a `RecordFilter`, the others are skipped on their header.
+pub struct FilteredDecoder<R> {
+    decoder: RecordDecoder<R>,
+    metadata: Option<Metadata>,
+    filter: RecordFilter,
+}
+
+impl<R: Read> FilteredDecoder<R> {
+    pub fn new(
+        decoder: RecordDecoder<R>,
+        metadata: Option<Metadata>,
+        filter: RecordFilter,
+    ) -> Self {
+        Self {
+            decoder,
+            metadata: metadata.map(|metadata| filter.filter_metadata(&metadata)),
+            filter,
+        }
+    }
+
+    /// Metadata of the source narrowed by `RecordFilter::filter_metadata`.
+    pub fn metadata(&self) -> Option<Metadata> {
+        self.metadata.clone()
+    }
+
+    pub fn record_filter(&self) -> &RecordFilter {
+        &self.filter
+    }
+
+    pub fn decode_ref(&mut self) -> std::io::Result<Option<RecordRef<'_>>> {
+        let filter = &self.filter;
+        self.decoder
+            .decode_ref_where(|header| filter.matches(header))
+    }
+
+    pub fn decode_to_owned(&mut self) -> crate::error::Result<Vec<RecordEnum>> {
+        let mut records = Vec::new();
+        while let Some(record_ref) = self.decode_ref()? {
+            records.push(RecordEnum::from_ref(record_ref)?);
+        }
+        Ok(records)
+    }
+}
+
+impl<R: Read> Iterator for FilteredDecoder<R> {
+    type Item = std::io::Result<RecordEnum>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.decode_ref() {
+            Ok(Some(record_ref)) => Some(RecordEnum::from_ref(record_ref).map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Failed to convert record reference to RecordEnum",
+                )
+            })),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Async counterpart of `FilteredDecoder`.
+pub struct AsyncFilteredDecoder<R> {
+    decoder: AsyncRecordDecoder<R>,
+    metadata: Option<Metadata>,
+    filter: RecordFilter,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncFilteredDecoder<R> {
+    pub fn new(
+        decoder: AsyncRecordDecoder<R>,
+        metadata: Option<Metadata>,
+        filter: RecordFilter,
+    ) -> Self {
+        Self {
+            decoder,
+            metadata: metadata.map(|metadata| filter.filter_metadata(&metadata)),
+            filter,
+        }
+    }
+
+    /// Metadata of the source narrowed by `RecordFilter::filter_metadata`.
+    pub fn metadata(&self) -> Option<Metadata> {
+        self.metadata.clone()
+    }
+
+    pub fn record_filter(&self) -> &RecordFilter {
+        &self.filter
+    }
+
+    pub async fn decode_ref(&mut self) -> tokio::io::Result<Option<RecordRef<'_>>> {
+        let filter = &self.filter;
+        self.decoder
+            .decode_ref_where(|header| filter.matches(header))
+            .await
+    }
+
+    pub async fn decode_to_owned(&mut self) -> crate::error::Result<Vec<RecordEnum>> {
+        let mut records = Vec::new();
+        while let Some(record_ref) = self.decode_ref().await? {
+            records.push(RecordEnum::from_ref(record_ref)?);
+        }
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{AsyncDecoder, Decoder};
+    use crate::encode::CombinedEncoder;
+    use crate::enums::{Dataset, Schema};
+    use crate::records::{OhlcvMsg, Record, TradeMsg};
+    use crate::symbols::SymbolMap;
+    use std::io::Cursor;
+
+    fn ohlcv(instrument_id: u32, ts_event: u64) -> OhlcvMsg {
+        OhlcvMsg {
+            hd: RecordHeader::new::<OhlcvMsg>(instrument_id, ts_event, 0),
+            open: 100,
+            high: 200,
+            low: 50,
+            close: 150,
+            volume: 1000,
+        }
+    }
+
+    fn trade(instrument_id: u32, ts_event: u64) -> TradeMsg {
+        TradeMsg {
+            hd: RecordHeader::new::<TradeMsg>(instrument_id, ts_event, 0),
+            price: 1000,
+            size: 10,
+            action: 1,
+            side: 1,
+            depth: 0,
+            flags: 0,
+            ts_recv: ts_event,
+            ts_in_delta: 0,
+            sequence: 1,
+        }
+    }
+
+    fn encoded() -> anyhow::Result<Vec<u8>> {
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        mappings.add_instrument("TSLA", 2);
+        let metadata = Metadata::new(Schema::Ohlcv1S, Dataset::Equities, 0, 100, mappings);
+
+        let bars = [ohlcv(1, 10), ohlcv(2, 20), ohlcv(1, 40)];
+        let trade = trade(1, 30);
+        let refs = [
+            RecordRef::from(&bars[0]),
+            RecordRef::from(&bars[1]),
+            RecordRef::from(&trade),
+            RecordRef::from(&bars[2]),
+        ];
+
+        let mut buffer = Vec::new();
+        CombinedEncoder::new(&mut buffer).encode(&metadata, &refs)?;
+        Ok(buffer)
+    }
+
+    fn headers(records: &[RecordEnum]) -> Vec<(u32, u64)> {
+        records
+            .iter()
+            .map(|record| {
+                let header = record.msg().header();
+                (header.instrument_id, header.ts_event)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_matches() {
+        let filter = RecordFilter::new()
+            .instrument_ids([1, 2])
+            .rtype(RType::Ohlcv)
+            .start(10)
+            .end(20);
+
+        // Validate
+        assert!(filter.matches(ohlcv(1, 10).header()));
+        assert!(filter.matches(ohlcv(2, 19).header()));
+        assert!(!filter.matches(ohlcv(3, 10).header()));
+        assert!(!filter.matches(ohlcv(1, 20).header()));
+        assert!(!filter.matches(ohlcv(1, 9).header()));
+        assert!(!filter.matches(trade(1, 10).header()));
+        assert!(RecordFilter::new().matches(trade(99, 0).header()));
+    }
+
+    #[test]
+    fn test_filter_metadata() {
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        mappings.add_instrument("TSLA", 2);
+        let metadata = Metadata::new(Schema::Ohlcv1S, Dataset::Equities, 0, 100, mappings);
+
+        // Test
+        let filtered = RecordFilter::new()
+            .instrument_id(2)
+            .start(20)
+            .end(500)
+            .filter_metadata(&metadata);
+
+        // Validate
+        assert_eq!(filtered.start, 20);
+        assert_eq!(filtered.end, 100);
+        assert_eq!(
+            filtered.mappings.get_instrument_ticker(2),
+            Some("TSLA".to_string())
+        );
+        assert_eq!(filtered.mappings.get_instrument_ticker(1), None);
+        assert_eq!(filtered.schema, Schema::Ohlcv1S);
+    }
+
+    #[test]
+    fn test_filtered_decoder() -> anyhow::Result<()> {
+        let buffer = encoded()?;
+        let filter = RecordFilter::new().instrument_id(1).rtype(RType::Ohlcv);
+
+        // Test
+        let mut decoder = Decoder::new(Cursor::new(buffer))?.filter(filter);
+        let records = decoder.decode_to_owned()?;
+
+        // Validate
+        assert_eq!(headers(&records), vec![(1, 10), (1, 40)]);
+        let metadata = decoder.metadata().unwrap();
+        assert_eq!(metadata.mappings.map.len(), 1);
+        assert_eq!(
+            metadata.mappings.get_instrument_ticker(1),
+            Some("AAPL".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_filtered_record_decoder_iterator() -> anyhow::Result<()> {
+        let buffer = encoded()?;
+        let mut cursor = Cursor::new(buffer);
+        Decoder::new(&mut cursor)?;
+
+        // Test
+        let decoder = RecordDecoder::new(cursor).filter(RecordFilter::new().start(20).end(40));
+        let records = decoder.collect::<std::io::Result<Vec<_>>>()?;
+
+        // Validate
+        assert_eq!(headers(&records), vec![(2, 20), (1, 30)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_async_filtered_decoder() -> anyhow::Result<()> {
+        let buffer = encoded()?;
+        let filter = RecordFilter::new().rtype(RType::Trades);
+
+        // Test
+        let mut decoder = AsyncDecoder::new(buffer.as_slice()).await?.filter(filter);
+        let records = decoder.decode_to_owned().await?;
+
+        // Validate
+        assert_eq!(headers(&records), vec![(1, 30)]);
+        assert_eq!(decoder.metadata().unwrap().mappings.map.len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,345 @@
+use crate::backtest::{PositionStats, Trades};
+use crate::record_enum::RecordEnum;
+use crate::records::Record;
+use crate::symbols::SymbolMap;
+use crate::PRICE_SCALE;
+use std::collections::HashMap;
+
+/// `quantity * price` for two 1e9-scaled values, scaled by 1e9.
+pub(crate) fn notional(quantity: i64, price: i64) -> i128 {
+    quantity as i128 * price as i128 / PRICE_SCALE as i128
+}
+
+/// Holding in a single ticker, all values scaled by 1e9.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    /// Signed, negative when short.
+    pub quantity: i64,
+    /// Average fill price of the open quantity.
+    pub avg_cost: i64,
+    /// Closed profit less every fee paid on the ticker.
+    pub realized_pnl: i64,
+}
+
+/// Profit of a fill that reduced a position, scaled by 1e9.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedTrade {
+    pub ticker: String,
+    pub timestamp: i64,
+    pub quantity: i64,
+    /// Profit on the closed quantity less the closing fill's fees.
+    pub pnl: i64,
+    pub cost_basis: i64,
+}
+
+impl ClosedTrade {
+    pub fn return_fraction(&self) -> f64 {
+        if self.cost_basis == 0 {
+            0.0
+        } else {
+            self.pnl as f64 / self.cost_basis as f64
+        }
+    }
+}
+
+/// Replays fills into per-ticker positions, cash and PnL using average cost accounting.
+///
+/// Positions are valued at the last mark for their ticker, which is the latest fill price
+/// until a price is supplied with `mark`.
+#[derive(Debug, Clone)]
+pub struct Ledger {
+    cash: i128,
+    positions: HashMap<String, Position>,
+    marks: HashMap<String, i64>,
+    closed: Vec<ClosedTrade>,
+    total_fees: i64,
+}
+
+impl Ledger {
+    /// Starts a ledger holding `cash`, scaled by 1e9.
+    pub fn new(cash: i64) -> Self {
+        Ledger {
+            cash: cash as i128,
+            positions: HashMap::new(),
+            marks: HashMap::new(),
+            closed: Vec::new(),
+            total_fees: 0,
+        }
+    }
+
+    pub fn apply(&mut self, trade: &Trades) {
+        let quantity = trade.quantity.abs() * trade.action.direction();
+        let price = trade.avg_price;
+
+        self.cash -= notional(quantity, price) + trade.fees as i128;
+        self.total_fees += trade.fees;
+        self.marks.insert(trade.ticker.clone(), price);
+
+        let position = self.positions.entry(trade.ticker.clone()).or_default();
+        position.realized_pnl -= trade.fees;
+
+        if position.quantity == 0 || position.quantity.signum() == quantity.signum() {
+            let held = position.quantity.abs() as i128;
+            let added = quantity.abs() as i128;
+            position.avg_cost = ((held * position.avg_cost as i128 + added * price as i128)
+                / (held + added)) as i64;
+            position.quantity += quantity;
+            return;
+        }
+
+        let closed = quantity.abs().min(position.quantity.abs());
+        let gross =
+            notional(closed, price - position.avg_cost) * position.quantity.signum() as i128;
+        position.realized_pnl += gross as i64;
+        self.closed.push(ClosedTrade {
+            ticker: trade.ticker.clone(),
+            timestamp: trade.timestamp,
+            quantity: closed,
+            pnl: (gross - trade.fees as i128) as i64,
+            cost_basis: notional(closed, position.avg_cost) as i64,
+        });
+
+        position.quantity += quantity;
+        if position.quantity != 0 && position.quantity.signum() == quantity.signum() {
+            // Flipped through flat, the remainder opens at the fill price
+            position.avg_cost = price;
+        }
+    }
+
+    /// Sets the price `ticker` is valued at, scaled by 1e9.
+    pub fn mark(&mut self, ticker: &str, price: i64) {
+        self.marks.insert(ticker.to_string(), price);
+    }
+
+    pub fn cash(&self) -> i64 {
+        self.cash as i64
+    }
+
+    pub fn position(&self, ticker: &str) -> Option<&Position> {
+        self.positions.get(ticker)
+    }
+
+    /// Every ticker traded so far, including flat ones.
+    pub fn positions(&self) -> &HashMap<String, Position> {
+        &self.positions
+    }
+
+    pub fn closed_trades(&self) -> &[ClosedTrade] {
+        &self.closed
+    }
+
+    pub fn total_fees(&self) -> i64 {
+        self.total_fees
+    }
+
+    /// Price `ticker` is currently valued at.
+    pub fn market_price(&self, ticker: &str) -> Option<i64> {
+        self.marks
+            .get(ticker)
+            .copied()
+            .or_else(|| self.positions.get(ticker).map(|p| p.avg_cost))
+    }
+
+    pub fn unrealized_pnl(&self, ticker: &str) -> i64 {
+        match (self.positions.get(ticker), self.market_price(ticker)) {
+            (Some(position), Some(price)) => {
+                notional(position.quantity, price - position.avg_cost) as i64
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn total_realized_pnl(&self) -> i64 {
+        self.positions.values().map(|p| p.realized_pnl).sum()
+    }
+
+    pub fn total_unrealized_pnl(&self) -> i64 {
+        self.positions
+            .keys()
+            .map(|ticker| self.unrealized_pnl(ticker))
+            .sum()
+    }
+
+    /// Cash plus the marked value of every position.
+    pub fn equity(&self) -> i64 {
+        let holdings: i128 = self
+            .positions
+            .iter()
+            .map(|(ticker, position)| {
+                let mark = self.marks.get(ticker).copied().unwrap_or(position.avg_cost);
+                notional(position.quantity, mark)
+            })
+            .sum();
+        (self.cash + holdings) as i64
+    }
+
+    /// Point-in-time view of the position in `ticker`, if it has been traded.
+    pub fn position_stats(&self, timestamp: i64, ticker: &str) -> Option<PositionStats> {
+        let position = self.positions.get(ticker)?;
+        let market_price = self.market_price(ticker).unwrap_or(position.avg_cost);
+
+        Some(PositionStats {
+            timestamp,
+            ticker: ticker.to_string(),
+            quantity: position.quantity,
+            avg_cost: position.avg_cost,
+            market_price,
+            market_value: notional(position.quantity, market_price) as i64,
+            realized_pnl: position.realized_pnl,
+            unrealized_pnl: self.unrealized_pnl(ticker),
+        })
+    }
+}
+
+fn fill(ledger: &mut Ledger, trade: &Trades, timeseries: &mut Vec<PositionStats>) {
+    ledger.apply(trade);
+    timeseries.extend(ledger.position_stats(trade.timestamp, &trade.ticker));
+}
+
+/// Replays `trades` in timestamp order and records the position in a ticker after each of
+/// its fills, and after each price from `records` while the position is open.
+///
+/// Records are mapped to tickers through `mappings` and valued with `Record::price`; pass no
+/// records to value positions at their fills.
+pub fn position_timeseries(
+    trades: &[Trades],
+    mappings: &SymbolMap,
+    records: &[RecordEnum],
+) -> Vec<PositionStats> {
+    // Cash is not part of `PositionStats`, so the ledger starts without any
+    let mut ledger = Ledger::new(0);
+
+    let mut trades: Vec<&Trades> = trades.iter().collect();
+    trades.sort_by_key(|t| t.timestamp);
+    let mut pending = trades.into_iter().peekable();
+
+    let mut timeseries = Vec::new();
+
+    for record in records {
+        let timestamp = record.header().ts_event as i64;
+        while let Some(trade) = pending.next_if(|t| t.timestamp <= timestamp) {
+            fill(&mut ledger, trade, &mut timeseries);
+        }
+
+        let Some(ticker) = mappings.get_instrument_ticker(record.header().instrument_id) else {
+            continue;
+        };
+        ledger.mark(&ticker, record.price());
+        if ledger.position(&ticker).is_some_and(|p| p.quantity != 0) {
+            timeseries.extend(ledger.position_stats(timestamp, &ticker));
+        }
+    }
+
+    for trade in pending {
+        fill(&mut ledger, trade, &mut timeseries);
+    }
+
+    timeseries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::TradeAction;
+    use crate::records::{OhlcvMsg, RecordHeader};
+
+    fn trade(timestamp: i64, action: TradeAction, quantity: i64, price: i64, fees: i64) -> Trades {
+        Trades {
+            trade_id: 1,
+            signal_id: 1,
+            timestamp,
+            ticker: "AAPL".to_string(),
+            quantity: quantity * PRICE_SCALE,
+            avg_price: price * PRICE_SCALE,
+            trade_value: quantity * price * PRICE_SCALE,
+            trade_cost: quantity * price * PRICE_SCALE,
+            action,
+            fees: fees * PRICE_SCALE,
+        }
+    }
+
+    fn bar(ts_event: u64, close: i64) -> RecordEnum {
+        RecordEnum::Ohlcv(OhlcvMsg {
+            hd: RecordHeader::new::<OhlcvMsg>(1, ts_event, 0),
+            open: close * PRICE_SCALE,
+            high: close * PRICE_SCALE,
+            low: close * PRICE_SCALE,
+            close: close * PRICE_SCALE,
+            volume: 100,
+        })
+    }
+
+    #[test]
+    fn test_ledger_pnl() {
+        let mut ledger = Ledger::new(10_000 * PRICE_SCALE);
+
+        // Test
+        ledger.apply(&trade(1, TradeAction::Buy, 10, 100, 1));
+        ledger.apply(&trade(2, TradeAction::Buy, 10, 110, 1));
+        ledger.apply(&trade(3, TradeAction::Sell, 5, 120, 1));
+        ledger.mark("AAPL", 100 * PRICE_SCALE);
+
+        // Validate
+        let position = ledger.position("AAPL").unwrap();
+        assert_eq!(position.quantity, 15 * PRICE_SCALE);
+        assert_eq!(position.avg_cost, 105 * PRICE_SCALE);
+        assert_eq!(position.realized_pnl, (5 * 15 - 3) * PRICE_SCALE);
+        assert_eq!(ledger.unrealized_pnl("AAPL"), -15 * 5 * PRICE_SCALE);
+        assert_eq!(
+            ledger.cash(),
+            (10_000 - 1000 - 1100 + 600 - 3) * PRICE_SCALE
+        );
+        assert_eq!(ledger.total_fees(), 3 * PRICE_SCALE);
+        assert_eq!(ledger.closed_trades().len(), 1);
+
+        // Equity moves by exactly the realized and unrealized PnL
+        assert_eq!(
+            ledger.equity() - 10_000 * PRICE_SCALE,
+            ledger.total_realized_pnl() + ledger.total_unrealized_pnl()
+        );
+    }
+
+    #[test]
+    fn test_ledger_flip() {
+        let mut ledger = Ledger::new(10_000 * PRICE_SCALE);
+
+        // Test
+        ledger.apply(&trade(1, TradeAction::Buy, 10, 100, 0));
+        ledger.apply(&trade(2, TradeAction::Short, 15, 90, 0));
+
+        // Validate
+        let position = ledger.position("AAPL").unwrap();
+        assert_eq!(position.quantity, -5 * PRICE_SCALE);
+        assert_eq!(position.avg_cost, 90 * PRICE_SCALE);
+        assert_eq!(position.realized_pnl, -100 * PRICE_SCALE);
+        assert_eq!(ledger.unrealized_pnl("AAPL"), 0);
+    }
+
+    #[test]
+    fn test_position_timeseries() {
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        let trades = vec![
+            trade(2, TradeAction::Buy, 10, 100, 1),
+            trade(5, TradeAction::Sell, 10, 104, 1),
+        ];
+        let records = vec![bar(1, 99), bar(3, 102), bar(4, 103), bar(6, 105)];
+
+        // Test
+        let timeseries = position_timeseries(&trades, &mappings, &records);
+
+        // Validate
+        let timestamps: Vec<i64> = timeseries.iter().map(|p| p.timestamp).collect();
+        assert_eq!(timestamps, vec![2, 3, 4, 5]);
+
+        assert_eq!(timeseries[1].market_price, 102 * PRICE_SCALE);
+        assert_eq!(timeseries[1].market_value, 1020 * PRICE_SCALE);
+        assert_eq!(timeseries[1].unrealized_pnl, 20 * PRICE_SCALE);
+        assert_eq!(timeseries[1].realized_pnl, -PRICE_SCALE);
+
+        let last = timeseries.last().unwrap();
+        assert_eq!(last.quantity, 0);
+        assert_eq!(last.realized_pnl, 38 * PRICE_SCALE);
+        assert_eq!(last.unrealized_pnl, 0);
+    }
+}
@@ -4,17 +4,31 @@ pub const QUANTITY_SCALE: i32 = 1_000;
 pub mod backtest;
 pub mod backtest_decoder;
 pub mod backtest_encode;
+pub mod backtest_stream;
+pub mod compare;
 pub mod decode;
 pub mod decode_iterator;
 pub mod encode;
 pub mod enums;
 pub mod error;
+pub mod filter;
+pub mod ledger;
 pub mod live;
+pub mod live_decoder;
+pub mod live_encode;
 pub mod metadata;
 pub mod params;
+pub mod performance;
+#[cfg(feature = "parquet")]
+pub mod parquet;
 pub mod record_enum;
 pub mod record_ref;
+pub mod reconcile;
 pub mod records;
+pub mod replay;
+pub mod retrieve;
+pub mod simulator;
+pub mod store;
 pub mod symbols;
 pub mod utils;
 pub mod vendors;
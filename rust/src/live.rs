@@ -1,10 +1,22 @@
+use crate::backtest::{read_fixed, read_string, write_string, Decode, Encode, Legacy};
 use crate::backtest::{Parameters, Signals, Trades};
+use crate::backtest_decoder::decode_vector;
+use crate::backtest_encode::encode_vector;
+use crate::live_encode::LIVE_FORMAT_VERSION;
+use crate::Result;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::io::Read;
 
 #[cfg(feature = "python")]
 use pyo3::pyclass;
 
+/// Live format version storing trade actions, order types and signal prices as strings.
+const STRING_FIELDS_VERSION: u8 = 1;
+
+/// First live format version carrying account snapshots.
+const SNAPSHOTS_VERSION: u8 = 3;
+
 #[repr(C)]
 #[cfg_attr(
     feature = "python",
@@ -17,6 +29,77 @@ pub struct LiveData {
     pub trades: Vec<Trades>,
     pub signals: Vec<Signals>,
     pub account: AccountSummary,
+    #[serde(default)]
+    pub account_snapshots: Vec<AccountSnapshot>,
+}
+
+impl Encode for LiveData {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        let live_id = self.live_id.unwrap_or(u16::MAX); // Sentinel value for None
+        buffer.extend(&live_id.to_le_bytes());
+        self.parameters.encode(buffer);
+        encode_vector(&self.trades, buffer);
+        encode_vector(&self.signals, buffer);
+        self.account.encode(buffer);
+        encode_vector(&self.account_snapshots, buffer);
+    }
+}
+
+impl<R: Read> Decode<R> for LiveData {
+    fn decode(cursor: &mut R) -> Result<Self> {
+        LiveData::decode_version(cursor, LIVE_FORMAT_VERSION)
+    }
+}
+
+impl LiveData {
+    /// Decodes a session body written with live format `version`.
+    pub(crate) fn decode_version<R: Read>(cursor: &mut R, version: u8) -> Result<Self> {
+        let live_id: u16 = read_fixed(cursor)?;
+        let parameters = Parameters::decode(cursor)?;
+        let (trades, signals) = if version == STRING_FIELDS_VERSION {
+            let trades: Vec<Legacy<Trades>> = decode_vector(cursor)?;
+            let signals: Vec<Legacy<Signals>> = decode_vector(cursor)?;
+            (
+                trades.into_iter().map(|t| t.0).collect(),
+                signals.into_iter().map(|s| s.0).collect(),
+            )
+        } else {
+            (decode_vector(cursor)?, decode_vector(cursor)?)
+        };
+        let account = AccountSummary::decode(cursor)?;
+        let account_snapshots = if version >= SNAPSHOTS_VERSION {
+            decode_vector(cursor)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            live_id: (live_id != u16::MAX).then_some(live_id),
+            parameters,
+            trades,
+            signals,
+            account,
+            account_snapshots,
+        })
+    }
+
+    pub fn push_account_snapshot(&mut self, snapshot: AccountSnapshot) {
+        self.account_snapshots.push(snapshot);
+    }
+
+    /// Start/end summary of the session, taken from the first and last snapshots when any
+    /// were recorded and from `account` otherwise.
+    pub fn account_summary(&self) -> AccountSummary {
+        match (
+            self.account_snapshots.first(),
+            self.account_snapshots.last(),
+        ) {
+            (Some(start), Some(end)) => {
+                AccountSummary::from_snapshots(&self.account.currency, start, end)
+            }
+            _ => self.account.clone(),
+        }
+    }
 }
 
 #[repr(C)]
@@ -48,3 +131,176 @@ pub struct AccountSummary {
     pub end_total_cash_balance: i64,
     pub end_unrealized_pnl: i64,
 }
+
+impl Encode for AccountSummary {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        write_string(buffer, &self.currency);
+        for value in [
+            self.start_timestamp,
+            self.start_buying_power,
+            self.start_excess_liquidity,
+            self.start_full_available_funds,
+            self.start_full_init_margin_req,
+            self.start_full_maint_margin_req,
+            self.start_futures_pnl,
+            self.start_net_liquidation,
+            self.start_total_cash_balance,
+            self.start_unrealized_pnl,
+            self.end_timestamp,
+            self.end_buying_power,
+            self.end_excess_liquidity,
+            self.end_full_available_funds,
+            self.end_full_init_margin_req,
+            self.end_full_maint_margin_req,
+            self.end_futures_pnl,
+            self.end_net_liquidation,
+            self.end_total_cash_balance,
+            self.end_unrealized_pnl,
+        ] {
+            buffer.extend(&value.to_le_bytes());
+        }
+    }
+}
+
+impl<R: Read> Decode<R> for AccountSummary {
+    fn decode(cursor: &mut R) -> Result<Self> {
+        Ok(Self {
+            currency: read_string(cursor)?,
+            start_timestamp: read_fixed(cursor)?,
+            start_buying_power: read_fixed(cursor)?,
+            start_excess_liquidity: read_fixed(cursor)?,
+            start_full_available_funds: read_fixed(cursor)?,
+            start_full_init_margin_req: read_fixed(cursor)?,
+            start_full_maint_margin_req: read_fixed(cursor)?,
+            start_futures_pnl: read_fixed(cursor)?,
+            start_net_liquidation: read_fixed(cursor)?,
+            start_total_cash_balance: read_fixed(cursor)?,
+            start_unrealized_pnl: read_fixed(cursor)?,
+            end_timestamp: read_fixed(cursor)?,
+            end_buying_power: read_fixed(cursor)?,
+            end_excess_liquidity: read_fixed(cursor)?,
+            end_full_available_funds: read_fixed(cursor)?,
+            end_full_init_margin_req: read_fixed(cursor)?,
+            end_full_maint_margin_req: read_fixed(cursor)?,
+            end_futures_pnl: read_fixed(cursor)?,
+            end_net_liquidation: read_fixed(cursor)?,
+            end_total_cash_balance: read_fixed(cursor)?,
+            end_unrealized_pnl: read_fixed(cursor)?,
+        })
+    }
+}
+
+/// Account balances and margin at a point in the session.
+#[repr(C)]
+#[cfg_attr(
+    feature = "python",
+    pyclass(get_all, set_all, dict, module = "mbinary")
+)]
+#[derive(Deserialize, Serialize, FromRow, Debug, Clone, PartialEq)]
+pub struct AccountSnapshot {
+    pub timestamp: i64,
+    pub buying_power: i64,
+    pub excess_liquidity: i64,
+    pub full_available_funds: i64,
+    pub full_init_margin_req: i64,
+    pub full_maint_margin_req: i64,
+    pub futures_pnl: i64,
+    pub net_liquidation: i64,
+    pub total_cash_balance: i64,
+    pub unrealized_pnl: i64,
+}
+
+impl Encode for AccountSnapshot {
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        for value in [
+            self.timestamp,
+            self.buying_power,
+            self.excess_liquidity,
+            self.full_available_funds,
+            self.full_init_margin_req,
+            self.full_maint_margin_req,
+            self.futures_pnl,
+            self.net_liquidation,
+            self.total_cash_balance,
+            self.unrealized_pnl,
+        ] {
+            buffer.extend(&value.to_le_bytes());
+        }
+    }
+}
+
+impl<R: Read> Decode<R> for AccountSnapshot {
+    fn decode(cursor: &mut R) -> Result<Self> {
+        Ok(Self {
+            timestamp: read_fixed(cursor)?,
+            buying_power: read_fixed(cursor)?,
+            excess_liquidity: read_fixed(cursor)?,
+            full_available_funds: read_fixed(cursor)?,
+            full_init_margin_req: read_fixed(cursor)?,
+            full_maint_margin_req: read_fixed(cursor)?,
+            futures_pnl: read_fixed(cursor)?,
+            net_liquidation: read_fixed(cursor)?,
+            total_cash_balance: read_fixed(cursor)?,
+            unrealized_pnl: read_fixed(cursor)?,
+        })
+    }
+}
+
+impl AccountSummary {
+    /// Builds the start/end view from two snapshots.
+    pub fn from_snapshots(currency: &str, start: &AccountSnapshot, end: &AccountSnapshot) -> Self {
+        AccountSummary {
+            currency: currency.to_string(),
+            start_timestamp: start.timestamp,
+            start_buying_power: start.buying_power,
+            start_excess_liquidity: start.excess_liquidity,
+            start_full_available_funds: start.full_available_funds,
+            start_full_init_margin_req: start.full_init_margin_req,
+            start_full_maint_margin_req: start.full_maint_margin_req,
+            start_futures_pnl: start.futures_pnl,
+            start_net_liquidation: start.net_liquidation,
+            start_total_cash_balance: start.total_cash_balance,
+            start_unrealized_pnl: start.unrealized_pnl,
+            end_timestamp: end.timestamp,
+            end_buying_power: end.buying_power,
+            end_excess_liquidity: end.excess_liquidity,
+            end_full_available_funds: end.full_available_funds,
+            end_full_init_margin_req: end.full_init_margin_req,
+            end_full_maint_margin_req: end.full_maint_margin_req,
+            end_futures_pnl: end.futures_pnl,
+            end_net_liquidation: end.net_liquidation,
+            end_total_cash_balance: end.total_cash_balance,
+            end_unrealized_pnl: end.unrealized_pnl,
+        }
+    }
+
+    pub fn start(&self) -> AccountSnapshot {
+        AccountSnapshot {
+            timestamp: self.start_timestamp,
+            buying_power: self.start_buying_power,
+            excess_liquidity: self.start_excess_liquidity,
+            full_available_funds: self.start_full_available_funds,
+            full_init_margin_req: self.start_full_init_margin_req,
+            full_maint_margin_req: self.start_full_maint_margin_req,
+            futures_pnl: self.start_futures_pnl,
+            net_liquidation: self.start_net_liquidation,
+            total_cash_balance: self.start_total_cash_balance,
+            unrealized_pnl: self.start_unrealized_pnl,
+        }
+    }
+
+    pub fn end(&self) -> AccountSnapshot {
+        AccountSnapshot {
+            timestamp: self.end_timestamp,
+            buying_power: self.end_buying_power,
+            excess_liquidity: self.end_excess_liquidity,
+            full_available_funds: self.end_full_available_funds,
+            full_init_margin_req: self.end_full_init_margin_req,
+            full_maint_margin_req: self.end_full_maint_margin_req,
+            futures_pnl: self.end_futures_pnl,
+            net_liquidation: self.end_net_liquidation,
+            total_cash_balance: self.end_total_cash_balance,
+            unrealized_pnl: self.end_unrealized_pnl,
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::live::LiveData;
+use crate::live_encode::LIVE_FORMAT_VERSION;
+use crate::{Error, Result};
+use std::io::Read;
+
+pub struct LiveDecoder<R: Read> {
+    cursor: R,
+}
+
+impl<R: Read> LiveDecoder<R> {
+    pub fn new(reader: R) -> Self {
+        LiveDecoder { cursor: reader }
+    }
+
+    pub fn decode_live(&mut self) -> Result<LiveData> {
+        let mut version = [0u8; 1];
+        self.cursor
+            .read_exact(&mut version)
+            .map_err(|_| Error::Decode("Failed to read live format version".to_string()))?;
+
+        match version[0] {
+            1..=LIVE_FORMAT_VERSION => LiveData::decode_version(&mut self.cursor, version[0]),
+            _ => Err(Error::Decode(format!(
+                "Unsupported live format version: {}",
+                version[0]
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::{Encode, Legacy};
+    use crate::backtest::{Parameters, SignalInstructions, Signals, Trades};
+    use crate::backtest_encode::encode_vector;
+    use crate::enums::{OrderType, TradeAction};
+    use crate::live::{AccountSnapshot, AccountSummary};
+    use crate::live_encode::LiveEncoder;
+
+    fn live_data(live_id: Option<u16>) -> LiveData {
+        let params = Parameters {
+            strategy_name: "Testing".to_string(),
+            capital: 10000,
+            schema: "Ohlcv-1s".to_string(),
+            data_type: "BAR".to_string(),
+            start: 1730160814000000000,
+            end: 1730160814000000000,
+            tickers: vec!["HE.n.0".to_string(), "AAPL".to_string()],
+        };
+
+        let trade = Trades {
+            trade_id: 1,
+            signal_id: 1,
+            timestamp: 1704903000,
+            ticker: "AAPL".to_string(),
+            quantity: 4,
+            avg_price: 13074,
+            trade_value: -52296,
+            trade_cost: -52296,
+            action: TradeAction::Buy,
+            fees: 100,
+        };
+
+        let instructions = SignalInstructions {
+            ticker: "AAPL".to_string(),
+            order_type: OrderType::Market,
+            action: TradeAction::Buy,
+            signal_id: 2,
+            weight: 13213432,
+            quantity: 2343,
+            limit_price: Some(12341),
+            aux_price: Some(1233212),
+        };
+
+        let signal = Signals {
+            timestamp: 1234565432345,
+            trade_instructions: vec![instructions.clone(), instructions],
+        };
+
+        let account = AccountSummary {
+            currency: "USD".to_string(),
+            start_timestamp: 1704903000,
+            start_buying_power: 7962017000000000,
+            start_excess_liquidity: 7962017000000000,
+            start_full_available_funds: 7962017000000000,
+            start_full_init_margin_req: 1,
+            start_full_maint_margin_req: 2,
+            start_futures_pnl: 3,
+            start_net_liquidation: 7962017000000000,
+            start_total_cash_balance: 7962017000000000,
+            start_unrealized_pnl: 4,
+            end_timestamp: 1704904000,
+            end_buying_power: 7962017000000000,
+            end_excess_liquidity: 7962017000000000,
+            end_full_available_funds: 7962017000000000,
+            end_full_init_margin_req: 5,
+            end_full_maint_margin_req: 6,
+            end_futures_pnl: 7,
+            end_net_liquidation: 7962017000000000,
+            end_total_cash_balance: 7962017000000000,
+            end_unrealized_pnl: 8,
+        };
+
+        LiveData {
+            live_id,
+            parameters: params,
+            trades: vec![trade.clone(), trade],
+            signals: vec![signal.clone(), signal],
+            account_snapshots: vec![account.start(), account.end()],
+            account,
+        }
+    }
+
+    #[test]
+    fn livedecoder() -> anyhow::Result<()> {
+        for live in [live_data(None), live_data(Some(12))] {
+            // Encode
+            let mut bytes = Vec::new();
+            let mut encoder = LiveEncoder::new(&mut bytes);
+            encoder.encode_live(&live);
+
+            // Decode
+            let mut decoder = LiveDecoder::new(bytes.as_slice());
+            let decoded = decoder.decode_live()?;
+
+            // Validate
+            assert_eq!(live, decoded);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn livedecoder_v1() -> anyhow::Result<()> {
+        let mut live = live_data(Some(4));
+        live.account_snapshots.clear();
+        let mut bytes = vec![1];
+        bytes.extend(&4u16.to_le_bytes());
+        live.parameters.encode(&mut bytes);
+        let trades: Vec<Legacy<Trades>> = live.trades.iter().cloned().map(Legacy).collect();
+        let signals: Vec<Legacy<Signals>> = live.signals.iter().cloned().map(Legacy).collect();
+        encode_vector(&trades, &mut bytes);
+        encode_vector(&signals, &mut bytes);
+        live.account.encode(&mut bytes);
+
+        // Test
+        let decoded = LiveDecoder::new(bytes.as_slice()).decode_live()?;
+
+        // Validate
+        assert_eq!(live, decoded);
+        Ok(())
+    }
+
+    #[test]
+    fn livedecoder_v2() -> anyhow::Result<()> {
+        let mut live = live_data(None);
+        live.account_snapshots.clear();
+        let mut bytes = vec![2];
+        bytes.extend(&u16::MAX.to_le_bytes());
+        live.parameters.encode(&mut bytes);
+        encode_vector(&live.trades, &mut bytes);
+        encode_vector(&live.signals, &mut bytes);
+        live.account.encode(&mut bytes);
+
+        // Test
+        let decoded = LiveDecoder::new(bytes.as_slice()).decode_live()?;
+
+        // Validate
+        assert_eq!(live, decoded);
+        Ok(())
+    }
+
+    #[test]
+    fn live_account_summary() {
+        let mut live = live_data(None);
+        let midday = AccountSnapshot {
+            timestamp: 1704903500,
+            ..live.account.start()
+        };
+        let close = AccountSnapshot {
+            timestamp: 1704905000,
+            net_liquidation: 7962018000000000,
+            ..live.account.end()
+        };
+
+        // Test
+        live.push_account_snapshot(midday);
+        live.push_account_snapshot(close.clone());
+        let summary = live.account_summary();
+
+        // Validate
+        assert_eq!(live.account_snapshots.len(), 4);
+        assert_eq!(summary.start(), live.account.start());
+        assert_eq!(summary.end(), close);
+        assert_eq!(summary.currency, "USD");
+
+        // Without snapshots the recorded summary is kept
+        live.account_snapshots.clear();
+        assert_eq!(live.account_summary(), live.account);
+    }
+
+    #[test]
+    fn livedecoder_unknown_version() -> anyhow::Result<()> {
+        let mut bytes = Vec::new();
+        let mut encoder = LiveEncoder::new(&mut bytes);
+        encoder.encode_live(&live_data(None));
+        bytes[0] = LIVE_FORMAT_VERSION + 1;
+
+        // Test
+        let mut decoder = LiveDecoder::new(bytes.as_slice());
+        let result = decoder.decode_live();
+
+        // Validate
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
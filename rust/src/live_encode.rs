@@ -0,0 +1,117 @@
+use crate::backtest::Encode;
+use crate::live::LiveData;
+
+/// Version of the live session binary layout, written as the first byte.
+///
+/// Version 1 stored trade actions, order types and signal prices as strings, and versions
+/// before 3 had no account snapshots.
+pub const LIVE_FORMAT_VERSION: u8 = 3;
+
+pub struct LiveEncoder<'a> {
+    buffer: &'a mut Vec<u8>,
+}
+
+impl<'a> LiveEncoder<'a> {
+    pub fn new(buffer: &'a mut Vec<u8>) -> Self {
+        LiveEncoder { buffer }
+    }
+
+    pub fn encode_live(&mut self, live: &LiveData) {
+        self.buffer.push(LIVE_FORMAT_VERSION);
+        live.encode(self.buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::{Parameters, SignalInstructions, Signals, Trades};
+    use crate::enums::{OrderType, TradeAction};
+    use crate::live::AccountSummary;
+
+    #[test]
+    fn liveencoder() -> anyhow::Result<()> {
+        let params = Parameters {
+            strategy_name: "Testing".to_string(),
+            capital: 10000,
+            schema: "Ohlcv-1s".to_string(),
+            data_type: "BAR".to_string(),
+            start: 1730160814000000000,
+            end: 1730160814000000000,
+            tickers: vec!["HE.n.0".to_string(), "AAPL".to_string()],
+        };
+
+        let trade = Trades {
+            trade_id: 1,
+            signal_id: 1,
+            timestamp: 1704903000,
+            ticker: "AAPL".to_string(),
+            quantity: 4,
+            avg_price: 13074,
+            trade_value: -52296,
+            trade_cost: -52296,
+            action: TradeAction::Buy,
+            fees: 100,
+        };
+
+        let instructions = SignalInstructions {
+            ticker: "AAPL".to_string(),
+            order_type: OrderType::Market,
+            action: TradeAction::Buy,
+            signal_id: 2,
+            weight: 13213432,
+            quantity: 2343,
+            limit_price: Some(12341),
+            aux_price: Some(1233212),
+        };
+
+        let signal = Signals {
+            timestamp: 1234565432345,
+            trade_instructions: vec![instructions],
+        };
+
+        let account = AccountSummary {
+            currency: "USD".to_string(),
+            start_timestamp: 1704903000,
+            start_buying_power: 7962017000000000,
+            start_excess_liquidity: 7962017000000000,
+            start_full_available_funds: 7962017000000000,
+            start_full_init_margin_req: 0,
+            start_full_maint_margin_req: 0,
+            start_futures_pnl: 0,
+            start_net_liquidation: 7962017000000000,
+            start_total_cash_balance: 7962017000000000,
+            start_unrealized_pnl: 0,
+            end_timestamp: 1704904000,
+            end_buying_power: 7962017000000000,
+            end_excess_liquidity: 7962017000000000,
+            end_full_available_funds: 7962017000000000,
+            end_full_init_margin_req: 0,
+            end_full_maint_margin_req: 0,
+            end_futures_pnl: 0,
+            end_net_liquidation: 7962017000000000,
+            end_total_cash_balance: 7962017000000000,
+            end_unrealized_pnl: 0,
+        };
+
+        let live = LiveData {
+            live_id: None,
+            parameters: params,
+            trades: vec![trade],
+            signals: vec![signal],
+            account,
+            account_snapshots: vec![],
+        };
+
+        // Encode
+        let mut bytes = Vec::new();
+        let mut encoder = LiveEncoder::new(&mut bytes);
+        encoder.encode_live(&live);
+
+        // Validate
+        assert_eq!(bytes[0], LIVE_FORMAT_VERSION);
+        assert!(bytes.len() > 1);
+
+        Ok(())
+    }
+}
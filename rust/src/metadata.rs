@@ -46,7 +46,7 @@ impl Metadata {
     }
 
     pub fn deserialize(bytes: &[u8]) -> io::Result<Metadata> {
-        if bytes.len() < 17 {
+        if bytes.len() < 18 {
             return Err(io::Error::new(
                 io::ErrorKind::InvalidData,
                 "Insufficient data to deserialize metadata",
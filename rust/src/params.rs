@@ -1,6 +1,8 @@
 use crate::enums::{Dataset, RType, Schema, Stype};
+use crate::filter::RecordFilter;
+use crate::metadata::Metadata;
 use crate::utils::date_to_unix_nanos;
-use crate::Result;
+use crate::{Error, Result};
 use serde::{Deserialize, Serialize};
 
 #[cfg(feature = "python")]
@@ -81,11 +83,48 @@ impl RetrieveParams {
             Ok(())
         }
     }
+
+    /// Params with the time range widened to whole schema intervals, as the server applies them.
+    pub fn adjusted(&self) -> Result<RetrieveParams> {
+        let mut params = self.clone();
+        params.interval_adjust_ts_start()?;
+        params.interval_adjust_ts_end()?;
+        Ok(params)
+    }
+
+    /// `RecordFilter` selecting the matching records of a file described by `metadata`.
+    ///
+    /// Symbols are resolved to instrument ids through the file's mappings, the end of the
+    /// adjusted time range is exclusive. Fails when the file holds another schema or dataset.
+    pub fn record_filter(&self, metadata: &Metadata) -> Result<RecordFilter> {
+        if metadata.schema != self.schema || metadata.dataset != self.dataset {
+            return Err(Error::CustomError(format!(
+                "File holds {} {:?} data, requested {} {:?}",
+                metadata.schema, metadata.dataset, self.schema, self.dataset
+            )));
+        }
+
+        let params = self.adjusted()?;
+        let instrument_ids = metadata
+            .mappings
+            .map
+            .iter()
+            .filter(|(_, ticker)| params.symbols.contains(ticker))
+            .map(|(id, _)| *id);
+
+        Ok(RecordFilter::new()
+            .instrument_ids(instrument_ids)
+            .rtype(params.rtype()?)
+            .start(params.start_ts.max(0) as u64)
+            .end(params.end_ts.max(0) as u64))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::records::{OhlcvMsg, RecordHeader, TradeMsg};
+    use crate::symbols::SymbolMap;
 
     #[test]
     fn test_retrieve_params_schema() -> anyhow::Result<()> {
@@ -137,4 +176,57 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_retrieve_params_record_filter() -> anyhow::Result<()> {
+        let minute = 60_000_000_000;
+        let params = RetrieveParams {
+            symbols: vec!["TSLA".to_string()],
+            start_ts: minute + 5,
+            end_ts: 3 * minute + 5,
+            schema: Schema::Ohlcv1M,
+            dataset: Dataset::Equities,
+            stype: Stype::Raw,
+        };
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        mappings.add_instrument("TSLA", 2);
+        let metadata = Metadata::new(Schema::Ohlcv1M, Dataset::Equities, 0, 0, mappings);
+        let header = |instrument_id, ts_event| {
+            RecordHeader::new::<OhlcvMsg>(instrument_id, ts_event as u64, 0)
+        };
+
+        // Test
+        let filter = params.record_filter(&metadata)?;
+
+        // Validate
+        assert!(filter.matches(&header(2, minute)));
+        assert!(filter.matches(&header(2, 3 * minute)));
+        assert!(!filter.matches(&header(2, 4 * minute)));
+        assert!(!filter.matches(&header(1, 2 * minute)));
+        assert!(!filter.matches(&RecordHeader::new::<TradeMsg>(2, 2 * minute as u64, 0)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_retrieve_params_record_filter_incompatible() {
+        let params = RetrieveParams {
+            symbols: vec!["AAPL".to_string()],
+            start_ts: 0,
+            end_ts: 1,
+            schema: Schema::Ohlcv1M,
+            dataset: Dataset::Equities,
+            stype: Stype::Raw,
+        };
+        let schema = Metadata::new(Schema::Ohlcv1S, Dataset::Equities, 0, 0, SymbolMap::new());
+        let dataset = Metadata::new(Schema::Ohlcv1M, Dataset::Futures, 0, 0, SymbolMap::new());
+
+        // Test
+        let schema_result = params.record_filter(&schema);
+        let dataset_result = params.record_filter(&dataset);
+
+        // Validate
+        assert!(schema_result.is_err());
+        assert!(dataset_result.is_err());
+    }
 }
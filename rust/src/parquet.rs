@@ -0,0 +1,982 @@
+use crate::backtest::{BacktestData, PositionStats, Signals, TimeseriesStats, Trades};
+use crate::enums::RType;
+use crate::metadata::Metadata;
+use crate::record_enum::RecordEnum;
+use crate::records::{
+    BboMsg, BidAskPair, Mbp1Msg, OhlcvMsg, Record, RecordHeader, TbboMsg, TradeMsg,
+};
+use crate::symbols::SymbolMap;
+use crate::utils::unix_nanos_to_day;
+use crate::{Error, Result};
+use arrow::array::{
+    ArrayRef, AsArray, Int32Array, Int64Array, RecordBatch, StringArray, UInt32Array, UInt64Array,
+    UInt8Array,
+};
+use arrow::datatypes::{
+    ArrowPrimitiveType, DataType, Field, Int32Type, Int64Type, Schema, UInt32Type, UInt64Type,
+    UInt8Type,
+};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::os::raw::c_char;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Directory layout used by `write_records_partitioned`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Partition {
+    /// Single file directly in the target directory.
+    None,
+    /// One `date=YYYY-MM-DD` directory per UTC day of `ts_event`.
+    Date,
+    /// One `symbol=TICKER` directory per instrument, the ticker escaped by
+    /// `escape_partition_value`.
+    Instrument,
+    /// `date=YYYY-MM-DD/symbol=TICKER`.
+    DateInstrument,
+}
+
+/// Percent-encodes the characters Hive escapes in partition values, so any ticker is a
+/// single directory name.
+pub fn escape_partition_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_control() || "\"#%'*/:=?\\{[]^".contains(c) {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+/// Reverses `escape_partition_value`.
+pub fn unescape_partition_value(value: &str) -> Result<String> {
+    let invalid = || Error::Decode(format!("Invalid partition value: {}", value));
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = tail.get(..2).ok_or_else(invalid)?;
+            let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+            bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+fn writer_properties() -> WriterProperties {
+    WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build()
+}
+
+fn write_batch<W: Write + Send>(writer: W, batch: &RecordBatch) -> Result<()> {
+    let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(writer_properties()))?;
+    writer.write(batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+fn field(name: &str, data_type: DataType) -> Field {
+    Field::new(name, data_type, false)
+}
+
+/// Columns shared by every record table.
+fn header_fields() -> Vec<Field> {
+    vec![
+        field("rtype", DataType::UInt8),
+        field("instrument_id", DataType::UInt32),
+        field("ts_event", DataType::UInt64),
+        field("rollover_flag", DataType::UInt8),
+        Field::new("symbol", DataType::Utf8, true),
+    ]
+}
+
+fn level_fields() -> Vec<Field> {
+    vec![
+        field("bid_px", DataType::Int64),
+        field("ask_px", DataType::Int64),
+        field("bid_sz", DataType::UInt32),
+        field("ask_sz", DataType::UInt32),
+        field("bid_ct", DataType::UInt32),
+        field("ask_ct", DataType::UInt32),
+    ]
+}
+
+fn trade_fields() -> Vec<Field> {
+    vec![
+        field("price", DataType::Int64),
+        field("size", DataType::UInt32),
+        field("action", DataType::UInt8),
+        field("side", DataType::UInt8),
+        field("depth", DataType::UInt8),
+        field("flags", DataType::UInt8),
+        field("ts_recv", DataType::UInt64),
+        field("ts_in_delta", DataType::Int32),
+        field("sequence", DataType::UInt32),
+    ]
+}
+
+/// Arrow schema of the table written for records of the given `RType`.
+pub fn record_schema(rtype: &RType) -> Schema {
+    let mut fields = header_fields();
+    match rtype {
+        RType::Mbp1 | RType::Tbbo => {
+            fields.extend(trade_fields());
+            fields.push(field("discriminator", DataType::UInt32));
+            fields.extend(level_fields());
+        }
+        RType::Trades => fields.extend(trade_fields()),
+        RType::Bbo => fields.extend(level_fields()),
+        RType::Ohlcv => {
+            fields.push(field("open", DataType::Int64));
+            fields.push(field("high", DataType::Int64));
+            fields.push(field("low", DataType::Int64));
+            fields.push(field("close", DataType::Int64));
+            fields.push(field("volume", DataType::UInt64));
+        }
+    }
+    Schema::new(fields)
+}
+
+fn u8_column<T>(records: &[&RecordEnum], f: impl Fn(&RecordEnum) -> T) -> ArrayRef
+where
+    T: Into<u8>,
+{
+    Arc::new(UInt8Array::from_iter_values(
+        records.iter().map(|r| f(r).into()),
+    ))
+}
+
+fn u32_column(records: &[&RecordEnum], f: impl Fn(&RecordEnum) -> u32) -> ArrayRef {
+    Arc::new(UInt32Array::from_iter_values(records.iter().map(|r| f(r))))
+}
+
+fn u64_column(records: &[&RecordEnum], f: impl Fn(&RecordEnum) -> u64) -> ArrayRef {
+    Arc::new(UInt64Array::from_iter_values(records.iter().map(|r| f(r))))
+}
+
+fn i32_column(records: &[&RecordEnum], f: impl Fn(&RecordEnum) -> i32) -> ArrayRef {
+    Arc::new(Int32Array::from_iter_values(records.iter().map(|r| f(r))))
+}
+
+fn i64_column(records: &[&RecordEnum], f: impl Fn(&RecordEnum) -> i64) -> ArrayRef {
+    Arc::new(Int64Array::from_iter_values(records.iter().map(|r| f(r))))
+}
+
+/// Fields of the MBP-1 style records (Mbp1, Tbbo and Trades share a layout up to `sequence`).
+fn mbp_like(record: &RecordEnum) -> (i64, u32, c_char, c_char, u8, u8, u64, i32, u32) {
+    match record {
+        RecordEnum::Mbp1(msg) | RecordEnum::Tbbo(msg) => (
+            msg.price,
+            msg.size,
+            msg.action,
+            msg.side,
+            msg.depth,
+            msg.flags,
+            msg.ts_recv,
+            msg.ts_in_delta,
+            msg.sequence,
+        ),
+        RecordEnum::Trade(msg) => (
+            msg.price,
+            msg.size,
+            msg.action,
+            msg.side,
+            msg.depth,
+            msg.flags,
+            msg.ts_recv,
+            msg.ts_in_delta,
+            msg.sequence,
+        ),
+        _ => unreachable!("record is not trade-like"),
+    }
+}
+
+fn levels(record: &RecordEnum) -> &BidAskPair {
+    match record {
+        RecordEnum::Mbp1(msg) | RecordEnum::Tbbo(msg) => &msg.levels[0],
+        RecordEnum::Bbo(msg) => &msg.levels[0],
+        _ => unreachable!("record has no book levels"),
+    }
+}
+
+fn ohlcv(record: &RecordEnum) -> &OhlcvMsg {
+    match record {
+        RecordEnum::Ohlcv(msg) => msg,
+        _ => unreachable!("record is not ohlcv"),
+    }
+}
+
+fn trade_columns(records: &[&RecordEnum]) -> Vec<ArrayRef> {
+    vec![
+        i64_column(records, |r| mbp_like(r).0),
+        u32_column(records, |r| mbp_like(r).1),
+        u8_column(records, |r| mbp_like(r).2 as u8),
+        u8_column(records, |r| mbp_like(r).3 as u8),
+        u8_column(records, |r| mbp_like(r).4),
+        u8_column(records, |r| mbp_like(r).5),
+        u64_column(records, |r| mbp_like(r).6),
+        i32_column(records, |r| mbp_like(r).7),
+        u32_column(records, |r| mbp_like(r).8),
+    ]
+}
+
+fn level_columns(records: &[&RecordEnum]) -> Vec<ArrayRef> {
+    vec![
+        i64_column(records, |r| levels(r).bid_px),
+        i64_column(records, |r| levels(r).ask_px),
+        u32_column(records, |r| levels(r).bid_sz),
+        u32_column(records, |r| levels(r).ask_sz),
+        u32_column(records, |r| levels(r).bid_ct),
+        u32_column(records, |r| levels(r).ask_ct),
+    ]
+}
+
+/// Builds a single arrow batch out of records sharing the same `RType`.
+pub fn records_to_batch(
+    rtype: &RType,
+    records: &[&RecordEnum],
+    mappings: &SymbolMap,
+) -> Result<RecordBatch> {
+    if let Some(record) = records.iter().find(|r| r.header().rtype() != *rtype) {
+        return Err(Error::Encode(format!(
+            "Expected {} records, found {}",
+            rtype,
+            record.header().rtype()
+        )));
+    }
+
+    let symbols: StringArray = records
+        .iter()
+        .map(|r| mappings.get_instrument_ticker(r.header().instrument_id))
+        .collect();
+
+    let mut columns: Vec<ArrayRef> = vec![
+        u8_column(records, |r| r.header().rtype),
+        u32_column(records, |r| r.header().instrument_id),
+        u64_column(records, |r| r.header().ts_event),
+        u8_column(records, |r| r.header().rollover_flag),
+        Arc::new(symbols),
+    ];
+
+    match rtype {
+        RType::Mbp1 | RType::Tbbo => {
+            columns.extend(trade_columns(records));
+            columns.push(u32_column(records, |r| match r {
+                RecordEnum::Mbp1(msg) | RecordEnum::Tbbo(msg) => msg.discriminator,
+                _ => 0,
+            }));
+            columns.extend(level_columns(records));
+        }
+        RType::Trades => columns.extend(trade_columns(records)),
+        RType::Bbo => columns.extend(level_columns(records)),
+        RType::Ohlcv => {
+            columns.push(i64_column(records, |r| ohlcv(r).open));
+            columns.push(i64_column(records, |r| ohlcv(r).high));
+            columns.push(i64_column(records, |r| ohlcv(r).low));
+            columns.push(i64_column(records, |r| ohlcv(r).close));
+            columns.push(u64_column(records, |r| ohlcv(r).volume));
+        }
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::new(record_schema(rtype)),
+        columns,
+    )?)
+}
+
+/// Writes a decoded record stream as a single parquet table.
+///
+/// All records must share the record type implied by `metadata.schema`.
+pub fn write_records<W: Write + Send>(
+    writer: W,
+    metadata: &Metadata,
+    records: &[RecordEnum],
+) -> Result<()> {
+    let rtype = RType::from(metadata.schema);
+    let refs: Vec<&RecordEnum> = records.iter().collect();
+    let batch = records_to_batch(&rtype, &refs, &metadata.mappings)?;
+    write_batch(writer, &batch)
+}
+
+/// Writes a decoded record stream under `dir`, split into hive-style partitions.
+///
+/// Instruments are named with `Metadata::mappings`, falling back to the instrument id when
+/// no ticker is known. Returns the paths of the files written.
+pub fn write_records_partitioned<P: AsRef<Path>>(
+    dir: P,
+    metadata: &Metadata,
+    records: &[RecordEnum],
+    partition: Partition,
+) -> Result<Vec<PathBuf>> {
+    let rtype = RType::from(metadata.schema);
+    let mut partitions: BTreeMap<PathBuf, Vec<&RecordEnum>> = BTreeMap::new();
+
+    for record in records {
+        let header = record.header();
+        let date = || -> Result<String> {
+            Ok(format!(
+                "date={}",
+                unix_nanos_to_day(header.ts_event as i64)?
+            ))
+        };
+        let symbol = || {
+            let ticker = metadata
+                .mappings
+                .get_instrument_ticker(header.instrument_id)
+                .unwrap_or_else(|| header.instrument_id.to_string());
+            format!("symbol={}", escape_partition_value(&ticker))
+        };
+
+        let key = match partition {
+            Partition::None => PathBuf::new(),
+            Partition::Date => PathBuf::from(date()?),
+            Partition::Instrument => PathBuf::from(symbol()),
+            Partition::DateInstrument => PathBuf::from(date()?).join(symbol()),
+        };
+        partitions.entry(key).or_default().push(record);
+    }
+
+    let mut paths = Vec::with_capacity(partitions.len());
+    for (key, group) in partitions {
+        let partition_dir = dir.as_ref().join(key);
+        std::fs::create_dir_all(&partition_dir)?;
+
+        let path = partition_dir.join(format!("{}.parquet", metadata.schema));
+        let batch = records_to_batch(&rtype, &group, &metadata.mappings)?;
+        write_batch(File::create(&path)?, &batch)?;
+        paths.push(path);
+    }
+
+    Ok(paths)
+}
+
+fn column<'a, T: ArrowPrimitiveType>(
+    batch: &'a RecordBatch,
+    name: &str,
+) -> Result<&'a arrow::array::PrimitiveArray<T>> {
+    let array = batch
+        .column_by_name(name)
+        .ok_or_else(|| Error::Decode(format!("Missing column '{}'", name)))?;
+    array
+        .as_primitive_opt::<T>()
+        .ok_or_else(|| Error::Decode(format!("Unexpected type for column '{}'", name)))
+}
+
+fn batch_to_records(batch: &RecordBatch, records: &mut Vec<RecordEnum>) -> Result<()> {
+    let rtype = column::<UInt8Type>(batch, "rtype")?;
+    let instrument_id = column::<UInt32Type>(batch, "instrument_id")?;
+    let ts_event = column::<UInt64Type>(batch, "ts_event")?;
+    let rollover_flag = column::<UInt8Type>(batch, "rollover_flag")?;
+
+    for row in 0..batch.num_rows() {
+        let rtype_byte = rtype.value(row);
+        let rtype = RType::try_from(rtype_byte)?;
+        let header = |hd: RecordHeader| RecordHeader {
+            rtype: rtype_byte,
+            ..hd
+        };
+        let i64_at =
+            |name: &str| -> Result<i64> { Ok(column::<Int64Type>(batch, name)?.value(row)) };
+        let u32_at =
+            |name: &str| -> Result<u32> { Ok(column::<UInt32Type>(batch, name)?.value(row)) };
+        let u8_at = |name: &str| -> Result<u8> { Ok(column::<UInt8Type>(batch, name)?.value(row)) };
+        let level = || -> Result<BidAskPair> {
+            Ok(BidAskPair {
+                bid_px: i64_at("bid_px")?,
+                ask_px: i64_at("ask_px")?,
+                bid_sz: u32_at("bid_sz")?,
+                ask_sz: u32_at("ask_sz")?,
+                bid_ct: u32_at("bid_ct")?,
+                ask_ct: u32_at("ask_ct")?,
+            })
+        };
+
+        let (id, ts, rollover) = (
+            instrument_id.value(row),
+            ts_event.value(row),
+            rollover_flag.value(row),
+        );
+
+        let record = match rtype {
+            RType::Mbp1 | RType::Tbbo => {
+                let msg = Mbp1Msg {
+                    hd: header(RecordHeader::new::<Mbp1Msg>(id, ts, rollover)),
+                    price: i64_at("price")?,
+                    size: u32_at("size")?,
+                    action: u8_at("action")? as c_char,
+                    side: u8_at("side")? as c_char,
+                    depth: u8_at("depth")?,
+                    flags: u8_at("flags")?,
+                    ts_recv: column::<UInt64Type>(batch, "ts_recv")?.value(row),
+                    ts_in_delta: column::<Int32Type>(batch, "ts_in_delta")?.value(row),
+                    sequence: u32_at("sequence")?,
+                    discriminator: u32_at("discriminator")?,
+                    levels: [level()?],
+                };
+                if rtype == RType::Tbbo {
+                    RecordEnum::Tbbo(msg as TbboMsg)
+                } else {
+                    RecordEnum::Mbp1(msg)
+                }
+            }
+            RType::Trades => RecordEnum::Trade(TradeMsg {
+                hd: RecordHeader::new::<TradeMsg>(id, ts, rollover),
+                price: i64_at("price")?,
+                size: u32_at("size")?,
+                action: u8_at("action")? as c_char,
+                side: u8_at("side")? as c_char,
+                depth: u8_at("depth")?,
+                flags: u8_at("flags")?,
+                ts_recv: column::<UInt64Type>(batch, "ts_recv")?.value(row),
+                ts_in_delta: column::<Int32Type>(batch, "ts_in_delta")?.value(row),
+                sequence: u32_at("sequence")?,
+            }),
+            RType::Bbo => RecordEnum::Bbo(BboMsg {
+                hd: RecordHeader::new::<BboMsg>(id, ts, rollover),
+                levels: [level()?],
+            }),
+            RType::Ohlcv => RecordEnum::Ohlcv(OhlcvMsg {
+                hd: RecordHeader::new::<OhlcvMsg>(id, ts, rollover),
+                open: i64_at("open")?,
+                high: i64_at("high")?,
+                low: i64_at("low")?,
+                close: i64_at("close")?,
+                volume: column::<UInt64Type>(batch, "volume")?.value(row),
+            }),
+        };
+        records.push(record);
+    }
+    Ok(())
+}
+
+/// Reads a record table written by `write_records` back into records.
+pub fn read_records<P: AsRef<Path>>(file_path: P) -> Result<Vec<RecordEnum>> {
+    let file = File::open(file_path.as_ref())?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+
+    let mut records = Vec::new();
+    for batch in reader {
+        batch_to_records(&batch?, &mut records)?;
+    }
+    Ok(records)
+}
+
+/// Reads every parquet file below `dir` (as written by `write_records_partitioned`),
+/// returning the records sorted by `ts_event`. Fails on a `symbol=` directory that is not
+/// escaped by `escape_partition_value`.
+pub fn read_records_partitioned<P: AsRef<Path>>(dir: P) -> Result<Vec<RecordEnum>> {
+    let mut files = Vec::new();
+    collect_parquet_files(dir.as_ref(), &mut files)?;
+    files.sort();
+
+    let mut records = Vec::new();
+    for file in files {
+        let relative = file.strip_prefix(dir.as_ref()).unwrap_or(&file);
+        for component in relative.iter().filter_map(|c| c.to_str()) {
+            if let Some(value) = component.strip_prefix("symbol=") {
+                unescape_partition_value(value)?;
+            }
+        }
+        records.extend(read_records(file)?);
+    }
+    records.sort_by_key(|r| r.header().ts_event);
+    Ok(records)
+}
+
+fn collect_parquet_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_parquet_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "parquet") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn timeseries_batch(stats: &[TimeseriesStats]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        field("timestamp", DataType::Int64),
+        field("equity_value", DataType::Int64),
+        field("percent_drawdown", DataType::Int64),
+        field("cumulative_return", DataType::Int64),
+        field("period_return", DataType::Int64),
+    ]);
+    let column = |f: fn(&TimeseriesStats) -> i64| -> ArrayRef {
+        Arc::new(Int64Array::from_iter_values(stats.iter().map(f)))
+    };
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            column(|s| s.timestamp),
+            column(|s| s.equity_value),
+            column(|s| s.percent_drawdown),
+            column(|s| s.cumulative_return),
+            column(|s| s.period_return),
+        ],
+    )?)
+}
+
+fn trades_batch(trades: &[Trades]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        field("trade_id", DataType::Int32),
+        field("signal_id", DataType::Int32),
+        field("timestamp", DataType::Int64),
+        field("ticker", DataType::Utf8),
+        field("quantity", DataType::Int64),
+        field("avg_price", DataType::Int64),
+        field("trade_value", DataType::Int64),
+        field("trade_cost", DataType::Int64),
+        field("action", DataType::Utf8),
+        field("fees", DataType::Int64),
+    ]);
+    let int64 = |f: fn(&Trades) -> i64| -> ArrayRef {
+        Arc::new(Int64Array::from_iter_values(trades.iter().map(f)))
+    };
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int32Array::from_iter_values(
+                trades.iter().map(|t| t.trade_id),
+            )),
+            Arc::new(Int32Array::from_iter_values(
+                trades.iter().map(|t| t.signal_id),
+            )),
+            int64(|t| t.timestamp),
+            Arc::new(StringArray::from_iter_values(
+                trades.iter().map(|t| t.ticker.as_str()),
+            )),
+            int64(|t| t.quantity),
+            int64(|t| t.avg_price),
+            int64(|t| t.trade_value),
+            int64(|t| t.trade_cost),
+            Arc::new(StringArray::from_iter_values(
+                trades.iter().map(|t| t.action.as_str()),
+            )),
+            int64(|t| t.fees),
+        ],
+    )?)
+}
+
+/// Signals are flattened to one row per trade instruction.
+fn signals_batch(signals: &[Signals]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        field("timestamp", DataType::Int64),
+        field("ticker", DataType::Utf8),
+        field("order_type", DataType::Utf8),
+        field("action", DataType::Utf8),
+        field("signal_id", DataType::Int32),
+        field("weight", DataType::Int64),
+        field("quantity", DataType::Int32),
+        Field::new("limit_price", DataType::Int64, true),
+        Field::new("aux_price", DataType::Int64, true),
+    ]);
+    let rows: Vec<_> = signals
+        .iter()
+        .flat_map(|s| s.trade_instructions.iter().map(move |i| (s.timestamp, i)))
+        .collect();
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            Arc::new(Int64Array::from_iter_values(rows.iter().map(|r| r.0))),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|r| r.1.ticker.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|r| r.1.order_type.as_str()),
+            )),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|r| r.1.action.as_str()),
+            )),
+            Arc::new(Int32Array::from_iter_values(
+                rows.iter().map(|r| r.1.signal_id),
+            )),
+            Arc::new(Int64Array::from_iter_values(
+                rows.iter().map(|r| r.1.weight),
+            )),
+            Arc::new(Int32Array::from_iter_values(
+                rows.iter().map(|r| r.1.quantity),
+            )),
+            Arc::new(Int64Array::from_iter(rows.iter().map(|r| r.1.limit_price))),
+            Arc::new(Int64Array::from_iter(rows.iter().map(|r| r.1.aux_price))),
+        ],
+    )?)
+}
+
+fn positions_batch(positions: &[PositionStats]) -> Result<RecordBatch> {
+    let schema = Schema::new(vec![
+        field("timestamp", DataType::Int64),
+        field("ticker", DataType::Utf8),
+        field("quantity", DataType::Int64),
+        field("avg_cost", DataType::Int64),
+        field("market_price", DataType::Int64),
+        field("market_value", DataType::Int64),
+        field("realized_pnl", DataType::Int64),
+        field("unrealized_pnl", DataType::Int64),
+    ]);
+    let int64 = |f: fn(&PositionStats) -> i64| -> ArrayRef {
+        Arc::new(Int64Array::from_iter_values(positions.iter().map(f)))
+    };
+
+    Ok(RecordBatch::try_new(
+        Arc::new(schema),
+        vec![
+            int64(|p| p.timestamp),
+            Arc::new(StringArray::from_iter_values(
+                positions.iter().map(|p| p.ticker.as_str()),
+            )),
+            int64(|p| p.quantity),
+            int64(|p| p.avg_cost),
+            int64(|p| p.market_price),
+            int64(|p| p.market_value),
+            int64(|p| p.realized_pnl),
+            int64(|p| p.unrealized_pnl),
+        ],
+    )?)
+}
+
+/// Writes a backtest under `dir` as separate tables: `period_timeseries_stats.parquet`,
+/// `daily_timeseries_stats.parquet`, `trades.parquet`, `signals.parquet` and
+/// `position_timeseries.parquet`.
+pub fn write_backtest<P: AsRef<Path>>(dir: P, backtest: &BacktestData) -> Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+
+    let tables = [
+        (
+            "period_timeseries_stats",
+            timeseries_batch(&backtest.period_timeseries_stats)?,
+        ),
+        (
+            "daily_timeseries_stats",
+            timeseries_batch(&backtest.daily_timeseries_stats)?,
+        ),
+        ("trades", trades_batch(&backtest.trades)?),
+        ("signals", signals_batch(&backtest.signals)?),
+        (
+            "position_timeseries",
+            positions_batch(&backtest.position_timeseries)?,
+        ),
+    ];
+
+    let mut paths = Vec::with_capacity(tables.len());
+    for (name, batch) in tables {
+        let path = dir.join(format!("{}.parquet", name));
+        write_batch(File::create(&path)?, &batch)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::{BacktestMetaData, Parameters, SignalInstructions, StaticStats};
+    use crate::enums::{Dataset, OrderType, Schema as MbinarySchema, TradeAction};
+    use serial_test::serial;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = PathBuf::from("tests").join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn mbp1(instrument_id: u32, ts_event: u64) -> Mbp1Msg {
+        Mbp1Msg {
+            hd: RecordHeader::new::<Mbp1Msg>(instrument_id, ts_event, 0),
+            price: 12345676543,
+            size: 1234543,
+            action: b'T' as c_char,
+            side: b'A' as c_char,
+            depth: 0,
+            flags: 0,
+            ts_recv: ts_event,
+            ts_in_delta: 123432,
+            sequence: 23432,
+            discriminator: 0,
+            levels: [BidAskPair {
+                bid_px: 10000000,
+                ask_px: 200000,
+                bid_sz: 3000000,
+                ask_sz: 400000000,
+                bid_ct: 50000000,
+                ask_ct: 60000000,
+            }],
+        }
+    }
+
+    fn metadata(schema: MbinarySchema) -> Metadata {
+        let mut symbol_map = SymbolMap::new();
+        symbol_map.add_instrument("AAPL", 1);
+        symbol_map.add_instrument("TSLA", 2);
+        Metadata::new(
+            schema,
+            Dataset::Equities,
+            1704067200000000000,
+            1704240000000000000,
+            symbol_map,
+        )
+    }
+
+    #[test]
+    #[serial]
+    fn test_records_round_trip() -> anyhow::Result<()> {
+        let metadata = metadata(MbinarySchema::Ohlcv1S);
+        let records = vec![
+            RecordEnum::Ohlcv(OhlcvMsg {
+                hd: RecordHeader::new::<OhlcvMsg>(1, 1704067200000000000, 0),
+                open: 100,
+                high: 200,
+                low: 50,
+                close: 150,
+                volume: 1000,
+            }),
+            RecordEnum::Ohlcv(OhlcvMsg {
+                hd: RecordHeader::new::<OhlcvMsg>(2, 1704067201000000000, 1),
+                open: 110,
+                high: 210,
+                low: 55,
+                close: 155,
+                volume: 1100,
+            }),
+        ];
+        let dir = test_dir("parquet_records");
+        std::fs::create_dir_all(&dir)?;
+        let file = dir.join("ohlcv.parquet");
+
+        // Test
+        write_records(File::create(&file)?, &metadata, &records)?;
+        let decoded = read_records(&file)?;
+
+        // Validate
+        assert_eq!(records, decoded);
+
+        // Cleanup
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_records_partitioned_round_trip() -> anyhow::Result<()> {
+        let metadata = metadata(MbinarySchema::Mbp1);
+        let records = vec![
+            RecordEnum::Mbp1(mbp1(1, 1704067200000000000)),
+            RecordEnum::Mbp1(mbp1(2, 1704067201000000000)),
+            RecordEnum::Mbp1(mbp1(1, 1704153600000000000)),
+            RecordEnum::Mbp1(mbp1(3, 1704153601000000000)),
+        ];
+        let dir = test_dir("parquet_partitioned");
+
+        // Test
+        let paths =
+            write_records_partitioned(&dir, &metadata, &records, Partition::DateInstrument)?;
+        let decoded = read_records_partitioned(&dir)?;
+
+        // Validate
+        assert_eq!(paths.len(), 4);
+        assert!(paths.contains(
+            &dir.join("date=2024-01-02")
+                .join("symbol=3")
+                .join("mbp-1.parquet")
+        ));
+        assert!(paths.contains(
+            &dir.join("date=2024-01-01")
+                .join("symbol=AAPL")
+                .join("mbp-1.parquet")
+        ));
+        assert_eq!(records, decoded);
+
+        // Cleanup
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_records_partitioned_escaped_ticker() -> anyhow::Result<()> {
+        let mut metadata = metadata(MbinarySchema::Mbp1);
+        metadata.mappings.add_instrument("BRK/B", 3);
+        metadata.mappings.add_instrument("../x", 4);
+        let records = vec![
+            RecordEnum::Mbp1(mbp1(3, 1704067200000000000)),
+            RecordEnum::Mbp1(mbp1(4, 1704067201000000000)),
+        ];
+        let dir = test_dir("parquet_escaped");
+
+        // Test
+        let paths = write_records_partitioned(&dir, &metadata, &records, Partition::Instrument)?;
+        let decoded = read_records_partitioned(&dir)?;
+
+        // Validate
+        assert_eq!(
+            paths,
+            vec![
+                dir.join("symbol=..%2Fx").join("mbp-1.parquet"),
+                dir.join("symbol=BRK%2FB").join("mbp-1.parquet"),
+            ]
+        );
+        assert_eq!(records, decoded);
+        assert_eq!(unescape_partition_value("BRK%2FB")?, "BRK/B");
+        assert_eq!(
+            unescape_partition_value(&escape_partition_value("a=b%c:\u{e9}"))?,
+            "a=b%c:\u{e9}"
+        );
+        assert!(unescape_partition_value("BRK%2").is_err());
+
+        std::fs::create_dir_all(dir.join("symbol=%zz"))?;
+        std::fs::copy(&paths[0], dir.join("symbol=%zz").join("mbp-1.parquet"))?;
+        assert!(read_records_partitioned(&dir).is_err());
+
+        // Cleanup
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_mixed_rtype_rejected() -> anyhow::Result<()> {
+        let metadata = metadata(MbinarySchema::Mbp1);
+        let records = vec![
+            RecordEnum::Mbp1(mbp1(1, 1704067200000000000)),
+            RecordEnum::Bbo(BboMsg {
+                hd: RecordHeader::new::<BboMsg>(1, 1704067200000000000, 0),
+                levels: [BidAskPair {
+                    bid_px: 1,
+                    ask_px: 2,
+                    bid_sz: 3,
+                    ask_sz: 4,
+                    bid_ct: 5,
+                    ask_ct: 6,
+                }],
+            }),
+        ];
+
+        // Test
+        let result = write_records(Vec::new(), &metadata, &records);
+
+        // Validate
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_write_backtest() -> anyhow::Result<()> {
+        let params = Parameters {
+            strategy_name: "Testing".to_string(),
+            capital: 10000,
+            schema: "Ohlcv-1s".to_string(),
+            data_type: "BAR".to_string(),
+            start: 1730160814000000000,
+            end: 1730160814000000000,
+            tickers: vec!["AAPL".to_string()],
+        };
+        let static_stats = StaticStats {
+            total_trades: 100,
+            total_winning_trades: 50,
+            total_losing_trades: 50,
+            avg_profit: 1000000000000,
+            avg_profit_percent: 10383783337737,
+            avg_gain: 23323212233,
+            avg_gain_percent: 24323234,
+            avg_loss: 203982828,
+            avg_loss_percent: 23432134323,
+            profitability_ratio: 130213212323,
+            profit_factor: 12342123431,
+            profit_and_loss_ratio: 1234321343,
+            total_fees: 123453234,
+            net_profit: 1234323,
+            beginning_equity: 12343234323,
+            ending_equity: 12343234,
+            total_return: 234532345,
+            annualized_return: 234532345,
+            daily_standard_deviation_percentage: 23453234,
+            annual_standard_deviation_percentage: 34543443,
+            max_drawdown_percentage_period: 234543234,
+            max_drawdown_percentage_daily: 23432345,
+            sharpe_ratio: 23432343,
+            sortino_ratio: 123453234543,
+        };
+        let timeseries = TimeseriesStats {
+            timestamp: 123700000000000,
+            equity_value: 9999999,
+            percent_drawdown: 2343234,
+            cumulative_return: 2343234,
+            period_return: 2345432345,
+        };
+        let trade = Trades {
+            trade_id: 1,
+            signal_id: 1,
+            timestamp: 1704903000,
+            ticker: "AAPL".to_string(),
+            quantity: 4,
+            avg_price: 13074,
+            trade_value: -52296,
+            trade_cost: -52296,
+            action: TradeAction::Buy,
+            fees: 100,
+        };
+        let instructions = SignalInstructions {
+            ticker: "AAPL".to_string(),
+            order_type: OrderType::Market,
+            action: TradeAction::Buy,
+            signal_id: 1,
+            weight: 13213432,
+            quantity: 2343,
+            limit_price: Some(12341),
+            aux_price: Some(1233212),
+        };
+        let backtest = BacktestData {
+            metadata: BacktestMetaData::new(None, "testing", params, static_stats),
+            period_timeseries_stats: vec![timeseries.clone(), timeseries.clone()],
+            daily_timeseries_stats: vec![timeseries],
+            trades: vec![trade.clone(), trade],
+            signals: vec![Signals {
+                timestamp: 1234565432345,
+                trade_instructions: vec![instructions.clone(), instructions],
+            }],
+            position_timeseries: vec![],
+        };
+        let dir = test_dir("parquet_backtest");
+
+        // Test
+        let paths = write_backtest(&dir, &backtest)?;
+
+        // Validate
+        let rows: Vec<usize> = paths
+            .iter()
+            .map(|path| {
+                let file = File::open(path).unwrap();
+                ParquetRecordBatchReaderBuilder::try_new(file)
+                    .unwrap()
+                    .metadata()
+                    .file_metadata()
+                    .num_rows() as usize
+            })
+            .collect();
+        assert_eq!(rows, vec![2, 1, 2, 2, 0]);
+
+        // Cleanup
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,388 @@
+use crate::backtest::{StaticStats, TimeseriesStats, Trades};
+use crate::ledger::{ClosedTrade, Ledger};
+use crate::record_enum::RecordEnum;
+use crate::records::Record;
+use crate::symbols::SymbolMap;
+use crate::{Error, Result, PRICE_SCALE};
+
+const NANOS_PER_DAY: i64 = 86_400_000_000_000;
+const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Statistics derived from a strategy's capital, fills and market data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Performance {
+    pub period_timeseries_stats: Vec<TimeseriesStats>,
+    pub daily_timeseries_stats: Vec<TimeseriesStats>,
+    pub static_stats: StaticStats,
+}
+
+/// Converts a fraction or amount to the 1e9 fixed-point representation.
+fn to_fixed(value: f64) -> i64 {
+    (value * PRICE_SCALE as f64).round() as i64
+}
+
+fn from_fixed(value: i64) -> f64 {
+    value as f64 / PRICE_SCALE as f64
+}
+
+/// Builds a timeseries from (timestamp, equity) points, with returns and drawdowns measured
+/// against the previous point and the running peak, starting from `beginning_equity`.
+fn timeseries(points: &[(i64, i64)], beginning_equity: i64) -> Vec<TimeseriesStats> {
+    let mut previous = beginning_equity;
+    let mut peak = beginning_equity;
+    let ratio = |a: i64, b: i64| {
+        if b == 0 {
+            0.0
+        } else {
+            a as f64 / b as f64 - 1.0
+        }
+    };
+
+    points
+        .iter()
+        .map(|&(timestamp, equity_value)| {
+            peak = peak.max(equity_value);
+            let stats = TimeseriesStats {
+                timestamp,
+                equity_value,
+                percent_drawdown: to_fixed(ratio(equity_value, peak)),
+                cumulative_return: to_fixed(ratio(equity_value, beginning_equity)),
+                period_return: to_fixed(ratio(equity_value, previous)),
+            };
+            previous = equity_value;
+            stats
+        })
+        .collect()
+}
+
+/// Records equity at `timestamp`, replacing the sample already taken at the same timestamp.
+fn sample(points: &mut Vec<(i64, i64)>, timestamp: i64, equity: i64) {
+    match points.last_mut() {
+        Some(last) if last.0 == timestamp => last.1 = equity,
+        _ => points.push((timestamp, equity)),
+    }
+}
+
+/// Keeps the last point of each UTC day.
+fn daily_points(points: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut daily: Vec<(i64, i64)> = Vec::new();
+    for &(timestamp, equity) in points {
+        match daily.last_mut() {
+            Some(last)
+                if last.0.div_euclid(NANOS_PER_DAY) == timestamp.div_euclid(NANOS_PER_DAY) =>
+            {
+                *last = (timestamp, equity)
+            }
+            _ => daily.push((timestamp, equity)),
+        }
+    }
+    daily
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+fn std_dev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let mean = mean(values);
+    let variance =
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    variance.sqrt()
+}
+
+fn static_stats(
+    beginning_equity: i64,
+    period: &[TimeseriesStats],
+    daily: &[TimeseriesStats],
+    ledger: &Ledger,
+) -> StaticStats {
+    let closed = ledger.closed_trades();
+    let gains: Vec<&ClosedTrade> = closed.iter().filter(|t| t.pnl > 0).collect();
+    let losses: Vec<&ClosedTrade> = closed.iter().filter(|t| t.pnl < 0).collect();
+
+    let avg = |trades: &[&ClosedTrade]| -> (f64, f64) {
+        let pnl: Vec<f64> = trades.iter().map(|t| from_fixed(t.pnl)).collect();
+        let pct: Vec<f64> = trades.iter().map(|t| t.return_fraction()).collect();
+        (mean(&pnl), mean(&pct))
+    };
+    let all: Vec<&ClosedTrade> = closed.iter().collect();
+    let (avg_profit, avg_profit_percent) = avg(&all);
+    let (avg_gain, avg_gain_percent) = avg(&gains);
+    let (avg_loss, avg_loss_percent) = avg(&losses);
+
+    let gross_gain: f64 = gains.iter().map(|t| from_fixed(t.pnl)).sum();
+    let gross_loss: f64 = losses.iter().map(|t| from_fixed(t.pnl)).sum();
+
+    let ending_equity = period.last().map_or(beginning_equity, |p| p.equity_value);
+    let total_return = if beginning_equity == 0 {
+        0.0
+    } else {
+        ending_equity as f64 / beginning_equity as f64 - 1.0
+    };
+
+    let daily_returns: Vec<f64> = daily.iter().map(|d| from_fixed(d.period_return)).collect();
+    let daily_std = std_dev(&daily_returns);
+    let downside = {
+        let squared: Vec<f64> = daily_returns.iter().map(|r| r.min(0.0).powi(2)).collect();
+        mean(&squared).sqrt()
+    };
+    let annualized_return = if daily.is_empty() {
+        0.0
+    } else {
+        (1.0 + total_return).powf(TRADING_DAYS_PER_YEAR / daily.len() as f64) - 1.0
+    };
+    let scaled_ratio = |numerator: f64, denominator: f64| {
+        if denominator == 0.0 {
+            0.0
+        } else {
+            numerator / denominator * TRADING_DAYS_PER_YEAR.sqrt()
+        }
+    };
+    let ratio = |numerator: f64, denominator: f64| {
+        if denominator == 0.0 {
+            0.0
+        } else {
+            numerator / denominator
+        }
+    };
+
+    StaticStats {
+        total_trades: closed.len() as i32,
+        total_winning_trades: gains.len() as i32,
+        total_losing_trades: losses.len() as i32,
+        avg_profit: to_fixed(avg_profit),
+        avg_profit_percent: to_fixed(avg_profit_percent),
+        avg_gain: to_fixed(avg_gain),
+        avg_gain_percent: to_fixed(avg_gain_percent),
+        avg_loss: to_fixed(avg_loss),
+        avg_loss_percent: to_fixed(avg_loss_percent),
+        profitability_ratio: to_fixed(ratio(gains.len() as f64, closed.len() as f64)),
+        profit_factor: to_fixed(ratio(gross_gain, gross_loss.abs())),
+        profit_and_loss_ratio: to_fixed(ratio(avg_gain, avg_loss.abs())),
+        total_fees: ledger.total_fees(),
+        net_profit: ending_equity - beginning_equity,
+        beginning_equity,
+        ending_equity,
+        total_return: to_fixed(total_return),
+        annualized_return: to_fixed(annualized_return),
+        daily_standard_deviation_percentage: to_fixed(daily_std),
+        annual_standard_deviation_percentage: to_fixed(daily_std * TRADING_DAYS_PER_YEAR.sqrt()),
+        max_drawdown_percentage_period: period
+            .iter()
+            .map(|p| p.percent_drawdown)
+            .min()
+            .unwrap_or(0),
+        max_drawdown_percentage_daily: daily.iter().map(|p| p.percent_drawdown).min().unwrap_or(0),
+        sharpe_ratio: to_fixed(scaled_ratio(mean(&daily_returns), daily_std)),
+        sortino_ratio: to_fixed(scaled_ratio(mean(&daily_returns), downside)),
+    }
+}
+
+/// Derives timeseries and static statistics for a strategy.
+///
+/// `capital` is `Parameters::capital` in whole currency units, failing when it does not fit
+/// the 1e9 fixed-point scale. Trades are applied in timestamp
+/// order, positions are marked to market with `Record::price` of the records whose instrument
+/// maps to the trade's ticker in `mappings`, and equity is sampled at every record timestamp
+/// (or trade timestamp without market data). Daily stats use the last sample of each UTC day.
+/// Returns and ratios are fractions, all values use the 1e9 fixed-point convention.
+pub fn compute_performance(
+    capital: i64,
+    trades: &[Trades],
+    mappings: &SymbolMap,
+    records: &[RecordEnum],
+) -> Result<Performance> {
+    let beginning_equity = capital.checked_mul(PRICE_SCALE).ok_or_else(|| {
+        Error::CustomError(format!(
+            "Capital {} overflows the fixed-point price scale",
+            capital
+        ))
+    })?;
+    let mut ledger = Ledger::new(beginning_equity);
+
+    let mut trades: Vec<&Trades> = trades.iter().collect();
+    trades.sort_by_key(|t| t.timestamp);
+    let mut pending = trades.into_iter().peekable();
+
+    let mut points: Vec<(i64, i64)> = Vec::new();
+
+    for record in records {
+        let timestamp = record.header().ts_event as i64;
+        while let Some(trade) = pending.next_if(|t| t.timestamp <= timestamp) {
+            ledger.apply(trade);
+        }
+        if let Some(ticker) = mappings.get_instrument_ticker(record.header().instrument_id) {
+            ledger.mark(&ticker, record.price());
+        }
+        sample(&mut points, timestamp, ledger.equity());
+    }
+
+    for trade in pending {
+        ledger.apply(trade);
+        sample(&mut points, trade.timestamp, ledger.equity());
+    }
+
+    let period_timeseries_stats = timeseries(&points, beginning_equity);
+    let daily_timeseries_stats = timeseries(&daily_points(&points), beginning_equity);
+    let static_stats = static_stats(
+        beginning_equity,
+        &period_timeseries_stats,
+        &daily_timeseries_stats,
+        &ledger,
+    );
+
+    Ok(Performance {
+        period_timeseries_stats,
+        daily_timeseries_stats,
+        static_stats,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::TradeAction;
+    use crate::records::{OhlcvMsg, RecordHeader};
+
+    const DAY_1: i64 = 1704067200000000000;
+    const DAY_2: i64 = DAY_1 + NANOS_PER_DAY;
+
+    fn bar(instrument_id: u32, ts_event: i64, close: i64) -> RecordEnum {
+        let close = close * PRICE_SCALE;
+        RecordEnum::Ohlcv(OhlcvMsg {
+            hd: RecordHeader::new::<OhlcvMsg>(instrument_id, ts_event as u64, 0),
+            open: close,
+            high: close,
+            low: close,
+            close,
+            volume: 100,
+        })
+    }
+
+    fn trade(
+        trade_id: i32,
+        timestamp: i64,
+        action: TradeAction,
+        quantity: i64,
+        price: i64,
+    ) -> Trades {
+        Trades {
+            trade_id,
+            signal_id: trade_id,
+            timestamp,
+            ticker: "AAPL".to_string(),
+            quantity: quantity * PRICE_SCALE,
+            avg_price: price * PRICE_SCALE,
+            trade_value: quantity * price * PRICE_SCALE,
+            trade_cost: quantity * price * PRICE_SCALE,
+            action,
+            fees: PRICE_SCALE,
+        }
+    }
+
+    fn mappings() -> SymbolMap {
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        mappings
+    }
+
+    #[test]
+    fn test_round_trip_trade() -> anyhow::Result<()> {
+        let records = vec![
+            bar(1, DAY_1, 100),
+            bar(1, DAY_1 + 60_000_000_000, 110),
+            bar(1, DAY_2, 105),
+        ];
+        let trades = vec![
+            trade(1, DAY_1, TradeAction::Buy, 10, 100),
+            trade(2, DAY_2, TradeAction::Sell, 10, 105),
+        ];
+
+        // Test
+        let performance = compute_performance(10_000, &trades, &mappings(), &records)?;
+
+        // Validate
+        let equity: Vec<i64> = performance
+            .period_timeseries_stats
+            .iter()
+            .map(|p| p.equity_value)
+            .collect();
+        assert_eq!(
+            equity,
+            vec![
+                9_999 * PRICE_SCALE,
+                10_099 * PRICE_SCALE,
+                10_048 * PRICE_SCALE
+            ]
+        );
+
+        let daily = &performance.daily_timeseries_stats;
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily[0].equity_value, 10_099 * PRICE_SCALE);
+        assert_eq!(daily[0].period_return, to_fixed(10_099.0 / 10_000.0 - 1.0));
+        assert_eq!(
+            daily[1].percent_drawdown,
+            to_fixed(10_048.0 / 10_099.0 - 1.0)
+        );
+
+        let stats = &performance.static_stats;
+        assert_eq!(stats.total_trades, 1);
+        assert_eq!(stats.total_winning_trades, 1);
+        assert_eq!(stats.total_losing_trades, 0);
+        assert_eq!(stats.avg_profit, 49 * PRICE_SCALE);
+        assert_eq!(stats.avg_profit_percent, to_fixed(0.049));
+        assert_eq!(stats.profitability_ratio, PRICE_SCALE);
+        assert_eq!(stats.total_fees, 2 * PRICE_SCALE);
+        assert_eq!(stats.beginning_equity, 10_000 * PRICE_SCALE);
+        assert_eq!(stats.ending_equity, 10_048 * PRICE_SCALE);
+        assert_eq!(stats.net_profit, 48 * PRICE_SCALE);
+        assert_eq!(stats.total_return, to_fixed(0.0048));
+        assert_eq!(
+            stats.max_drawdown_percentage_period,
+            to_fixed(10_048.0 / 10_099.0 - 1.0)
+        );
+        assert!(stats.sharpe_ratio != 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_short_and_flip() -> anyhow::Result<()> {
+        let trades = vec![
+            trade(1, DAY_1, TradeAction::Short, 10, 100),
+            trade(2, DAY_1 + 1, TradeAction::Buy, 15, 90),
+            trade(3, DAY_2, TradeAction::Sell, 5, 80),
+        ];
+
+        // Test
+        let performance = compute_performance(10_000, &trades, &mappings(), &[])?;
+
+        // Validate
+        let stats = &performance.static_stats;
+        assert_eq!(stats.total_trades, 2);
+        assert_eq!(stats.total_winning_trades, 1);
+        assert_eq!(stats.total_losing_trades, 1);
+        // Short covered 10 @ 90 (+100 - 1 fee), long closed 5 @ 80 from 90 (-50 - 1 fee)
+        assert_eq!(stats.avg_gain, 99 * PRICE_SCALE);
+        assert_eq!(stats.avg_loss, -51 * PRICE_SCALE);
+        assert_eq!(stats.profit_factor, to_fixed(99.0 / 51.0));
+        assert_eq!(stats.net_profit, 47 * PRICE_SCALE);
+        assert_eq!(performance.period_timeseries_stats.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_capital_overflow() {
+        // Test
+        let result = compute_performance(i64::MAX / 10, &[], &mappings(), &[]);
+
+        // Validate
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use crate::decode::AsyncDecoder;
+use crate::filter::{AsyncFilteredDecoder, RecordFilter};
+use crate::record_enum::RecordEnum;
+use pyo3::exceptions::{PyIOError, PyStopAsyncIteration, PyValueError};
+use pyo3::prelude::*;
+use pyo3_async_runtimes::tokio::future_into_py;
+use std::sync::Arc;
+use tokio::io::{AsyncBufRead, BufReader};
+use tokio::sync::Mutex;
+
+type AsyncReader = Box<dyn AsyncBufRead + Unpin + Send>;
+type SourceDecoder = AsyncFilteredDecoder<AsyncReader>;
+
+#[derive(Clone)]
+enum Source {
+    File(String),
+    #[cfg(unix)]
+    Socket(String),
+}
+
+impl Source {
+    async fn open(&self, filter: RecordFilter) -> tokio::io::Result<SourceDecoder> {
+        let reader: AsyncReader = match self {
+            Source::File(path) => Box::new(BufReader::new(tokio::fs::File::open(path).await?)),
+            #[cfg(unix)]
+            Source::Socket(path) => {
+                Box::new(BufReader::new(tokio::net::UnixStream::connect(path).await?))
+            }
+        };
+        Ok(AsyncDecoder::new(reader).await?.filter(filter))
+    }
+}
+
+/// Async iterator over records from a file or a unix socket, driven by the tokio runtime
+/// so awaiting the next record never blocks the asyncio event loop.
+///
+/// The source is opened lazily on the first await. With a `RecordFilter` only matching
+/// records are decoded and the metadata is narrowed to match.
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "mbinary"))]
+pub struct PyAsyncDecoder {
+    source: Source,
+    filter: RecordFilter,
+    decoder: Arc<Mutex<Option<SourceDecoder>>>,
+}
+
+impl PyAsyncDecoder {
+    fn new(source: Source, filter: Option<RecordFilter>) -> Self {
+        PyAsyncDecoder {
+            source,
+            filter: filter.unwrap_or_default(),
+            decoder: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+async fn ensure_open(
+    source: &Source,
+    filter: &RecordFilter,
+    decoder: &mut Option<SourceDecoder>,
+) -> PyResult<()> {
+    if decoder.is_none() {
+        let opened = source
+            .open(filter.clone())
+            .await
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+        *decoder = Some(opened);
+    }
+    Ok(())
+}
+
+#[pymethods]
+impl PyAsyncDecoder {
+    #[staticmethod]
+    #[pyo3(signature = (file_path, filter=None))]
+    pub fn from_file(file_path: &str, filter: Option<RecordFilter>) -> Self {
+        PyAsyncDecoder::new(Source::File(file_path.to_string()), filter)
+    }
+
+    #[cfg(unix)]
+    #[staticmethod]
+    #[pyo3(signature = (socket_path, filter=None))]
+    pub fn from_socket(socket_path: &str, filter: Option<RecordFilter>) -> Self {
+        PyAsyncDecoder::new(Source::Socket(socket_path.to_string()), filter)
+    }
+
+    /// Awaitable resolving to the stream metadata, if the stream starts with one.
+    pub fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let source = self.source.clone();
+        let filter = self.filter.clone();
+        let decoder = self.decoder.clone();
+
+        future_into_py(py, async move {
+            let mut guard = decoder.lock().await;
+            ensure_open(&source, &filter, &mut guard).await?;
+            Ok(guard.as_mut().and_then(|d| d.metadata()))
+        })
+    }
+
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let source = self.source.clone();
+        let filter = self.filter.clone();
+        let decoder = self.decoder.clone();
+
+        future_into_py(py, async move {
+            let mut guard = decoder.lock().await;
+            ensure_open(&source, &filter, &mut guard).await?;
+            let decoder = guard.as_mut().expect("decoder opened");
+
+            match decoder.decode_ref().await {
+                Ok(Some(record_ref)) => RecordEnum::from_ref(record_ref)
+                    .map_err(|e| PyValueError::new_err(e.to_string())),
+                Ok(None) => Err(PyStopAsyncIteration::new_err(())),
+                Err(e) => Err(PyIOError::new_err(e.to_string())),
+            }
+        })
+    }
+}
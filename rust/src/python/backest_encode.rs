@@ -23,11 +23,7 @@ impl PyBacktestEncoder {
         self.buffer.clear(); // Clear the buffer for new encoding
 
         let mut encoder = BacktestEncoder::new(&mut self.buffer);
-        encoder.encode_metadata(&backtest.metadata);
-        encoder.encode_timeseries(&backtest.period_timeseries_stats);
-        encoder.encode_timeseries(&backtest.daily_timeseries_stats);
-        encoder.encode_trades(&backtest.trades);
-        encoder.encode_signals(&backtest.signals);
+        encoder.encode_backtest(&backtest);
 
         Ok(self.buffer.clone())
     }
@@ -1,19 +1,22 @@
 use crate::backtest::{
-    BacktestData, BacktestMetaData, Parameters, SignalInstructions, Signals, StaticStats,
-    TimeseriesStats, Trades,
+    BacktestData, BacktestMetaData, Parameters, PositionStats, SignalInstructions, Signals,
+    StaticStats, TimeseriesStats, Trades,
 };
+use crate::enums::{OrderType, TradeAction};
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 
 #[pymethods]
 impl BacktestData {
     #[new]
+    #[pyo3(signature = (metadata, period_timeseries_stats, daily_timeseries_stats, trades, signals, position_timeseries=Vec::new()))]
     pub fn py_new(
         metadata: BacktestMetaData,
         period_timeseries_stats: Vec<TimeseriesStats>,
         daily_timeseries_stats: Vec<TimeseriesStats>,
         trades: Vec<Trades>,
         signals: Vec<Signals>,
+        position_timeseries: Vec<PositionStats>,
     ) -> PyResult<Self> {
         Ok(BacktestData {
             metadata,
@@ -21,6 +24,7 @@ impl BacktestData {
             daily_timeseries_stats,
             trades,
             signals,
+            position_timeseries,
         })
     }
     pub fn to_dict(&self, py: Python) -> Py<PyDict> {
@@ -61,6 +65,13 @@ impl BacktestData {
         }
         let _ = dict.set_item("signals", &signal_list);
 
+        let position_list = PyList::empty(py);
+        for stat in &self.position_timeseries {
+            let dict = stat.to_dict(py);
+            position_list.append(dict).unwrap();
+        }
+        let _ = dict.set_item("position_timeseries", &position_list);
+
         dict.into()
     }
 }
@@ -273,6 +284,47 @@ impl TimeseriesStats {
     }
 }
 
+#[pymethods]
+impl PositionStats {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    pub fn py_new(
+        timestamp: i64,
+        ticker: String,
+        quantity: i64,
+        avg_cost: i64,
+        market_price: i64,
+        market_value: i64,
+        realized_pnl: i64,
+        unrealized_pnl: i64,
+    ) -> Self {
+        PositionStats {
+            timestamp,
+            ticker,
+            quantity,
+            avg_cost,
+            market_price,
+            market_value,
+            realized_pnl,
+            unrealized_pnl,
+        }
+    }
+
+    pub fn to_dict(&self, py: Python) -> Py<PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("timestamp", self.timestamp).unwrap();
+        dict.set_item("ticker", &self.ticker).unwrap();
+        dict.set_item("quantity", self.quantity).unwrap();
+        dict.set_item("avg_cost", self.avg_cost).unwrap();
+        dict.set_item("market_price", self.market_price).unwrap();
+        dict.set_item("market_value", self.market_value).unwrap();
+        dict.set_item("realized_pnl", self.realized_pnl).unwrap();
+        dict.set_item("unrealized_pnl", self.unrealized_pnl)
+            .unwrap();
+        dict.into()
+    }
+}
+
 #[pymethods]
 impl Trades {
     #[new]
@@ -285,7 +337,7 @@ impl Trades {
         avg_price: i64,
         trade_value: i64,
         trade_cost: i64,
-        action: String,
+        action: TradeAction,
         fees: i64,
     ) -> Self {
         Trades {
@@ -311,7 +363,7 @@ impl Trades {
         dict.set_item("avg_price", self.avg_price).unwrap();
         dict.set_item("trade_value", self.trade_value).unwrap();
         dict.set_item("trade_cost", self.trade_cost).unwrap();
-        dict.set_item("action", &self.action).unwrap();
+        dict.set_item("action", self.action.as_str()).unwrap();
         dict.set_item("fees", self.fees).unwrap();
         dict.into()
     }
@@ -347,15 +399,16 @@ impl Signals {
 #[pymethods]
 impl SignalInstructions {
     #[new]
+    #[pyo3(signature = (ticker, order_type, action, signal_id, weight, quantity, limit_price=None, aux_price=None))]
     pub fn py_new(
         ticker: String,
-        order_type: String,
-        action: String,
+        order_type: OrderType,
+        action: TradeAction,
         signal_id: i32,
         weight: i64,
         quantity: i32,
-        limit_price: String,
-        aux_price: String,
+        limit_price: Option<i64>,
+        aux_price: Option<i64>,
     ) -> Self {
         SignalInstructions {
             ticker,
@@ -371,13 +424,14 @@ impl SignalInstructions {
     pub fn to_dict(&self, py: Python) -> Py<PyDict> {
         let dict = PyDict::new(py);
         dict.set_item("ticker", &self.ticker).unwrap();
-        dict.set_item("order_type", &self.order_type).unwrap();
-        dict.set_item("action", &self.action).unwrap();
+        dict.set_item("order_type", self.order_type.as_str())
+            .unwrap();
+        dict.set_item("action", self.action.as_str()).unwrap();
         dict.set_item("signal_id", self.signal_id).unwrap();
         dict.set_item("weight", self.weight).unwrap();
         dict.set_item("quantity", self.quantity).unwrap();
-        dict.set_item("limit_price", &self.limit_price).unwrap();
-        dict.set_item("aux_price", &self.aux_price).unwrap();
+        dict.set_item("limit_price", self.limit_price).unwrap();
+        dict.set_item("aux_price", self.aux_price).unwrap();
         dict.into()
     }
 }
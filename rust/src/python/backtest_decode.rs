@@ -0,0 +1,24 @@
+use crate::backtest::BacktestData;
+use crate::backtest_decoder::BacktestDecoder;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Python-facing wrapper for BacktestDecoder
+#[cfg_attr(feature = "python", pyclass(module = "mbinary"))]
+pub struct PyBacktestDecoder {}
+
+#[pymethods]
+impl PyBacktestDecoder {
+    #[new]
+    fn py_new() -> PyResult<Self> {
+        Ok(PyBacktestDecoder {})
+    }
+
+    /// Decodes a backtest produced by `PyBacktestEncoder.encode_backtest`
+    fn decode_backtest(&self, data: Vec<u8>) -> PyResult<BacktestData> {
+        let mut decoder = BacktestDecoder::new(data.as_slice());
+        decoder
+            .decode_backtest()
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
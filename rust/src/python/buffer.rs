@@ -1,8 +1,9 @@
 use crate::decode::Decoder;
+use crate::enums::RType;
 use crate::metadata::Metadata;
-use crate::utils::unix_nanos_to_date;
-use crate::PRICE_SCALE;
-use pyo3::exceptions::PyIOError;
+use crate::python::columns::group_columns;
+use numpy::PyArray1;
+use pyo3::exceptions::{PyIOError, PyValueError};
 use pyo3::types::{PyBytes, PyDict};
 use pyo3::{prelude::*, IntoPyObjectExt};
 use std::io::Cursor;
@@ -68,94 +69,79 @@ impl BufferStore {
         }
     }
 
-    pub fn decode_to_df(
+    /// Decodes the buffer into columns grouped by rtype, as `{"mbp-1": {field: numpy.ndarray}, ...}`.
+    #[pyo3(signature = (pretty_ts=false, pretty_px=false))]
+    pub fn decode_to_columns(
         &mut self,
         py: Python,
         pretty_ts: bool,
         pretty_px: bool,
     ) -> PyResult<PyObject> {
-        // Use the existing `decode_to_array` to get the list of PyObject
-        let flat_array: Vec<PyObject> = self.decode_to_array()?;
-
-        // Map instrument_id to symbols using the metadata mappings
-        let mappings = self.metadata.mappings.map.clone();
-
-        // Convert to DataFrame using the dictionaries returned by `__dict__`
-        let dicts: Vec<_> = flat_array
-            .iter()
-            .map(|obj| {
-                let dict_obj = obj.call_method0(py, "__dict__")?; // Create a binding for the temporary value
-                let dict = dict_obj.downcast_bound::<PyDict>(py)?; // Now use the bound value
-
-                // Get the instrument_id from the dict, handling the PyResult<Option<PyAny>>
-                if let Some(instrument_id_obj) = dict.get_item("instrument_id")? {
-                    // Extract the instrument_id as a u32
-                    let instrument_id: u32 = instrument_id_obj.extract()?;
-
-                    // Set the corresponding symbol
-                    if let Some(symbol) = mappings.get(&instrument_id) {
-                        dict.set_item("symbol", symbol)?;
-                    }
-                }
-                // Outputs char instead of number
-                if let Some(action_obj) = dict.get_item("action")? {
-                    let action: u8 = action_obj.extract()?;
-                    dict.set_item("action", action as char)?;
-                }
-
-                if let Some(side_obj) = dict.get_item("side")? {
-                    let side: u8 = side_obj.extract()?;
-                    dict.set_item("side", side as char)?;
-                }
+        let decoded = self
+            .decoder
+            .decode()
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
 
-                if pretty_ts {
-                    if let Some(ts_obj) = dict.get_item("ts_event")? {
-                        let ts_event: i64 = ts_obj.extract()?;
-                        let iso: String = unix_nanos_to_date(ts_event).map_err(|e| {
-                            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{}", e))
-                        })?;
-                        dict.set_item("ts_event", iso)?;
-                    }
-                }
-                // Convert price fields if pretty = True
-                if pretty_px {
-                    if let Some(open_obj) = dict.get_item("open")? {
-                        let open: i64 = open_obj.extract()?;
-                        dict.set_item("open", (open as f64) / (PRICE_SCALE as f64))?;
-                    }
-                    if let Some(high_obj) = dict.get_item("high")? {
-                        let high: i64 = high_obj.extract()?;
-                        dict.set_item("high", (high as f64) / (PRICE_SCALE as f64))?;
-                    }
-                    if let Some(low_obj) = dict.get_item("low")? {
-                        let low: i64 = low_obj.extract()?;
-                        dict.set_item("low", (low as f64) / (PRICE_SCALE as f64))?;
-                    }
-                    if let Some(close_obj) = dict.get_item("close")? {
-                        let close: i64 = close_obj.extract()?;
-                        dict.set_item("close", (close as f64) / (PRICE_SCALE as f64))?;
-                    }
-                    if let Some(price_obj) = dict.get_item("price")? {
-                        let price: i64 = price_obj.extract()?;
-                        dict.set_item("price", (price as f64) / (PRICE_SCALE as f64))?;
-                    }
-                    if let Some(ask_px_obj) = dict.get_item("ask_px")? {
-                        let ask_px: i64 = ask_px_obj.extract()?;
-                        dict.set_item("ask_px", (ask_px as f64) / (PRICE_SCALE as f64))?;
-                    }
-                    if let Some(bid_px_obj) = dict.get_item("bid_px")? {
-                        let bid_px: i64 = bid_px_obj.extract()?;
-                        dict.set_item("bid_px", (bid_px as f64) / (PRICE_SCALE as f64))?;
-                    }
-                }
+        let columns = PyDict::new(py);
+        for (rtype, group) in group_columns(&decoded) {
+            let rtype_enum =
+                RType::try_from(rtype).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            let dict = group.into_dict(
+                py,
+                &rtype_enum,
+                &self.metadata.mappings,
+                pretty_ts,
+                pretty_px,
+            )?;
+            columns.set_item(rtype_enum.as_str(), dict)?;
+        }
+        Ok(columns.into_any().unbind())
+    }
 
-                Ok(dict.clone().into_pyobject(py)?)
-            })
-            .collect::<PyResult<Vec<_>>>()?;
+    pub fn decode_to_df(
+        &mut self,
+        py: Python,
+        pretty_ts: bool,
+        pretty_px: bool,
+    ) -> PyResult<PyObject> {
+        let decoded = self
+            .decoder
+            .decode()
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
 
         let pandas = py.import("pandas")?;
-        let df = pandas.call_method1("DataFrame", (dicts,))?;
-        Ok(df.into())
+        let groups = group_columns(&decoded);
+        let single_rtype = groups.len() == 1;
+
+        // One frame per rtype, indexed by stream position so mixed streams keep their order
+        let mut frames = Vec::with_capacity(groups.len());
+        for (rtype, group) in groups {
+            let rtype_enum =
+                RType::try_from(rtype).map_err(|e| PyValueError::new_err(e.to_string()))?;
+            let index = PyArray1::from_slice(py, &group.index);
+            let dict = group.into_dict(
+                py,
+                &rtype_enum,
+                &self.metadata.mappings,
+                pretty_ts,
+                pretty_px,
+            )?;
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("index", index)?;
+            frames.push(pandas.call_method("DataFrame", (dict,), Some(&kwargs))?);
+        }
+
+        let df = match frames.len() {
+            0 => pandas.call_method0("DataFrame")?,
+            _ if single_rtype => frames.remove(0),
+            _ => pandas
+                .call_method1("concat", (frames,))?
+                .call_method0("sort_index")?,
+        };
+
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("drop", true)?;
+        Ok(df.call_method("reset_index", (), Some(&kwargs))?.unbind())
     }
 
     pub fn write_to_file(&self, file_path: &str) -> PyResult<()> {
@@ -0,0 +1,263 @@
+use crate::enums::RType;
+use crate::record_enum::RecordEnum;
+use crate::records::{BidAskPair, Record, RecordHeader};
+use crate::symbols::SymbolMap;
+use crate::utils::unix_nanos_to_date;
+use crate::PRICE_SCALE;
+use numpy::{Element, PyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString};
+use std::collections::{BTreeMap, HashMap};
+use std::os::raw::c_char;
+
+/// Column buffers for records of a single `RType`, filled in one pass over the decoded records.
+#[derive(Default)]
+pub(crate) struct RecordColumns {
+    pub(crate) index: Vec<u64>,
+    length: Vec<u8>,
+    rtype: Vec<u8>,
+    instrument_id: Vec<u32>,
+    ts_event: Vec<u64>,
+    rollover_flag: Vec<u8>,
+    price: Vec<i64>,
+    size: Vec<u32>,
+    action: Vec<u8>,
+    side: Vec<u8>,
+    flags: Vec<u8>,
+    depth: Vec<u8>,
+    ts_recv: Vec<u64>,
+    ts_in_delta: Vec<i32>,
+    sequence: Vec<u32>,
+    discriminator: Vec<u32>,
+    bid_px: Vec<i64>,
+    ask_px: Vec<i64>,
+    bid_sz: Vec<u32>,
+    ask_sz: Vec<u32>,
+    bid_ct: Vec<u32>,
+    ask_ct: Vec<u32>,
+    open: Vec<i64>,
+    high: Vec<i64>,
+    low: Vec<i64>,
+    close: Vec<i64>,
+    volume: Vec<u64>,
+}
+
+impl RecordColumns {
+    fn push_header(&mut self, hd: &RecordHeader) {
+        self.length.push(hd.length);
+        self.rtype.push(hd.rtype);
+        self.instrument_id.push(hd.instrument_id);
+        self.ts_event.push(hd.ts_event);
+        self.rollover_flag.push(hd.rollover_flag);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push_trade(
+        &mut self,
+        price: i64,
+        size: u32,
+        action: c_char,
+        side: c_char,
+        flags: u8,
+        depth: u8,
+        ts_recv: u64,
+        ts_in_delta: i32,
+        sequence: u32,
+    ) {
+        self.price.push(price);
+        self.size.push(size);
+        self.action.push(action as u8);
+        self.side.push(side as u8);
+        self.flags.push(flags);
+        self.depth.push(depth);
+        self.ts_recv.push(ts_recv);
+        self.ts_in_delta.push(ts_in_delta);
+        self.sequence.push(sequence);
+    }
+
+    fn push_level(&mut self, level: &BidAskPair) {
+        self.bid_px.push(level.bid_px);
+        self.ask_px.push(level.ask_px);
+        self.bid_sz.push(level.bid_sz);
+        self.ask_sz.push(level.ask_sz);
+        self.bid_ct.push(level.bid_ct);
+        self.ask_ct.push(level.ask_ct);
+    }
+
+    pub(crate) fn push(&mut self, index: u64, record: &RecordEnum) {
+        self.index.push(index);
+        self.push_header(record.header());
+
+        match record {
+            RecordEnum::Mbp1(msg) | RecordEnum::Tbbo(msg) => {
+                self.push_trade(
+                    msg.price,
+                    msg.size,
+                    msg.action,
+                    msg.side,
+                    msg.flags,
+                    msg.depth,
+                    msg.ts_recv,
+                    msg.ts_in_delta,
+                    msg.sequence,
+                );
+                self.discriminator.push(msg.discriminator);
+                self.push_level(&msg.levels[0]);
+            }
+            RecordEnum::Trade(msg) => self.push_trade(
+                msg.price,
+                msg.size,
+                msg.action,
+                msg.side,
+                msg.flags,
+                msg.depth,
+                msg.ts_recv,
+                msg.ts_in_delta,
+                msg.sequence,
+            ),
+            RecordEnum::Bbo(msg) => self.push_level(&msg.levels[0]),
+            RecordEnum::Ohlcv(msg) => {
+                self.open.push(msg.open);
+                self.high.push(msg.high);
+                self.low.push(msg.low);
+                self.close.push(msg.close);
+                self.volume.push(msg.volume);
+            }
+        }
+    }
+
+    /// Converts the buffers to a dict of NumPy arrays, keyed and ordered like the record's
+    /// `__dict__`, with a trailing `symbol` column resolved from `mappings`.
+    pub(crate) fn into_dict<'py>(
+        self,
+        py: Python<'py>,
+        rtype: &RType,
+        mappings: &SymbolMap,
+        pretty_ts: bool,
+        pretty_px: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+
+        dict.set_item("length", array(py, self.length))?;
+        dict.set_item("rtype", array(py, self.rtype))?;
+        dict.set_item("instrument_id", array(py, self.instrument_id.clone()))?;
+        if pretty_ts {
+            dict.set_item("ts_event", dates(py, &self.ts_event)?)?;
+        } else {
+            dict.set_item("ts_event", array(py, self.ts_event))?;
+        }
+        dict.set_item("rollover_flag", array(py, self.rollover_flag))?;
+
+        let px = |values: Vec<i64>| -> PyObject {
+            if pretty_px {
+                let scaled: Vec<f64> = values
+                    .into_iter()
+                    .map(|v| v as f64 / PRICE_SCALE as f64)
+                    .collect();
+                array(py, scaled)
+            } else {
+                array(py, values)
+            }
+        };
+
+        if matches!(rtype, RType::Mbp1 | RType::Tbbo | RType::Trades) {
+            dict.set_item("price", px(self.price))?;
+            dict.set_item("size", array(py, self.size))?;
+            dict.set_item("action", chars(py, &self.action))?;
+            dict.set_item("side", chars(py, &self.side))?;
+            dict.set_item("flags", array(py, self.flags))?;
+            dict.set_item("depth", array(py, self.depth))?;
+            dict.set_item("ts_recv", array(py, self.ts_recv))?;
+            dict.set_item("ts_in_delta", array(py, self.ts_in_delta))?;
+            dict.set_item("sequence", array(py, self.sequence))?;
+        }
+
+        if matches!(rtype, RType::Mbp1 | RType::Tbbo) {
+            dict.set_item("discriminator", array(py, self.discriminator))?;
+        }
+
+        if matches!(rtype, RType::Mbp1 | RType::Tbbo | RType::Bbo) {
+            dict.set_item("bid_px", px(self.bid_px))?;
+            dict.set_item("ask_px", px(self.ask_px))?;
+            dict.set_item("bid_sz", array(py, self.bid_sz))?;
+            dict.set_item("ask_sz", array(py, self.ask_sz))?;
+            dict.set_item("bid_ct", array(py, self.bid_ct))?;
+            dict.set_item("ask_ct", array(py, self.ask_ct))?;
+        }
+
+        if *rtype == RType::Ohlcv {
+            dict.set_item("open", px(self.open))?;
+            dict.set_item("high", px(self.high))?;
+            dict.set_item("low", px(self.low))?;
+            dict.set_item("close", px(self.close))?;
+            dict.set_item("volume", array(py, self.volume))?;
+        }
+
+        dict.set_item("symbol", symbols(py, &self.instrument_id, mappings))?;
+        Ok(dict)
+    }
+}
+
+/// Splits records into per-`RType` columns, remembering each record's position in the stream.
+pub(crate) fn group_columns(records: &[RecordEnum]) -> BTreeMap<u8, RecordColumns> {
+    let mut groups: BTreeMap<u8, RecordColumns> = BTreeMap::new();
+    for (i, record) in records.iter().enumerate() {
+        groups
+            .entry(record.header().rtype)
+            .or_default()
+            .push(i as u64, record);
+    }
+    groups
+}
+
+fn array<T: Element>(py: Python, values: Vec<T>) -> PyObject {
+    PyArray1::from_vec(py, values).into_any().unbind()
+}
+
+fn chars(py: Python, values: &[u8]) -> PyObject {
+    let mut cache: HashMap<u8, PyObject> = HashMap::new();
+    let objects: Vec<PyObject> = values
+        .iter()
+        .map(|v| {
+            cache
+                .entry(*v)
+                .or_insert_with(|| {
+                    PyString::new(py, &(*v as char).to_string())
+                        .into_any()
+                        .unbind()
+                })
+                .clone_ref(py)
+        })
+        .collect();
+    array(py, objects)
+}
+
+fn symbols(py: Python, instrument_ids: &[u32], mappings: &SymbolMap) -> PyObject {
+    let mut cache: HashMap<u32, PyObject> = HashMap::new();
+    let objects: Vec<PyObject> = instrument_ids
+        .iter()
+        .map(|id| {
+            cache
+                .entry(*id)
+                .or_insert_with(|| match mappings.map.get(id) {
+                    Some(ticker) => PyString::new(py, ticker).into_any().unbind(),
+                    None => py.None(),
+                })
+                .clone_ref(py)
+        })
+        .collect();
+    array(py, objects)
+}
+
+fn dates(py: Python, values: &[u64]) -> PyResult<PyObject> {
+    let objects = values
+        .iter()
+        .map(|ts| {
+            let iso = unix_nanos_to_date(*ts as i64)
+                .map_err(|e| PyValueError::new_err(format!("{}", e)))?;
+            Ok(PyString::new(py, &iso).into_any().unbind())
+        })
+        .collect::<PyResult<Vec<PyObject>>>()?;
+    Ok(array(py, objects))
+}
@@ -0,0 +1,18 @@
+use crate::backtest::BacktestData;
+use crate::compare::BacktestComparison;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+#[pymethods]
+impl BacktestComparison {
+    #[new]
+    fn py_new(backtests: Vec<BacktestData>) -> PyResult<Self> {
+        BacktestComparison::new(&backtests).map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[pyo3(name = "to_json")]
+    fn py_to_json(&self) -> PyResult<String> {
+        self.to_json()
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
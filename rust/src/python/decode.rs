@@ -0,0 +1,72 @@
+use crate::decode::Decoder;
+use crate::filter::{FilteredDecoder, RecordFilter};
+use crate::metadata::Metadata;
+use crate::record_enum::RecordEnum;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::{prelude::*, IntoPyObjectExt};
+use std::fs::File;
+use std::io::BufReader;
+
+/// Streams records from a file, holding only the current record in memory.
+///
+/// With a `RecordFilter` only matching records are decoded and the metadata is narrowed to
+/// match.
+#[cfg_attr(feature = "python", pyo3::pyclass(module = "mbinary"))]
+pub struct PyFileDecoder {
+    decoder: FilteredDecoder<BufReader<File>>,
+}
+
+impl PyFileDecoder {
+    fn next_record(&mut self) -> PyResult<Option<RecordEnum>> {
+        match self.decoder.decode_ref() {
+            Ok(Some(record_ref)) => RecordEnum::from_ref(record_ref)
+                .map(Some)
+                .map_err(|e| PyValueError::new_err(e.to_string())),
+            Ok(None) => Ok(None),
+            Err(e) => Err(PyIOError::new_err(e.to_string())),
+        }
+    }
+}
+
+#[pymethods]
+impl PyFileDecoder {
+    #[new]
+    #[pyo3(signature = (file_path, filter=None))]
+    pub fn py_new(file_path: &str, filter: Option<RecordFilter>) -> PyResult<Self> {
+        let decoder = Decoder::<BufReader<File>>::from_file(file_path)
+            .map_err(|e| PyIOError::new_err(e.to_string()))?;
+
+        Ok(PyFileDecoder {
+            decoder: decoder.filter(filter.unwrap_or_default()),
+        })
+    }
+
+    #[getter]
+    pub fn metadata(&self) -> Option<Metadata> {
+        self.decoder.metadata()
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<PyObject>> {
+        match self.next_record()? {
+            Some(record) => Ok(Some(record.into_py_any(py)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads up to `n` records, returning an empty list once the file is exhausted.
+    pub fn read_batch(&mut self, py: Python, n: usize) -> PyResult<Vec<PyObject>> {
+        // `n` comes from Python, only preallocate for a typical batch
+        let mut batch = Vec::with_capacity(n.min(4096));
+        while batch.len() < n {
+            match self.next_record()? {
+                Some(record) => batch.push(record.into_py_any(py)?),
+                None => break,
+            }
+        }
+        Ok(batch)
+    }
+}
@@ -1,9 +1,13 @@
+use crate::encode::CombinedEncoder;
 use crate::encode::MetadataEncoder;
 use crate::encode::RecordEncoder;
 use crate::metadata::Metadata;
+use crate::record_enum::RecordEnum;
 use crate::record_ref::RecordRef;
 use crate::records::Mbp1Msg; // Your existing RecordEncoder implementation
+use pyo3::exceptions::{PyIOError, PyValueError};
 use pyo3::prelude::*; // PyO3 essentials
+use std::path::Path;
 
 /// Python-facing wrapper for RecordEncoder
 #[cfg_attr(feature = "python", pyclass(module = "mbinary"))]
@@ -74,3 +78,77 @@ impl PyRecordEncoder {
         Ok(self.buffer.clone()) // Return a copy of the buffer
     }
 }
+
+/// Python-facing wrapper for CombinedEncoder, accepting any record type.
+#[cfg_attr(feature = "python", pyclass(module = "mbinary"))]
+pub struct PyCombinedEncoder {
+    buffer: Vec<u8>, // Owned buffer, appended to across calls
+}
+
+impl PyCombinedEncoder {
+    fn encode_into_buffer(&mut self, records: &[RecordEnum]) -> PyResult<()> {
+        let refs: Vec<RecordRef> = records.iter().map(|r| r.to_record_ref()).collect();
+        let mut encoder = CombinedEncoder::new(&mut self.buffer);
+        encoder
+            .encode_records(&refs)
+            .map_err(|e| PyIOError::new_err(format!("Failed to encode record: {}", e)))
+    }
+}
+
+#[pymethods]
+impl PyCombinedEncoder {
+    #[new]
+    fn py_new() -> PyResult<Self> {
+        Ok(PyCombinedEncoder { buffer: Vec::new() })
+    }
+
+    /// Encodes the metadata header, must be called before any records are added.
+    fn encode_metadata(&mut self, metadata: Metadata) -> PyResult<()> {
+        if !self.buffer.is_empty() {
+            return Err(PyValueError::new_err(
+                "Metadata must be encoded before any records",
+            ));
+        }
+
+        let mut encoder = CombinedEncoder::new(&mut self.buffer);
+        encoder
+            .encode_metadata(&metadata)
+            .map_err(|e| PyIOError::new_err(format!("Failed to encode metadata: {}", e)))
+    }
+
+    /// Appends a single record of any type.
+    fn encode_record(&mut self, record: RecordEnum) -> PyResult<()> {
+        self.encode_into_buffer(&[record])
+    }
+
+    /// Appends records of any mix of types.
+    fn encode_records(&mut self, records: Vec<RecordEnum>) -> PyResult<()> {
+        self.encode_into_buffer(&records)
+    }
+
+    /// Replaces the buffer with the metadata followed by the records.
+    fn encode(&mut self, metadata: Metadata, records: Vec<RecordEnum>) -> PyResult<()> {
+        self.buffer.clear();
+        self.encode_metadata(metadata)?;
+        self.encode_into_buffer(&records)
+    }
+
+    /// Drops the encoded data, e.g. after it has been written out.
+    fn clear(&mut self) {
+        self.buffer.clear();
+    }
+
+    /// Writes the encoded data to a file, appending to it if `append` is set.
+    #[pyo3(signature = (file_path, append=false))]
+    fn write_to_file(&mut self, file_path: &str, append: bool) -> PyResult<()> {
+        let encoder = CombinedEncoder::new(&mut self.buffer);
+        encoder
+            .write_to_file(Path::new(file_path), append)
+            .map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Retrieve the encoded data as bytes
+    fn get_encoded_data(&self) -> PyResult<Vec<u8>> {
+        Ok(self.buffer.clone())
+    }
+}
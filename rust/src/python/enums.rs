@@ -1,4 +1,4 @@
-use crate::enums::{Action, Dataset, RType, Schema, Side, Stype};
+use crate::enums::{Action, Dataset, OrderType, RType, Schema, Side, Stype, TradeAction};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::types::PyType;
@@ -205,3 +205,69 @@ impl RType {
         self.__str__()
     }
 }
+
+#[pymethods]
+impl TradeAction {
+    #[classmethod]
+    #[pyo3(name = "from_str")]
+    fn py_from_str(_cls: &Bound<'_, PyType>, value: &str) -> PyResult<Self> {
+        TradeAction::from_str(value).map_err(|e| PyValueError::new_err(e.extract_message()))
+    }
+
+    #[classmethod]
+    fn from_int(_cls: &Bound<'_, PyType>, value: u8) -> PyResult<Self> {
+        TradeAction::try_from(value)
+            .map_err(|_| PyValueError::new_err(format!("Unknown TradeAction value: {}", value)))
+    }
+
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<TradeAction.{}: '{}'>", self.name(), self.value())
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.as_ref().to_ascii_uppercase()
+    }
+
+    #[getter]
+    fn value(&self) -> String {
+        self.__str__()
+    }
+}
+
+#[pymethods]
+impl OrderType {
+    #[classmethod]
+    #[pyo3(name = "from_str")]
+    fn py_from_str(_cls: &Bound<'_, PyType>, value: &str) -> PyResult<Self> {
+        OrderType::from_str(value).map_err(|e| PyValueError::new_err(e.extract_message()))
+    }
+
+    #[classmethod]
+    fn from_int(_cls: &Bound<'_, PyType>, value: u8) -> PyResult<Self> {
+        OrderType::try_from(value)
+            .map_err(|_| PyValueError::new_err(format!("Unknown OrderType value: {}", value)))
+    }
+
+    fn __str__(&self) -> String {
+        format!("{}", self)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<OrderType.{}: '{}'>", self.name(), self.value())
+    }
+
+    #[getter]
+    fn name(&self) -> String {
+        self.as_ref().to_ascii_uppercase()
+    }
+
+    #[getter]
+    fn value(&self) -> String {
+        self.__str__()
+    }
+}
@@ -0,0 +1,48 @@
+use crate::enums::RType;
+use crate::filter::RecordFilter;
+use crate::metadata::Metadata;
+use crate::records::RecordHeader;
+use pyo3::prelude::*;
+
+/// Builder methods return a new filter, leaving `self` unchanged.
+#[pymethods]
+impl RecordFilter {
+    #[new]
+    fn py_new() -> Self {
+        RecordFilter::new()
+    }
+
+    #[pyo3(name = "instrument_ids")]
+    fn py_instrument_ids(&self, instrument_ids: Vec<u32>) -> Self {
+        self.clone().instrument_ids(instrument_ids)
+    }
+
+    #[pyo3(name = "rtypes")]
+    fn py_rtypes(&self, rtypes: Vec<RType>) -> Self {
+        self.clone().rtypes(rtypes)
+    }
+
+    #[pyo3(name = "start")]
+    fn py_start(&self, start: u64) -> Self {
+        self.clone().start(start)
+    }
+
+    #[pyo3(name = "end")]
+    fn py_end(&self, end: u64) -> Self {
+        self.clone().end(end)
+    }
+
+    #[pyo3(name = "matches")]
+    fn py_matches(&self, header: &RecordHeader) -> bool {
+        self.matches(header)
+    }
+
+    #[pyo3(name = "filter_metadata")]
+    fn py_filter_metadata(&self, metadata: &Metadata) -> Metadata {
+        self.filter_metadata(metadata)
+    }
+
+    fn __str__(&self) -> String {
+        format!("{:?}", self)
+    }
+}
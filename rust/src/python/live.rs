@@ -1,18 +1,19 @@
 use crate::backtest::{Parameters, Signals, Trades};
-use crate::live::{AccountSummary, LiveData};
+use crate::live::{AccountSnapshot, AccountSummary, LiveData};
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 
 #[pymethods]
 impl LiveData {
     #[new]
-    #[pyo3(signature = (live_id=None,parameters= None, trades=None, signals=None, account=None))]
+    #[pyo3(signature = (live_id=None,parameters= None, trades=None, signals=None, account=None, account_snapshots=Vec::new()))]
     fn py_new(
         live_id: Option<u16>,
         parameters: Option<Parameters>,
         trades: Option<Vec<Trades>>,
         signals: Option<Vec<Signals>>,
         account: Option<AccountSummary>,
+        account_snapshots: Vec<AccountSnapshot>,
     ) -> PyResult<Self> {
         Ok(LiveData {
             live_id,
@@ -28,9 +29,20 @@ impl LiveData {
             account: account.clone().ok_or_else(|| {
                 PyErr::new::<pyo3::exceptions::PyTypeError, _>("'account' is required")
             })?,
+            account_snapshots,
         })
     }
 
+    #[pyo3(name = "push_account_snapshot")]
+    fn py_push_account_snapshot(&mut self, snapshot: AccountSnapshot) {
+        self.push_account_snapshot(snapshot);
+    }
+
+    #[pyo3(name = "account_summary")]
+    fn py_account_summary(&self) -> AccountSummary {
+        self.account_summary()
+    }
+
     fn to_dict(&self, py: Python) -> Py<PyDict> {
         let dict = PyDict::new(py);
         dict.set_item("live_id", self.live_id).unwrap();
@@ -54,6 +66,12 @@ impl LiveData {
         }
         let _ = dict.set_item("signals", &signal_list);
 
+        let snapshot_list = PyList::empty(py);
+        for snapshot in &self.account_snapshots {
+            snapshot_list.append(snapshot.to_dict(py)).unwrap();
+        }
+        let _ = dict.set_item("account_snapshots", &snapshot_list);
+
         dict.into()
     }
 }
@@ -162,4 +180,73 @@ impl AccountSummary {
 
         dict.into()
     }
+
+    #[staticmethod]
+    #[pyo3(name = "from_snapshots")]
+    fn py_from_snapshots(currency: &str, start: AccountSnapshot, end: AccountSnapshot) -> Self {
+        AccountSummary::from_snapshots(currency, &start, &end)
+    }
+
+    #[pyo3(name = "start")]
+    fn py_start(&self) -> AccountSnapshot {
+        self.start()
+    }
+
+    #[pyo3(name = "end")]
+    fn py_end(&self) -> AccountSnapshot {
+        self.end()
+    }
+}
+
+#[pymethods]
+impl AccountSnapshot {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    pub fn py_new(
+        timestamp: i64,
+        buying_power: i64,
+        excess_liquidity: i64,
+        full_available_funds: i64,
+        full_init_margin_req: i64,
+        full_maint_margin_req: i64,
+        futures_pnl: i64,
+        net_liquidation: i64,
+        total_cash_balance: i64,
+        unrealized_pnl: i64,
+    ) -> Self {
+        AccountSnapshot {
+            timestamp,
+            buying_power,
+            excess_liquidity,
+            full_available_funds,
+            full_init_margin_req,
+            full_maint_margin_req,
+            futures_pnl,
+            net_liquidation,
+            total_cash_balance,
+            unrealized_pnl,
+        }
+    }
+
+    pub fn to_dict(&self, py: Python) -> Py<PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("timestamp", self.timestamp).unwrap();
+        dict.set_item("buying_power", self.buying_power).unwrap();
+        dict.set_item("excess_liquidity", self.excess_liquidity)
+            .unwrap();
+        dict.set_item("full_available_funds", self.full_available_funds)
+            .unwrap();
+        dict.set_item("full_init_margin_req", self.full_init_margin_req)
+            .unwrap();
+        dict.set_item("full_maint_margin_req", self.full_maint_margin_req)
+            .unwrap();
+        dict.set_item("futures_pnl", self.futures_pnl).unwrap();
+        dict.set_item("net_liquidation", self.net_liquidation)
+            .unwrap();
+        dict.set_item("total_cash_balance", self.total_cash_balance)
+            .unwrap();
+        dict.set_item("unrealized_pnl", self.unrealized_pnl)
+            .unwrap();
+        dict.into()
+    }
 }
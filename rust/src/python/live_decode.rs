@@ -0,0 +1,24 @@
+use crate::live::LiveData;
+use crate::live_decoder::LiveDecoder;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Python-facing wrapper for LiveDecoder
+#[cfg_attr(feature = "python", pyclass(module = "mbinary"))]
+pub struct PyLiveDecoder {}
+
+#[pymethods]
+impl PyLiveDecoder {
+    #[new]
+    fn py_new() -> PyResult<Self> {
+        Ok(PyLiveDecoder {})
+    }
+
+    /// Decodes a live session produced by `PyLiveEncoder.encode_live`
+    fn decode_live(&self, data: Vec<u8>) -> PyResult<LiveData> {
+        let mut decoder = LiveDecoder::new(data.as_slice());
+        decoder
+            .decode_live()
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
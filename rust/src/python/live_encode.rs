@@ -0,0 +1,27 @@
+use crate::live::LiveData;
+use crate::live_encode::LiveEncoder;
+use pyo3::prelude::*;
+
+/// Python-facing wrapper for LiveEncoder
+#[cfg_attr(feature = "python", pyclass(module = "mbinary"))]
+pub struct PyLiveEncoder {
+    buffer: Vec<u8>, // Owned buffer
+}
+
+#[pymethods]
+impl PyLiveEncoder {
+    #[new]
+    fn py_new() -> PyResult<Self> {
+        Ok(PyLiveEncoder { buffer: Vec::new() })
+    }
+
+    /// Encodes a live session
+    fn encode_live(&mut self, live: LiveData) -> PyResult<Vec<u8>> {
+        self.buffer.clear(); // Clear the buffer for new encoding
+
+        let mut encoder = LiveEncoder::new(&mut self.buffer);
+        encoder.encode_live(&live);
+
+        Ok(self.buffer.clone())
+    }
+}
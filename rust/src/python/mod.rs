@@ -1,11 +1,20 @@
+pub mod async_decode;
 pub mod backest_encode;
 pub mod backtest;
+pub mod backtest_decode;
 pub mod buffer;
+pub mod columns;
+pub mod compare;
+pub mod decode;
 pub mod encode;
 pub mod enums;
+pub mod filter;
 pub mod live;
+pub mod live_decode;
+pub mod live_encode;
 pub mod metadata;
 pub mod params;
+pub mod reconcile;
 pub mod records;
 pub mod symbols;
 pub mod vendors;
@@ -0,0 +1,21 @@
+use crate::backtest::BacktestData;
+use crate::live::LiveData;
+use crate::reconcile::Reconciliation;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+#[pymethods]
+impl Reconciliation {
+    #[new]
+    #[pyo3(signature = (live, backtest, tolerance=0))]
+    fn py_new(live: LiveData, backtest: BacktestData, tolerance: i64) -> PyResult<Self> {
+        Reconciliation::new(&live, &backtest, tolerance)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    #[pyo3(name = "to_json")]
+    fn py_to_json(&self) -> PyResult<String> {
+        self.to_json()
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
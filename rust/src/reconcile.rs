@@ -0,0 +1,491 @@
+use crate::backtest::{BacktestData, SignalInstructions, Signals, Trades};
+use crate::enums::TradeAction;
+use crate::ledger::{notional, Ledger};
+use crate::live::LiveData;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+#[cfg(feature = "python")]
+use pyo3::pyclass;
+
+/// Signal instruction generated by only one of the live run and the backtest.
+#[cfg_attr(feature = "python", pyclass(get_all, dict, module = "mbinary"))]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UnmatchedSignal {
+    pub timestamp: i64,
+    pub instruction: SignalInstructions,
+}
+
+/// A live fill paired with the backtest fill it reproduces.
+///
+/// `slippage` is the per-unit price difference, positive when the live price was worse for
+/// the trade's direction, and `slippage_cost` is that difference over the live quantity.
+#[cfg_attr(feature = "python", pyclass(get_all, dict, module = "mbinary"))]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FillMatch {
+    pub ticker: String,
+    pub action: TradeAction,
+    pub backtest_timestamp: i64,
+    pub live_timestamp: i64,
+    pub backtest_quantity: i64,
+    pub live_quantity: i64,
+    pub backtest_avg_price: i64,
+    pub live_avg_price: i64,
+    pub slippage: i64,
+    pub slippage_cost: i64,
+    pub backtest_fees: i64,
+    pub live_fees: i64,
+    pub fee_difference: i64,
+}
+
+/// Live minus backtest PnL for a ticker, split into what slippage and fees on matched fills
+/// explain. `unexplained` covers missed or extra fills, quantity and valuation differences.
+#[cfg_attr(feature = "python", pyclass(get_all, dict, module = "mbinary"))]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PnlAttribution {
+    pub ticker: String,
+    pub backtest_pnl: i64,
+    pub live_pnl: i64,
+    pub difference: i64,
+    pub slippage: i64,
+    pub fees: i64,
+    pub unexplained: i64,
+}
+
+#[cfg_attr(feature = "python", pyclass(get_all, dict, module = "mbinary"))]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Reconciliation {
+    pub strategy_name: String,
+    pub tolerance: i64,
+    /// Backtest signals the live run never generated.
+    pub missed_signals: Vec<UnmatchedSignal>,
+    /// Live signals the backtest never generated.
+    pub extra_signals: Vec<UnmatchedSignal>,
+    pub fills: Vec<FillMatch>,
+    pub unmatched_backtest_trades: Vec<Trades>,
+    pub unmatched_live_trades: Vec<Trades>,
+    pub pnl_attribution: Vec<PnlAttribution>,
+    pub total_slippage_cost: i64,
+    pub total_fee_difference: i64,
+}
+
+/// Pairs each backtest item with the closest unpaired live item sharing its key, at most
+/// `tolerance` nanoseconds away. Backtest items are visited in timestamp order.
+fn match_by_time<T, K, F, G>(
+    backtest: &[T],
+    live: &[T],
+    tolerance: i64,
+    key: F,
+    timestamp: G,
+) -> (Vec<(usize, usize)>, Vec<usize>, Vec<usize>)
+where
+    K: Eq + std::hash::Hash,
+    F: Fn(&T) -> K,
+    G: Fn(&T) -> i64,
+{
+    let mut candidates: HashMap<K, Vec<usize>> = HashMap::new();
+    for (i, item) in live.iter().enumerate() {
+        candidates.entry(key(item)).or_default().push(i);
+    }
+
+    let mut order: Vec<usize> = (0..backtest.len()).collect();
+    order.sort_by_key(|&i| timestamp(&backtest[i]));
+
+    let mut paired = vec![false; live.len()];
+    let mut matches = Vec::new();
+    let mut unmatched_backtest = Vec::new();
+
+    for b in order {
+        let ts = timestamp(&backtest[b]);
+        let best = candidates.get(&key(&backtest[b])).and_then(|indices| {
+            indices
+                .iter()
+                .copied()
+                .filter(|&l| !paired[l] && (timestamp(&live[l]) - ts).abs() <= tolerance)
+                .min_by_key(|&l| ((timestamp(&live[l]) - ts).abs(), timestamp(&live[l])))
+        });
+
+        match best {
+            Some(l) => {
+                paired[l] = true;
+                matches.push((b, l));
+            }
+            None => unmatched_backtest.push(b),
+        }
+    }
+
+    let unmatched_live = (0..live.len()).filter(|&l| !paired[l]).collect();
+    (matches, unmatched_backtest, unmatched_live)
+}
+
+fn flatten_signals(signals: &[Signals]) -> Vec<UnmatchedSignal> {
+    signals
+        .iter()
+        .flat_map(|signal| {
+            signal
+                .trade_instructions
+                .iter()
+                .map(|instruction| UnmatchedSignal {
+                    timestamp: signal.timestamp,
+                    instruction: instruction.clone(),
+                })
+        })
+        .collect()
+}
+
+fn fill_match(backtest: &Trades, live: &Trades) -> FillMatch {
+    let slippage = (live.avg_price - backtest.avg_price) * live.action.direction();
+    let fee_difference = live.fees - backtest.fees;
+
+    FillMatch {
+        ticker: live.ticker.clone(),
+        action: live.action,
+        backtest_timestamp: backtest.timestamp,
+        live_timestamp: live.timestamp,
+        backtest_quantity: backtest.quantity,
+        live_quantity: live.quantity,
+        backtest_avg_price: backtest.avg_price,
+        live_avg_price: live.avg_price,
+        slippage,
+        slippage_cost: notional(live.quantity.abs(), slippage) as i64,
+        backtest_fees: backtest.fees,
+        live_fees: live.fees,
+        fee_difference,
+    }
+}
+
+/// Ledger of `trades`, attribution only reads positions so it starts without cash.
+fn ledger(trades: &[Trades]) -> Ledger {
+    let mut trades: Vec<&Trades> = trades.iter().collect();
+    trades.sort_by_key(|t| t.timestamp);
+
+    let mut ledger = Ledger::new(0);
+    for trade in trades {
+        ledger.apply(trade);
+    }
+    ledger
+}
+
+/// Realized plus unrealized PnL per ticker, positions valued at their last fill.
+fn attribute_pnl(backtest: &[Trades], live: &[Trades], fills: &[FillMatch]) -> Vec<PnlAttribution> {
+    let backtest_ledger = ledger(backtest);
+    let live_ledger = ledger(live);
+
+    let pnl = |ledger: &Ledger, ticker: &str| {
+        ledger.position(ticker).map_or(0, |p| p.realized_pnl) + ledger.unrealized_pnl(ticker)
+    };
+
+    let tickers: BTreeSet<&String> = backtest.iter().chain(live).map(|t| &t.ticker).collect();
+
+    tickers
+        .into_iter()
+        .map(|ticker| {
+            let backtest_pnl = pnl(&backtest_ledger, ticker);
+            let live_pnl = pnl(&live_ledger, ticker);
+            let difference = live_pnl - backtest_pnl;
+
+            let matched = fills.iter().filter(|f| &f.ticker == ticker);
+            let (slippage, fees) = matched.fold((0, 0), |(s, f), fill| {
+                (s - fill.slippage_cost, f - fill.fee_difference)
+            });
+
+            PnlAttribution {
+                ticker: ticker.clone(),
+                backtest_pnl,
+                live_pnl,
+                difference,
+                slippage,
+                fees,
+                unexplained: difference - slippage - fees,
+            }
+        })
+        .collect()
+}
+
+impl Reconciliation {
+    /// Reconciles a live run against the backtest of the same parameters.
+    ///
+    /// Signals and trades are paired by ticker and action when their timestamps are at most
+    /// `tolerance` nanoseconds apart.
+    pub fn new(live: &LiveData, backtest: &BacktestData, tolerance: i64) -> Result<Self> {
+        if live.parameters != backtest.metadata.parameters {
+            return Err(Error::CustomError(
+                "Live and backtest parameters differ".to_string(),
+            ));
+        }
+        if tolerance < 0 {
+            return Err(Error::CustomError(
+                "Tolerance must not be negative".to_string(),
+            ));
+        }
+
+        let backtest_signals = flatten_signals(&backtest.signals);
+        let live_signals = flatten_signals(&live.signals);
+        let (_, missed, extra) = match_by_time(
+            &backtest_signals,
+            &live_signals,
+            tolerance,
+            |s| (s.instruction.ticker.clone(), s.instruction.action),
+            |s| s.timestamp,
+        );
+
+        let (pairs, unmatched_backtest, unmatched_live) = match_by_time(
+            &backtest.trades,
+            &live.trades,
+            tolerance,
+            |t| (t.ticker.clone(), t.action),
+            |t| t.timestamp,
+        );
+        let fills: Vec<FillMatch> = pairs
+            .into_iter()
+            .map(|(b, l)| fill_match(&backtest.trades[b], &live.trades[l]))
+            .collect();
+
+        Ok(Reconciliation {
+            strategy_name: live.parameters.strategy_name.clone(),
+            tolerance,
+            missed_signals: missed
+                .into_iter()
+                .map(|i| backtest_signals[i].clone())
+                .collect(),
+            extra_signals: extra.into_iter().map(|i| live_signals[i].clone()).collect(),
+            unmatched_backtest_trades: unmatched_backtest
+                .into_iter()
+                .map(|i| backtest.trades[i].clone())
+                .collect(),
+            unmatched_live_trades: unmatched_live
+                .into_iter()
+                .map(|i| live.trades[i].clone())
+                .collect(),
+            pnl_attribution: attribute_pnl(&backtest.trades, &live.trades, &fills),
+            total_slippage_cost: fills.iter().map(|f| f.slippage_cost).sum(),
+            total_fee_difference: fills.iter().map(|f| f.fee_difference).sum(),
+            fills,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| Error::Encode(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::{BacktestMetaData, Parameters, StaticStats};
+    use crate::enums::OrderType;
+    use crate::live::AccountSummary;
+    use crate::PRICE_SCALE;
+
+    fn parameters() -> Parameters {
+        Parameters {
+            strategy_name: "Testing".to_string(),
+            capital: 10000,
+            schema: "Ohlcv-1s".to_string(),
+            data_type: "BAR".to_string(),
+            start: 1730160814000000000,
+            end: 1730160814000000000,
+            tickers: vec!["AAPL".to_string()],
+        }
+    }
+
+    fn trade(timestamp: i64, action: TradeAction, price: i64, fees: i64) -> Trades {
+        Trades {
+            trade_id: 1,
+            signal_id: 1,
+            timestamp,
+            ticker: "AAPL".to_string(),
+            quantity: 10 * PRICE_SCALE,
+            avg_price: price * PRICE_SCALE,
+            trade_value: 10 * price * PRICE_SCALE,
+            trade_cost: 10 * price * PRICE_SCALE,
+            action,
+            fees: fees * PRICE_SCALE,
+        }
+    }
+
+    fn signal(timestamp: i64, action: TradeAction) -> Signals {
+        Signals {
+            timestamp,
+            trade_instructions: vec![SignalInstructions {
+                ticker: "AAPL".to_string(),
+                order_type: OrderType::Market,
+                action,
+                signal_id: 1,
+                weight: 13213432,
+                quantity: 2343,
+                limit_price: None,
+                aux_price: None,
+            }],
+        }
+    }
+
+    fn backtest(trades: Vec<Trades>, signals: Vec<Signals>) -> BacktestData {
+        BacktestData {
+            metadata: BacktestMetaData::new(None, "base", parameters(), StaticStats::default()),
+            period_timeseries_stats: vec![],
+            daily_timeseries_stats: vec![],
+            trades,
+            signals,
+            position_timeseries: vec![],
+        }
+    }
+
+    fn live(trades: Vec<Trades>, signals: Vec<Signals>) -> LiveData {
+        LiveData {
+            live_id: None,
+            parameters: parameters(),
+            trades,
+            signals,
+            account: AccountSummary {
+                currency: "USD".to_string(),
+                start_timestamp: 0,
+                start_buying_power: 0,
+                start_excess_liquidity: 0,
+                start_full_available_funds: 0,
+                start_full_init_margin_req: 0,
+                start_full_maint_margin_req: 0,
+                start_futures_pnl: 0,
+                start_net_liquidation: 0,
+                start_total_cash_balance: 0,
+                start_unrealized_pnl: 0,
+                end_timestamp: 0,
+                end_buying_power: 0,
+                end_excess_liquidity: 0,
+                end_full_available_funds: 0,
+                end_full_init_margin_req: 0,
+                end_full_maint_margin_req: 0,
+                end_futures_pnl: 0,
+                end_net_liquidation: 0,
+                end_total_cash_balance: 0,
+                end_unrealized_pnl: 0,
+            },
+            account_snapshots: vec![],
+        }
+    }
+
+    #[test]
+    fn test_reconcile() -> anyhow::Result<()> {
+        let backtest = backtest(
+            vec![
+                trade(100, TradeAction::Buy, 100, 1),
+                trade(200, TradeAction::Sell, 110, 1),
+                trade(300, TradeAction::Buy, 105, 1),
+            ],
+            vec![
+                signal(100, TradeAction::Buy),
+                signal(200, TradeAction::Sell),
+                signal(300, TradeAction::Buy),
+            ],
+        );
+        let live = live(
+            vec![
+                trade(102, TradeAction::Buy, 101, 2),
+                trade(205, TradeAction::Sell, 109, 2),
+            ],
+            vec![
+                signal(101, TradeAction::Buy),
+                signal(204, TradeAction::Sell),
+            ],
+        );
+
+        // Test
+        let reconciliation = Reconciliation::new(&live, &backtest, 10)?;
+
+        // Validate
+        assert_eq!(reconciliation.missed_signals.len(), 1);
+        assert_eq!(reconciliation.missed_signals[0].timestamp, 300);
+        assert!(reconciliation.extra_signals.is_empty());
+
+        assert_eq!(reconciliation.fills.len(), 2);
+        let buy = &reconciliation.fills[0];
+        assert_eq!(buy.live_timestamp, 102);
+        assert_eq!(buy.slippage, PRICE_SCALE);
+        assert_eq!(buy.slippage_cost, 10 * PRICE_SCALE);
+        assert_eq!(buy.fee_difference, PRICE_SCALE);
+        let sell = &reconciliation.fills[1];
+        assert_eq!(sell.slippage, PRICE_SCALE);
+
+        assert_eq!(reconciliation.total_slippage_cost, 20 * PRICE_SCALE);
+        assert_eq!(reconciliation.total_fee_difference, 2 * PRICE_SCALE);
+        assert_eq!(reconciliation.unmatched_backtest_trades.len(), 1);
+        assert!(reconciliation.unmatched_live_trades.is_empty());
+
+        // Backtest: 100 - 3 fees, open position at its fill. Live: 80 - 4 fees.
+        let attribution = &reconciliation.pnl_attribution[0];
+        assert_eq!(attribution.backtest_pnl, 97 * PRICE_SCALE);
+        assert_eq!(attribution.live_pnl, 76 * PRICE_SCALE);
+        assert_eq!(attribution.difference, -21 * PRICE_SCALE);
+        assert_eq!(attribution.slippage, -20 * PRICE_SCALE);
+        assert_eq!(attribution.fees, -2 * PRICE_SCALE);
+        assert_eq!(attribution.unexplained, PRICE_SCALE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconcile_outside_tolerance() -> anyhow::Result<()> {
+        let backtest = backtest(
+            vec![trade(100, TradeAction::Buy, 100, 1)],
+            vec![signal(100, TradeAction::Buy)],
+        );
+        let live = live(
+            vec![trade(150, TradeAction::Buy, 100, 1)],
+            vec![signal(150, TradeAction::Buy)],
+        );
+
+        // Test
+        let reconciliation = Reconciliation::new(&live, &backtest, 10)?;
+
+        // Validate
+        assert_eq!(reconciliation.missed_signals.len(), 1);
+        assert_eq!(reconciliation.extra_signals.len(), 1);
+        assert!(reconciliation.fills.is_empty());
+        assert_eq!(reconciliation.unmatched_live_trades.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconcile_json() -> anyhow::Result<()> {
+        let backtest = backtest(vec![trade(100, TradeAction::Buy, 100, 1)], vec![]);
+        let live = live(vec![trade(100, TradeAction::Buy, 101, 1)], vec![]);
+        let reconciliation = Reconciliation::new(&live, &backtest, 0)?;
+
+        // Test
+        let json = reconciliation.to_json()?;
+        let decoded: Reconciliation = serde_json::from_str(&json)?;
+
+        // Validate
+        assert_eq!(reconciliation, decoded);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reconcile_parameters_differ() {
+        let backtest = backtest(vec![], vec![]);
+        let mut live = live(vec![], vec![]);
+        live.parameters.capital = 20000;
+
+        // Test
+        let result = Reconciliation::new(&live, &backtest, 0);
+
+        // Validate
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_reconcile_large_capital() -> anyhow::Result<()> {
+        let mut backtest = backtest(vec![trade(100, TradeAction::Buy, 100, 1)], vec![]);
+        let mut live = live(vec![trade(100, TradeAction::Buy, 101, 1)], vec![]);
+        backtest.metadata.parameters.capital = i64::MAX / 10;
+        live.parameters.capital = i64::MAX / 10;
+
+        // Test
+        let reconciliation = Reconciliation::new(&live, &backtest, 0)?;
+
+        // Validate
+        assert_eq!(reconciliation.pnl_attribution.len(), 1);
+        assert_eq!(reconciliation.total_slippage_cost, 10 * PRICE_SCALE);
+        Ok(())
+    }
+}
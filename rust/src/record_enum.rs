@@ -8,7 +8,7 @@ use std::hash::Hash;
 #[cfg(feature = "python")]
 use pyo3::prelude::*;
 
-#[cfg_attr(feature = "python", derive(IntoPyObject))]
+#[cfg_attr(feature = "python", derive(IntoPyObject, FromPyObject))]
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize)]
 pub enum RecordEnum {
     Mbp1(Mbp1Msg),
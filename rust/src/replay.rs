@@ -0,0 +1,532 @@
+use crate::decode::Decoder;
+use crate::encode::CombinedEncoder;
+use crate::metadata::Metadata;
+use crate::params::RetrieveParams;
+use crate::record_enum::RecordEnum;
+use crate::retrieve::{open_files, Retrieval};
+use crate::store::DataStore;
+use crate::{Error, Result};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Path the `RetrieveParams` JSON is posted to.
+pub const RECORDS_PATH: &str = "/records";
+const MAX_BODY_LENGTH: usize = 1 << 20;
+const MAX_HEADER_LENGTH: u64 = 1 << 14;
+const CHUNK_SIZE: usize = 1 << 16;
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(30);
+
+type Records = Box<dyn Iterator<Item = Result<RecordEnum>>>;
+
+/// Directory the records are served from.
+pub enum ReplaySource {
+    /// A `DataStore` root, recognised by its manifest.
+    Store(DataStore),
+    /// Any other directory, every `.bin` file below it is a candidate source.
+    Files(PathBuf),
+}
+
+impl ReplaySource {
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref();
+        if !dir.is_dir() {
+            return Err(Error::CustomError(format!(
+                "{} is not a directory",
+                dir.display()
+            )));
+        }
+
+        if dir.join("manifest.json").exists() {
+            Ok(ReplaySource::Store(DataStore::open(dir)?))
+        } else {
+            Ok(ReplaySource::Files(dir.to_path_buf()))
+        }
+    }
+
+    /// Metadata and records matching `params`.
+    ///
+    /// In a plain directory, the files with the requested schema and dataset holding any of
+    /// the symbols are merged, see `Retrieval::new`.
+    pub fn retrieve(&self, params: &RetrieveParams) -> Result<(Metadata, Records)> {
+        match self {
+            ReplaySource::Store(store) => {
+                let reader = store.read(params)?;
+                Ok((reader.metadata().clone(), Box::new(reader)))
+            }
+            ReplaySource::Files(dir) => {
+                let mut paths = Vec::new();
+                for path in bin_files(dir)? {
+                    let metadata = Decoder::<BufReader<File>>::from_file(&path)
+                        .ok()
+                        .and_then(|mut decoder| decoder.metadata());
+                    if metadata.is_some_and(|m| {
+                        m.schema == params.schema
+                            && m.dataset == params.dataset
+                            && m.mappings.map.values().any(|t| params.symbols.contains(t))
+                    }) {
+                        paths.push(path);
+                    }
+                }
+
+                let retrieval = Retrieval::new(params, open_files(&paths)?)?;
+                Ok((retrieval.metadata().clone(), Box::new(retrieval)))
+            }
+        }
+    }
+}
+
+fn bin_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(bin_files(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "bin") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Local stand-in for the historical data service.
+///
+/// Answers `POST /records` with a `RetrieveParams` JSON body by a metadata-prefixed mbinary
+/// stream in a chunked body, errors are returned as plain text with a 4xx or 5xx status. A
+/// failure while streaming closes the connection without the terminating chunk.
+pub struct ReplayServer {
+    source: Arc<ReplaySource>,
+}
+
+impl ReplayServer {
+    pub fn new(source: ReplaySource) -> Self {
+        ReplayServer {
+            source: Arc::new(source),
+        }
+    }
+
+    /// Serves connections from `listener`, each on its own thread.
+    pub fn serve(&self, listener: TcpListener) -> io::Result<()> {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("Replay accept failed: {}", e);
+                    continue;
+                }
+            };
+            let source = Arc::clone(&self.source);
+            thread::spawn(move || {
+                if let Err(e) = handle(&source, stream) {
+                    eprintln!("Replay connection failed: {}", e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+fn handle(source: &ReplaySource, stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(SOCKET_TIMEOUT))?;
+    stream.set_write_timeout(Some(SOCKET_TIMEOUT))?;
+    let mut reader = BufReader::new(&stream);
+    let mut writer = BufWriter::new(&stream);
+
+    let params = match read_request(&mut reader) {
+        Ok(params) => params,
+        Err((status, message)) => return respond_error(&mut writer, status, &message),
+    };
+    if let Err(e) = validate(&params) {
+        return respond_error(&mut writer, 400, &e.to_string());
+    }
+
+    let (metadata, records) = match source.retrieve(&params) {
+        Ok(retrieved) => retrieved,
+        Err(e @ (Error::Io(_) | Error::Decode(_))) => {
+            return respond_error(&mut writer, 500, &e.to_string())
+        }
+        Err(e @ Error::CustomError(_)) => return respond_error(&mut writer, 404, &e.to_string()),
+        Err(e) => return respond_error(&mut writer, 400, &e.to_string()),
+    };
+
+    write!(
+        writer,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+    )?;
+    let mut body = ChunkedWriter::new(&mut writer);
+    let mut encoder = CombinedEncoder::new(&mut body);
+    encoder.encode_metadata(&metadata)?;
+    for record in records {
+        let record = record.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        encoder.encode_record(&record.to_record_ref())?;
+    }
+    body.finish()
+}
+
+/// Rejects params the sources cannot be queried with.
+fn validate(params: &RetrieveParams) -> Result<()> {
+    let interval = params.schema_interval()?;
+    if params.start_ts > params.end_ts || params.end_ts > i64::MAX - interval {
+        return Err(Error::CustomError(format!(
+            "Invalid time range {} to {}",
+            params.start_ts, params.end_ts
+        )));
+    }
+    params.adjusted()?.rtype()?;
+    Ok(())
+}
+
+/// Body writer for `Transfer-Encoding: chunked`.
+///
+/// Dropped without `finish`, the terminating chunk is never sent so the client sees an
+/// incomplete body rather than a complete one.
+struct ChunkedWriter<W: Write> {
+    inner: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> ChunkedWriter<W> {
+    fn new(inner: W) -> Self {
+        ChunkedWriter {
+            inner,
+            buffer: Vec::with_capacity(CHUNK_SIZE),
+        }
+    }
+
+    fn write_chunk(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            write!(self.inner, "{:x}\r\n", self.buffer.len())?;
+            self.inner.write_all(&self.buffer)?;
+            self.inner.write_all(b"\r\n")?;
+            self.buffer.clear();
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered data and the terminating chunk.
+    fn finish(mut self) -> io::Result<()> {
+        self.write_chunk()?;
+        self.inner.write_all(b"0\r\n\r\n")?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Write for ChunkedWriter<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(data);
+        if self.buffer.len() >= CHUNK_SIZE {
+            self.write_chunk()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_chunk()?;
+        self.inner.flush()
+    }
+}
+
+fn read_request<R: BufRead>(reader: &mut R) -> std::result::Result<RetrieveParams, (u16, String)> {
+    let bad_request = |e: io::Error| (400, e.to_string());
+    let too_large = || (431, "Request header is too large".to_string());
+
+    // The request line and headers share one budget, a line cut short by it has no newline
+    let mut head = Read::take(&mut *reader, MAX_HEADER_LENGTH);
+    let mut request_line = String::new();
+    head.read_line(&mut request_line).map_err(bad_request)?;
+    if !request_line.ends_with('\n') && head.limit() == 0 {
+        return Err(too_large());
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        let read = head.read_line(&mut line).map_err(bad_request)?;
+        if !line.ends_with('\n') && head.limit() == 0 {
+            return Err(too_large());
+        }
+        if read == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| (400, "Invalid Content-Length".to_string()))?;
+            }
+        }
+    }
+
+    if path != RECORDS_PATH {
+        return Err((404, format!("Unknown path {}", path)));
+    }
+    if method != "POST" {
+        return Err((405, format!("{} {} is not supported", method, path)));
+    }
+    if content_length > MAX_BODY_LENGTH {
+        return Err((413, "Request body is too large".to_string()));
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(bad_request)?;
+    serde_json::from_slice(&body).map_err(|e| (400, format!("Invalid RetrieveParams: {}", e)))
+}
+
+fn respond_error<W: Write>(writer: &mut W, status: u16, message: &str) -> io::Result<()> {
+    let reason = match status {
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        message.len(),
+        message
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{Dataset, Schema, Stype};
+    use crate::record_ref::RecordRef;
+    use crate::records::{OhlcvMsg, RecordHeader};
+    use crate::symbols::SymbolMap;
+    use std::io::Cursor;
+    use std::net::SocketAddr;
+
+    const MINUTE: u64 = 60_000_000_000;
+
+    fn ohlcv(instrument_id: u32, ts_event: u64) -> OhlcvMsg {
+        OhlcvMsg {
+            hd: RecordHeader::new::<OhlcvMsg>(instrument_id, ts_event, 0),
+            open: 100,
+            high: 200,
+            low: 50,
+            close: 150,
+            volume: 1000,
+        }
+    }
+
+    fn params(symbols: &[&str]) -> RetrieveParams {
+        RetrieveParams {
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            start_ts: 0,
+            end_ts: 2 * MINUTE as i64,
+            schema: Schema::Ohlcv1M,
+            dataset: Dataset::Equities,
+            stype: Stype::Raw,
+        }
+    }
+
+    fn spawn_server(dir: &Path) -> anyhow::Result<SocketAddr> {
+        let server = ReplayServer::new(ReplaySource::open(dir)?);
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        thread::spawn(move || server.serve(listener));
+        Ok(addr)
+    }
+
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> anyhow::Result<Vec<u8>> {
+        let mut stream = TcpStream::connect(addr)?;
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        Ok(response)
+    }
+
+    fn split_response(response: &[u8]) -> (String, &[u8]) {
+        let end = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let status = String::from_utf8_lossy(&response[..end])
+            .lines()
+            .next()
+            .unwrap()
+            .to_string();
+        (status, &response[end + 4..])
+    }
+
+    /// Data of a chunked body, `None` when the terminating chunk is missing.
+    fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+        let mut data = Vec::new();
+        loop {
+            let line = body.windows(2).position(|w| w == b"\r\n")?;
+            let size = usize::from_str_radix(std::str::from_utf8(&body[..line]).ok()?, 16).ok()?;
+            if size == 0 {
+                return Some(data);
+            }
+            data.extend_from_slice(body.get(line + 2..line + 2 + size)?);
+            body = body.get(line + 4 + size..)?;
+        }
+    }
+
+    #[test]
+    fn test_replay_files() -> anyhow::Result<()> {
+        let dir = PathBuf::from("tests/test_replay_files");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        mappings.add_instrument("TSLA", 2);
+        let metadata = Metadata::new(Schema::Ohlcv1M, Dataset::Equities, 0, 0, mappings);
+        let records = [ohlcv(1, 0), ohlcv(2, MINUTE), ohlcv(1, 2 * MINUTE)];
+        let refs: Vec<RecordRef> = records.iter().map(RecordRef::from).collect();
+        let mut encoder = CombinedEncoder::new(Vec::new());
+        encoder.encode(&metadata, &refs)?;
+        encoder.write_to_file(&dir.join("bars.bin"), false)?;
+        let addr = spawn_server(&dir)?;
+
+        // Test
+        let ok = request(
+            addr,
+            "POST",
+            RECORDS_PATH,
+            &serde_json::to_string(&params(&["AAPL"]))?,
+        )?;
+        let missing = request(
+            addr,
+            "POST",
+            RECORDS_PATH,
+            &serde_json::to_string(&params(&["NVDA"]))?,
+        )?;
+        let invalid = request(addr, "POST", RECORDS_PATH, "{\"symbols\": 1}")?;
+        let mut reversed = params(&["AAPL"]);
+        reversed.start_ts = reversed.end_ts + 1;
+        let range = request(
+            addr,
+            "POST",
+            RECORDS_PATH,
+            &serde_json::to_string(&reversed)?,
+        )?;
+        let method = request(addr, "GET", RECORDS_PATH, "")?;
+
+        // Validate
+        let (status, body) = split_response(&ok);
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        let mut decoder = Decoder::new(Cursor::new(dechunk(body).unwrap()))?;
+        let decoded_metadata = decoder.metadata().unwrap();
+        assert_eq!(decoded_metadata.mappings.map.len(), 1);
+        assert_eq!(decoded_metadata.end, 2 * MINUTE);
+        let decoded = decoder.decode()?;
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].msg().header().instrument_id, 1);
+
+        let (status, body) = split_response(&missing);
+        assert_eq!(status, "HTTP/1.1 404 Not Found");
+        assert!(String::from_utf8_lossy(body).contains("NVDA"));
+        assert!(split_response(&invalid).0.contains("400"));
+        assert_eq!(split_response(&range).0, "HTTP/1.1 400 Bad Request");
+        assert!(split_response(&method).0.contains("405"));
+
+        // Cleanup
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_store() -> anyhow::Result<()> {
+        let dir = PathBuf::from("tests/test_replay_store");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        let metadata = Metadata::new(Schema::Ohlcv1M, Dataset::Equities, 0, 0, mappings);
+        let records = [ohlcv(1, 0), ohlcv(1, MINUTE)];
+        let refs: Vec<RecordRef> = records.iter().map(RecordRef::from).collect();
+        DataStore::open(&dir)?.write_records(&metadata, &refs)?;
+        let addr = spawn_server(&dir)?;
+
+        // Test
+        let response = request(
+            addr,
+            "POST",
+            RECORDS_PATH,
+            &serde_json::to_string(&params(&["AAPL"]))?,
+        )?;
+
+        // Validate
+        let (status, body) = split_response(&response);
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        let decoded = Decoder::new(Cursor::new(dechunk(body).unwrap()))?.decode()?;
+        assert_eq!(decoded.len(), 2);
+
+        // Cleanup
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_corrupt() -> anyhow::Result<()> {
+        let dir = PathBuf::from("tests/test_replay_corrupt");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        let metadata = Metadata::new(Schema::Ohlcv1M, Dataset::Equities, 0, 0, mappings);
+        let records = [ohlcv(1, 0), ohlcv(1, MINUTE)];
+        let refs: Vec<RecordRef> = records.iter().map(RecordRef::from).collect();
+        let mut encoder = CombinedEncoder::new(Vec::new());
+        encoder.encode(&metadata, &refs)?;
+        let path = dir.join("bars.bin");
+        encoder.write_to_file(&path, false)?;
+        // A length shorter than a record header fails decoding after the records
+        let mut file = File::options().append(true).open(&path)?;
+        file.write_all(&[1; 16])?;
+        let addr = spawn_server(&dir)?;
+
+        // Test
+        let response = request(
+            addr,
+            "POST",
+            RECORDS_PATH,
+            &serde_json::to_string(&params(&["AAPL"]))?,
+        )?;
+
+        // Validate
+        let (status, body) = split_response(&response);
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert!(dechunk(body).is_none());
+
+        // Cleanup
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_header_too_large() {
+        let long_line = vec![b'a'; MAX_HEADER_LENGTH as usize + 1];
+        let mut long_header = b"POST /records HTTP/1.1\r\nX-Padding: ".to_vec();
+        long_header.extend_from_slice(&long_line);
+
+        // Test
+        let line = read_request(&mut Cursor::new(long_line));
+        let header = read_request(&mut Cursor::new(long_header));
+
+        // Validate
+        assert_eq!(line.unwrap_err().0, 431);
+        assert_eq!(header.unwrap_err().0, 431);
+    }
+}
@@ -0,0 +1,281 @@
+use crate::decode::Decoder;
+use crate::filter::FilteredDecoder;
+use crate::metadata::Metadata;
+use crate::params::RetrieveParams;
+use crate::record_enum::RecordEnum;
+use crate::symbols::SymbolMap;
+use crate::{Error, Result};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Records from local sources matching a `RetrieveParams`, merged in `ts_event` order.
+pub struct Retrieval<R> {
+    metadata: Metadata,
+    sources: Vec<Source<R>>,
+}
+
+struct Source<R> {
+    decoder: FilteredDecoder<R>,
+    head: Option<RecordEnum>,
+    done: bool,
+}
+
+impl<R: Read> Retrieval<R> {
+    /// Applies `params` to `decoders`, which must all start with metadata.
+    ///
+    /// Fails when a source is incompatible with `params`, a symbol is in none of the sources
+    /// or two sources map an instrument id to different symbols.
+    pub fn new(params: &RetrieveParams, decoders: Vec<Decoder<R>>) -> Result<Self> {
+        let retrieval = Self::merge(params, decoders)?;
+
+        let tickers: Vec<&String> = retrieval.metadata.mappings.map.values().collect();
+        if let Some(missing) = params.symbols.iter().find(|s| !tickers.contains(s)) {
+            return Err(Error::CustomError(format!(
+                "Symbol {} not found in any source",
+                missing
+            )));
+        }
+        Ok(retrieval)
+    }
+
+    /// `new` without requiring every symbol to be in the sources.
+    pub(crate) fn merge(params: &RetrieveParams, decoders: Vec<Decoder<R>>) -> Result<Self> {
+        let adjusted = params.adjusted()?;
+        let mut mappings = SymbolMap::new();
+        let mut sources = Vec::with_capacity(decoders.len());
+
+        for decoder in decoders {
+            let metadata = decoder.metadata.clone().ok_or_else(|| {
+                Error::CustomError("Source has no metadata to resolve symbols".to_string())
+            })?;
+            let decoder = decoder.filter(params.record_filter(&metadata)?);
+
+            for (id, ticker) in decoder.metadata().into_iter().flat_map(|m| m.mappings.map) {
+                match mappings.get_instrument_ticker(id) {
+                    Some(existing) if existing != ticker => {
+                        return Err(Error::CustomError(format!(
+                            "Instrument id {} maps to both {} and {}",
+                            id, existing, ticker
+                        )));
+                    }
+                    _ => mappings.add_instrument(&ticker, id),
+                }
+            }
+
+            sources.push(Source {
+                decoder,
+                head: None,
+                done: false,
+            });
+        }
+
+        Ok(Retrieval {
+            metadata: Metadata::new(
+                params.schema,
+                params.dataset,
+                adjusted.start_ts.max(0) as u64,
+                adjusted.end_ts.max(0) as u64,
+                mappings,
+            ),
+            sources,
+        })
+    }
+
+    /// Metadata for the retrieved records, with the mappings of every matched symbol.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+}
+
+impl<R: Read> Iterator for Retrieval<R> {
+    type Item = Result<RecordEnum>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for source in self.sources.iter_mut() {
+            if source.head.is_none() && !source.done {
+                match source.decoder.next() {
+                    Some(Ok(record)) => source.head = Some(record),
+                    Some(Err(e)) => return Some(Err(e.into())),
+                    None => source.done = true,
+                }
+            }
+        }
+
+        // Earliest head, the first source wins ties
+        self.sources
+            .iter_mut()
+            .filter(|source| source.head.is_some())
+            .min_by_key(|source| source.head.as_ref().map(|r| r.msg().header().ts_event))
+            .and_then(|source| source.head.take())
+            .map(Ok)
+    }
+}
+
+/// Applies `params` to local mbinary files, see `Retrieval::new`.
+pub fn retrieve_files<P: AsRef<Path>>(
+    params: &RetrieveParams,
+    file_paths: &[P],
+) -> Result<Retrieval<BufReader<File>>> {
+    Retrieval::new(params, open_files(file_paths)?)
+}
+
+pub(crate) fn open_files<P: AsRef<Path>>(
+    file_paths: &[P],
+) -> Result<Vec<Decoder<BufReader<File>>>> {
+    file_paths
+        .iter()
+        .map(|path| {
+            Decoder::<BufReader<File>>::from_file(path).map_err(|e| {
+                Error::Io(std::io::Error::new(
+                    e.kind(),
+                    format!("{}: {}", path.as_ref().display(), e),
+                ))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encode::CombinedEncoder;
+    use crate::enums::{Dataset, Schema, Stype};
+    use crate::record_ref::RecordRef;
+    use crate::records::{OhlcvMsg, RecordHeader};
+    use std::io::Cursor;
+
+    const MINUTE: u64 = 60_000_000_000;
+
+    fn ohlcv(instrument_id: u32, ts_event: u64) -> OhlcvMsg {
+        OhlcvMsg {
+            hd: RecordHeader::new::<OhlcvMsg>(instrument_id, ts_event, 0),
+            open: 100,
+            high: 200,
+            low: 50,
+            close: 150,
+            volume: 1000,
+        }
+    }
+
+    fn source(
+        schema: Schema,
+        tickers: &[(&str, u32)],
+        records: &[OhlcvMsg],
+    ) -> anyhow::Result<Decoder<Cursor<Vec<u8>>>> {
+        let mut mappings = SymbolMap::new();
+        for (ticker, id) in tickers {
+            mappings.add_instrument(ticker, *id);
+        }
+        let metadata = Metadata::new(schema, Dataset::Equities, 0, 10 * MINUTE, mappings);
+        let refs: Vec<RecordRef> = records.iter().map(RecordRef::from).collect();
+
+        let mut buffer = Vec::new();
+        CombinedEncoder::new(&mut buffer).encode(&metadata, &refs)?;
+        Ok(Decoder::new(Cursor::new(buffer))?)
+    }
+
+    fn params(symbols: &[&str], start: u64, end: u64) -> RetrieveParams {
+        RetrieveParams {
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            start_ts: start as i64,
+            end_ts: end as i64,
+            schema: Schema::Ohlcv1M,
+            dataset: Dataset::Equities,
+            stype: Stype::Raw,
+        }
+    }
+
+    fn headers(records: &[RecordEnum]) -> Vec<(u32, u64)> {
+        records
+            .iter()
+            .map(|r| (r.msg().header().instrument_id, r.msg().header().ts_event))
+            .collect()
+    }
+
+    #[test]
+    fn test_retrieval_merges_sources() -> anyhow::Result<()> {
+        let first = source(
+            Schema::Ohlcv1M,
+            &[("AAPL", 1), ("TSLA", 2)],
+            &[ohlcv(1, 0), ohlcv(2, MINUTE), ohlcv(1, 3 * MINUTE)],
+        )?;
+        let second = source(
+            Schema::Ohlcv1M,
+            &[("AAPL", 1), ("MSFT", 3)],
+            &[ohlcv(3, MINUTE), ohlcv(1, 2 * MINUTE), ohlcv(1, 5 * MINUTE)],
+        )?;
+
+        // Test
+        let retrieval = Retrieval::new(
+            &params(&["AAPL", "MSFT"], 0, 4 * MINUTE),
+            vec![first, second],
+        )?;
+        let metadata = retrieval.metadata().clone();
+        let records = retrieval.collect::<Result<Vec<_>>>()?;
+
+        // Validate
+        assert_eq!(
+            headers(&records),
+            vec![(1, 0), (3, MINUTE), (1, 2 * MINUTE), (1, 3 * MINUTE)]
+        );
+        assert_eq!(metadata.schema, Schema::Ohlcv1M);
+        assert_eq!(metadata.end, 4 * MINUTE);
+        assert_eq!(metadata.mappings.map.len(), 2);
+        assert_eq!(
+            metadata.mappings.get_instrument_ticker(3),
+            Some("MSFT".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_retrieval_errors() -> anyhow::Result<()> {
+        let aapl = || source(Schema::Ohlcv1M, &[("AAPL", 1)], &[ohlcv(1, 0)]);
+
+        // Test
+        let missing = Retrieval::new(&params(&["AAPL", "NVDA"], 0, MINUTE), vec![aapl()?]);
+        let conflict = Retrieval::new(
+            &params(&["AAPL", "TSLA"], 0, MINUTE),
+            vec![aapl()?, source(Schema::Ohlcv1M, &[("TSLA", 1)], &[])?],
+        );
+        let schema = Retrieval::new(
+            &params(&["AAPL"], 0, MINUTE),
+            vec![source(Schema::Trades, &[("AAPL", 1)], &[])?],
+        );
+
+        // Validate
+        assert!(missing.err().unwrap().to_string().contains("NVDA"));
+        assert!(conflict.err().unwrap().to_string().contains("maps to both"));
+        assert!(schema.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_retrieve_files() -> anyhow::Result<()> {
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        let metadata = Metadata::new(Schema::Ohlcv1M, Dataset::Equities, 0, 0, mappings);
+        let records = [ohlcv(1, 0), ohlcv(1, MINUTE)];
+        let refs: Vec<RecordRef> = records.iter().map(RecordRef::from).collect();
+
+        let mut buffer = Vec::new();
+        let mut encoder = CombinedEncoder::new(&mut buffer);
+        encoder.encode(&metadata, &refs)?;
+        let file = std::path::PathBuf::from("tests/test_retrieve_files.bin");
+        encoder.write_to_file(&file, false)?;
+
+        // Test
+        let retrieved =
+            retrieve_files(&params(&["AAPL"], 0, MINUTE), &[&file])?.collect::<Result<Vec<_>>>()?;
+        let missing = retrieve_files(&params(&["AAPL"], 0, MINUTE), &["tests/missing.bin"]);
+
+        // Validate
+        assert_eq!(headers(&retrieved), vec![(1, 0)]);
+        assert!(missing.err().unwrap().to_string().contains("missing.bin"));
+
+        // Cleanup
+        std::fs::remove_file(&file)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,508 @@
+use crate::backtest::{SignalInstructions, Signals, Trades};
+use crate::enums::OrderType;
+use crate::ledger::notional;
+use crate::record_enum::RecordEnum;
+use crate::records::{BidAskPair, Record};
+use crate::symbols::SymbolMap;
+use crate::{Error, Result, PRICE_SCALE};
+use dbn::UNDEF_PRICE;
+use serde::{Deserialize, Serialize};
+
+/// Fees charged on a simulated fill, all values scaled by 1e9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FeeModel {
+    /// Flat amount per fill.
+    PerFill(i64),
+    /// Amount per unit of filled quantity.
+    PerUnit(i64),
+    /// Basis points of the fill's notional.
+    Bps(i64),
+}
+
+impl Default for FeeModel {
+    fn default() -> Self {
+        FeeModel::PerFill(0)
+    }
+}
+
+impl FeeModel {
+    /// Fees for `quantity` filled at `price`, both scaled by 1e9.
+    pub fn fees(&self, quantity: i64, price: i64) -> i64 {
+        match *self {
+            FeeModel::PerFill(fee) => fee,
+            FeeModel::PerUnit(fee) => notional(quantity.abs(), fee) as i64,
+            FeeModel::Bps(bps) => {
+                (notional(quantity.abs(), price).abs() * bps as i128
+                    / (10_000 * PRICE_SCALE as i128)) as i64
+            }
+        }
+    }
+}
+
+/// When a limit price is satisfied by the touch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitFill {
+    /// The touch is at or through the limit.
+    #[default]
+    Touch,
+    /// The touch is strictly through the limit, a touch at the limit is assumed to queue.
+    Cross,
+}
+
+/// Rules of a `FillSimulator`.
+///
+/// Fills are always for the full quantity at the executable touch price, only the timing,
+/// limit rule and fees are configurable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulatorConfig {
+    /// Nanoseconds between a signal and its orders reaching the market.
+    pub latency: i64,
+    pub fee_model: FeeModel,
+    /// Applies to limit orders and to stop limits once triggered.
+    pub limit_fill: LimitFill,
+}
+
+#[derive(Debug, Clone)]
+struct Order {
+    active_at: i64,
+    instruction: SignalInstructions,
+    triggered: bool,
+}
+
+impl Order {
+    fn new(timestamp: i64, instruction: &SignalInstructions, latency: i64) -> Result<Self> {
+        let missing = |field: &str| {
+            Error::CustomError(format!(
+                "{} order for {} is missing {}",
+                instruction.order_type, instruction.ticker, field
+            ))
+        };
+
+        match instruction.order_type {
+            OrderType::Market => {}
+            OrderType::Limit => {
+                instruction
+                    .limit_price
+                    .ok_or_else(|| missing("limit_price"))?;
+            }
+            OrderType::StopMarket => {
+                instruction.aux_price.ok_or_else(|| missing("aux_price"))?;
+            }
+            OrderType::StopLimit => {
+                instruction
+                    .limit_price
+                    .ok_or_else(|| missing("limit_price"))?;
+                instruction.aux_price.ok_or_else(|| missing("aux_price"))?;
+            }
+        }
+
+        Ok(Order {
+            active_at: timestamp + latency,
+            instruction: instruction.clone(),
+            triggered: false,
+        })
+    }
+
+    fn is_buy(&self) -> bool {
+        self.instruction.action.direction() > 0
+    }
+
+    /// Whether `price` satisfies `limit` for the order's side under `rule`.
+    fn reaches(&self, price: i64, limit: i64, rule: LimitFill) -> bool {
+        match (rule, self.is_buy()) {
+            (LimitFill::Touch, true) => price <= limit,
+            (LimitFill::Touch, false) => price >= limit,
+            (LimitFill::Cross, true) => price < limit,
+            (LimitFill::Cross, false) => price > limit,
+        }
+    }
+
+    /// Price the order fills at against an executable `price`, if it fills.
+    fn fill_price(&mut self, price: i64, rule: LimitFill) -> Option<i64> {
+        let limit = self.instruction.limit_price;
+        match self.instruction.order_type {
+            OrderType::Market => Some(price),
+            OrderType::Limit => self.reaches(price, limit?, rule).then_some(price),
+            OrderType::StopMarket | OrderType::StopLimit => {
+                let stop = self.instruction.aux_price?;
+                let through_stop = if self.is_buy() {
+                    price >= stop
+                } else {
+                    price <= stop
+                };
+                self.triggered = self.triggered || through_stop;
+
+                match self.instruction.order_type {
+                    _ if !self.triggered => None,
+                    OrderType::StopLimit => self.reaches(price, limit?, rule).then_some(price),
+                    _ => Some(price),
+                }
+            }
+        }
+    }
+}
+
+/// Best bid and ask a record shows, trades printing at both.
+fn quote(record: &RecordEnum) -> Option<(i64, i64)> {
+    let top = |levels: &[BidAskPair; 1]| (levels[0].bid_px, levels[0].ask_px);
+    match record {
+        RecordEnum::Mbp1(msg) | RecordEnum::Tbbo(msg) => Some(top(&msg.levels)),
+        RecordEnum::Bbo(msg) => Some(top(&msg.levels)),
+        RecordEnum::Trade(msg) => Some((msg.price, msg.price)),
+        RecordEnum::Ohlcv(_) => None,
+    }
+}
+
+/// Replays signal instructions against quotes and trades to produce simulated fills.
+///
+/// Orders reach the market `latency` after their signal and fill in full on the first
+/// record for their ticker that satisfies them: market orders at the touch (ask for buys,
+/// bid for sells, or the trade price), limits once the touch satisfies the `LimitFill` rule,
+/// and stops once the touch reaches the stop, as a market or limit order from then on.
+/// Orders that never fill stay open.
+#[derive(Debug, Clone)]
+pub struct FillSimulator {
+    config: SimulatorConfig,
+    mappings: SymbolMap,
+    orders: Vec<Order>,
+    next_trade_id: i32,
+}
+
+impl FillSimulator {
+    pub fn new(config: SimulatorConfig, mappings: SymbolMap) -> Self {
+        FillSimulator {
+            config,
+            mappings,
+            orders: Vec::new(),
+            next_trade_id: 1,
+        }
+    }
+
+    /// Queues every instruction in `signal`.
+    pub fn submit(&mut self, signal: &Signals) -> Result<()> {
+        for instruction in &signal.trade_instructions {
+            let order = Order::new(signal.timestamp, instruction, self.config.latency)?;
+            self.orders.push(order);
+        }
+        Ok(())
+    }
+
+    /// Instructions that have not filled yet.
+    pub fn open_orders(&self) -> impl Iterator<Item = &SignalInstructions> {
+        self.orders.iter().map(|o| &o.instruction)
+    }
+
+    /// Fills the open orders `record` satisfies.
+    pub fn on_record(&mut self, record: &RecordEnum) -> Vec<Trades> {
+        let Some((bid, ask)) = quote(record) else {
+            return Vec::new();
+        };
+        let timestamp = record.header().ts_event as i64;
+        let Some(ticker) = self
+            .mappings
+            .get_instrument_ticker(record.header().instrument_id)
+        else {
+            return Vec::new();
+        };
+
+        let mut fills = Vec::new();
+        let mut i = 0;
+        while i < self.orders.len() {
+            let order = &mut self.orders[i];
+            if order.instruction.ticker != ticker || order.active_at > timestamp {
+                i += 1;
+                continue;
+            }
+
+            let touch = if order.is_buy() { ask } else { bid };
+            let price = if touch == UNDEF_PRICE {
+                None
+            } else {
+                order.fill_price(touch, self.config.limit_fill)
+            };
+            match price {
+                Some(price) => {
+                    let order = self.orders.remove(i);
+                    fills.push(self.fill(&order.instruction, timestamp, price));
+                }
+                None => i += 1,
+            }
+        }
+        fills
+    }
+
+    fn fill(&mut self, instruction: &SignalInstructions, timestamp: i64, price: i64) -> Trades {
+        let quantity = instruction.quantity.abs() as i64 * PRICE_SCALE;
+        // Cash impact of the fill, negative when buying
+        let trade_value = -(notional(quantity, price) as i64) * instruction.action.direction();
+        let fees = self.config.fee_model.fees(quantity, price);
+        let trade_id = self.next_trade_id;
+        self.next_trade_id += 1;
+
+        Trades {
+            trade_id,
+            signal_id: instruction.signal_id,
+            timestamp,
+            ticker: instruction.ticker.clone(),
+            quantity,
+            avg_price: price,
+            trade_value,
+            // Cash impact including fees, which are always paid
+            trade_cost: trade_value - fees,
+            action: instruction.action,
+            fees,
+        }
+    }
+
+    /// Submits each signal as time reaches it and returns every fill from `records`.
+    ///
+    /// `records` must be in time order.
+    pub fn simulate(&mut self, signals: &[Signals], records: &[RecordEnum]) -> Result<Vec<Trades>> {
+        let mut signals: Vec<&Signals> = signals.iter().collect();
+        signals.sort_by_key(|s| s.timestamp);
+        let mut pending = signals.into_iter().peekable();
+
+        let mut trades = Vec::new();
+        for record in records {
+            let timestamp = record.header().ts_event as i64;
+            while let Some(signal) = pending.next_if(|s| s.timestamp <= timestamp) {
+                self.submit(signal)?;
+            }
+            trades.extend(self.on_record(record));
+        }
+
+        for signal in pending {
+            self.submit(signal)?;
+        }
+        Ok(trades)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::TradeAction;
+    use crate::records::{Mbp1Msg, RecordHeader, TradeMsg};
+
+    fn quote_msg(ts_event: u64, bid: i64, ask: i64) -> RecordEnum {
+        RecordEnum::Mbp1(Mbp1Msg {
+            hd: RecordHeader::new::<Mbp1Msg>(1, ts_event, 0),
+            price: bid * PRICE_SCALE,
+            size: 1,
+            action: 'A' as i8,
+            side: 'B' as i8,
+            depth: 0,
+            flags: 0,
+            ts_recv: ts_event,
+            ts_in_delta: 0,
+            sequence: 0,
+            discriminator: 0,
+            levels: [BidAskPair {
+                bid_px: bid * PRICE_SCALE,
+                ask_px: ask * PRICE_SCALE,
+                bid_sz: 10,
+                ask_sz: 10,
+                bid_ct: 1,
+                ask_ct: 1,
+            }],
+        })
+    }
+
+    fn trade_msg(ts_event: u64, price: i64) -> RecordEnum {
+        RecordEnum::Trade(TradeMsg {
+            hd: RecordHeader::new::<TradeMsg>(1, ts_event, 0),
+            price: price * PRICE_SCALE,
+            size: 1,
+            action: 'T' as i8,
+            side: 'A' as i8,
+            depth: 0,
+            flags: 0,
+            ts_recv: ts_event,
+            ts_in_delta: 0,
+            sequence: 0,
+        })
+    }
+
+    fn signal(
+        timestamp: i64,
+        order_type: OrderType,
+        action: TradeAction,
+        limit_price: Option<i64>,
+        aux_price: Option<i64>,
+    ) -> Signals {
+        Signals {
+            timestamp,
+            trade_instructions: vec![SignalInstructions {
+                ticker: "AAPL".to_string(),
+                order_type,
+                action,
+                signal_id: timestamp as i32,
+                weight: 0,
+                quantity: 10,
+                limit_price: limit_price.map(|p| p * PRICE_SCALE),
+                aux_price: aux_price.map(|p| p * PRICE_SCALE),
+            }],
+        }
+    }
+
+    fn simulator(config: SimulatorConfig) -> FillSimulator {
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        FillSimulator::new(config, mappings)
+    }
+
+    #[test]
+    fn test_market_latency() -> anyhow::Result<()> {
+        let config = SimulatorConfig {
+            latency: 5,
+            fee_model: FeeModel::PerUnit(PRICE_SCALE / 100),
+            ..Default::default()
+        };
+        let signals = vec![
+            signal(10, OrderType::Market, TradeAction::Buy, None, None),
+            signal(20, OrderType::Market, TradeAction::Sell, None, None),
+        ];
+        let records = vec![
+            quote_msg(10, 99, 101),
+            quote_msg(14, 100, 102),
+            quote_msg(16, 101, 103),
+            trade_msg(30, 105),
+        ];
+
+        // Test
+        let trades = simulator(config).simulate(&signals, &records)?;
+
+        // Validate
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].timestamp, 16);
+        assert_eq!(trades[0].avg_price, 103 * PRICE_SCALE);
+        assert_eq!(trades[0].quantity, 10 * PRICE_SCALE);
+        assert_eq!(trades[0].trade_value, -1030 * PRICE_SCALE);
+        assert_eq!(trades[0].fees, PRICE_SCALE / 10);
+        assert_eq!(trades[0].trade_cost, -1030 * PRICE_SCALE - PRICE_SCALE / 10);
+        assert_eq!(trades[1].trade_id, 2);
+        assert_eq!(trades[1].timestamp, 30);
+        assert_eq!(trades[1].avg_price, 105 * PRICE_SCALE);
+        assert_eq!(trades[1].trade_value, 1050 * PRICE_SCALE);
+        assert_eq!(trades[1].trade_cost, 1050 * PRICE_SCALE - PRICE_SCALE / 10);
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit() -> anyhow::Result<()> {
+        let signals = vec![
+            signal(1, OrderType::Limit, TradeAction::Buy, Some(100), None),
+            signal(1, OrderType::Limit, TradeAction::Sell, Some(110), None),
+        ];
+        let records = vec![
+            quote_msg(2, 100, 101),
+            quote_msg(3, 99, 100),
+            quote_msg(4, 108, 109),
+        ];
+        let mut simulator = simulator(SimulatorConfig::default());
+
+        // Test
+        let trades = simulator.simulate(&signals, &records)?;
+
+        // Validate
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].timestamp, 3);
+        assert_eq!(trades[0].action, TradeAction::Buy);
+        assert_eq!(trades[0].avg_price, 100 * PRICE_SCALE);
+        assert_eq!(trades[0].fees, 0);
+
+        let open: Vec<TradeAction> = simulator.open_orders().map(|o| o.action).collect();
+        assert_eq!(open, vec![TradeAction::Sell]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_cross() -> anyhow::Result<()> {
+        let config = SimulatorConfig {
+            limit_fill: LimitFill::Cross,
+            ..Default::default()
+        };
+        let signals = vec![signal(
+            1,
+            OrderType::Limit,
+            TradeAction::Buy,
+            Some(100),
+            None,
+        )];
+        let records = vec![quote_msg(2, 99, 100), quote_msg(3, 98, 99)];
+
+        // Test
+        let trades = simulator(config).simulate(&signals, &records)?;
+
+        // Validate
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].timestamp, 3);
+        assert_eq!(trades[0].avg_price, 99 * PRICE_SCALE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stops() -> anyhow::Result<()> {
+        let signals = vec![
+            signal(1, OrderType::StopMarket, TradeAction::Buy, None, Some(105)),
+            signal(
+                1,
+                OrderType::StopLimit,
+                TradeAction::Sell,
+                Some(94),
+                Some(95),
+            ),
+        ];
+        let records = vec![
+            trade_msg(2, 100),
+            trade_msg(3, 106),
+            trade_msg(4, 93),
+            trade_msg(5, 94),
+        ];
+
+        // Test
+        let trades = simulator(SimulatorConfig::default()).simulate(&signals, &records)?;
+
+        // Validate
+        let fills: Vec<(i64, TradeAction, i64)> = trades
+            .iter()
+            .map(|t| (t.timestamp, t.action, t.avg_price / PRICE_SCALE))
+            .collect();
+        assert_eq!(
+            fills,
+            vec![(3, TradeAction::Buy, 106), (5, TradeAction::Sell, 94)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fee_models() {
+        let quantity = 10 * PRICE_SCALE;
+        let price = 200 * PRICE_SCALE;
+
+        // Validate
+        assert_eq!(
+            FeeModel::PerFill(PRICE_SCALE).fees(quantity, price),
+            PRICE_SCALE
+        );
+        assert_eq!(
+            FeeModel::PerUnit(PRICE_SCALE / 2).fees(quantity, price),
+            5 * PRICE_SCALE
+        );
+        assert_eq!(
+            FeeModel::Bps(10 * PRICE_SCALE).fees(quantity, price),
+            2 * PRICE_SCALE
+        );
+    }
+
+    #[test]
+    fn test_missing_limit_price() {
+        let signals = vec![signal(1, OrderType::Limit, TradeAction::Buy, None, None)];
+
+        // Test
+        let result = simulator(SimulatorConfig::default()).simulate(&signals, &[]);
+
+        // Validate
+        assert!(result.is_err());
+    }
+}
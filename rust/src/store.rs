@@ -0,0 +1,665 @@
+use crate::decode::Decoder;
+use crate::encode::{CombinedEncoder, RecordEncoder};
+use crate::enums::{Dataset, Schema};
+use crate::metadata::Metadata;
+use crate::params::RetrieveParams;
+use crate::record_enum::RecordEnum;
+use crate::record_ref::RecordRef;
+use crate::retrieve::{open_files, Retrieval};
+use crate::symbols::SymbolMap;
+use crate::utils::{date_to_unix_nanos, unix_nanos_to_day};
+use crate::{Error, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const NANOS_PER_DAY: u64 = 86_400_000_000_000;
+
+/// A file of the store, holding one instrument's records for one UTC day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Partition {
+    pub dataset: Dataset,
+    pub schema: Schema,
+    /// UTC day as "YYYY-MM-DD".
+    pub date: String,
+    pub instrument_id: u32,
+    pub ticker: String,
+    /// Path of the file relative to the store root.
+    pub path: PathBuf,
+    /// `ts_event` of the first record.
+    pub start: u64,
+    /// `ts_event` of the last record.
+    pub end: u64,
+    pub records: u64,
+}
+
+/// Index of every partition in the store, kept as `manifest.json` in the root.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub partitions: Vec<Partition>,
+}
+
+/// A run of consecutive days with data for a ticker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CatalogEntry {
+    pub dataset: Dataset,
+    pub schema: Schema,
+    pub ticker: String,
+    /// `ts_event` of the first record in the run.
+    pub start: u64,
+    /// `ts_event` of the last record in the run.
+    pub end: u64,
+    pub days: usize,
+    pub records: u64,
+}
+
+/// Records of a partition staged by a write before touching the store.
+struct Pending {
+    date: String,
+    ticker: String,
+    encoder: RecordEncoder<Vec<u8>>,
+    start: u64,
+    end: u64,
+    records: u64,
+}
+
+/// Partitions of the UTC day being written, keyed by instrument id.
+#[derive(Default)]
+struct Staging {
+    day: u64,
+    pending: BTreeMap<u32, Pending>,
+    written: u64,
+}
+
+/// A directory of mbinary files laid out as `{dataset}/{schema}/{date}/{instrument_id}.bin`.
+pub struct DataStore {
+    root: PathBuf,
+    manifest: Manifest,
+}
+
+impl DataStore {
+    /// Opens the store in `root`, creating the directory when missing.
+    pub fn open<P: AsRef<Path>>(root: P) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(&root)?;
+
+        let manifest_path = root.join(MANIFEST_FILE);
+        let manifest = if manifest_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&manifest_path)?)
+                .map_err(|e| Error::Decode(format!("{}: {}", manifest_path.display(), e)))?
+        } else {
+            Manifest::default()
+        };
+
+        Ok(DataStore { root, manifest })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Appends the records of `decoder`, split into a partition per instrument and UTC day.
+    ///
+    /// Records are staged a UTC day at a time and committed at each day boundary. Days must
+    /// come in order and records for a partition in `ts_event` order after any already stored,
+    /// otherwise the day is not written while the days committed before it stay. Returns the
+    /// number of records written.
+    pub fn write<R: Read>(&mut self, mut decoder: Decoder<R>) -> Result<u64> {
+        let metadata = decoder.metadata().ok_or_else(|| {
+            Error::CustomError("Stream has no metadata to partition records".to_string())
+        })?;
+
+        let mut staging = Staging::default();
+        while let Some(record) = decoder.decode_ref()? {
+            self.stage(&mut staging, &metadata, &record)?;
+        }
+        self.commit(&mut staging, &metadata)?;
+        Ok(staging.written)
+    }
+
+    /// Appends `records` described by `metadata`, see `write`.
+    pub fn write_records(&mut self, metadata: &Metadata, records: &[RecordRef]) -> Result<u64> {
+        let mut staging = Staging::default();
+        for record in records {
+            self.stage(&mut staging, metadata, record)?;
+        }
+        self.commit(&mut staging, metadata)?;
+        Ok(staging.written)
+    }
+
+    fn stage(
+        &mut self,
+        staging: &mut Staging,
+        metadata: &Metadata,
+        record: &RecordRef,
+    ) -> Result<()> {
+        let header = record.header();
+        let day = header.ts_event / NANOS_PER_DAY;
+        if day < staging.day {
+            return Err(Error::CustomError(format!(
+                "Record at {} is before the day already written",
+                header.ts_event
+            )));
+        }
+        if day > staging.day {
+            self.commit(staging, metadata)?;
+            staging.day = day;
+        }
+
+        let id = header.instrument_id;
+        let staged = match staging.pending.entry(id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let ticker = metadata.mappings.get_instrument_ticker(id).ok_or_else(|| {
+                    Error::CustomError(format!("Instrument id {} has no mapping", id))
+                })?;
+                entry.insert(Pending {
+                    date: unix_nanos_to_day(header.ts_event as i64)?,
+                    ticker,
+                    encoder: RecordEncoder::new(Vec::new()),
+                    start: header.ts_event,
+                    end: header.ts_event,
+                    records: 0,
+                })
+            }
+        };
+
+        if header.ts_event < staged.end {
+            return Err(Error::CustomError(format!(
+                "Records for {} are not in ts_event order",
+                staged.ticker
+            )));
+        }
+        staged.encoder.encode_record(record)?;
+        staged.end = header.ts_event;
+        staged.records += 1;
+        Ok(())
+    }
+
+    /// Writes the staged partitions to temporary files, then renames them over the partitions.
+    ///
+    /// A failed temporary write leaves the store as it was, the manifest is saved with every
+    /// partition renamed before a failure so it never misses records in the files.
+    fn commit(&mut self, staging: &mut Staging, metadata: &Metadata) -> Result<()> {
+        let pending = std::mem::take(&mut staging.pending);
+        if pending.is_empty() {
+            return Ok(());
+        }
+        for (id, staged) in &pending {
+            if let Some(stored) =
+                self.partition(metadata.dataset, metadata.schema, &staged.date, *id)
+            {
+                if stored.ticker != staged.ticker {
+                    return Err(Error::CustomError(format!(
+                        "Instrument id {} is stored as {}, not {}",
+                        id, stored.ticker, staged.ticker
+                    )));
+                }
+                if staged.start < stored.end {
+                    return Err(Error::CustomError(format!(
+                        "Records for {} on {} start before the stored end {}",
+                        staged.ticker, staged.date, stored.end
+                    )));
+                }
+            }
+        }
+
+        let mut written = Vec::with_capacity(pending.len());
+        for (id, staged) in pending {
+            let relative = PathBuf::from(metadata.dataset.as_str())
+                .join(metadata.schema.as_str())
+                .join(&staged.date)
+                .join(format!("{}.bin", id));
+            match self.write_temporary(metadata, id, &staged, &relative) {
+                Ok(tmp) => written.push((tmp, relative, id, staged)),
+                Err(e) => {
+                    for (tmp, ..) in written {
+                        let _ = fs::remove_file(tmp);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        let mut result = Ok(());
+        let mut renamed = written.into_iter();
+        for (tmp, relative, id, staged) in renamed.by_ref() {
+            if let Err(e) = fs::rename(&tmp, self.root.join(&relative)) {
+                let _ = fs::remove_file(tmp);
+                result = Err(e.into());
+                break;
+            }
+
+            match self
+                .manifest
+                .partitions
+                .iter_mut()
+                .find(|p| p.path == relative)
+            {
+                Some(stored) => {
+                    stored.end = staged.end;
+                    stored.records += staged.records;
+                }
+                None => self.manifest.partitions.push(Partition {
+                    dataset: metadata.dataset,
+                    schema: metadata.schema,
+                    date: staged.date,
+                    instrument_id: id,
+                    ticker: staged.ticker,
+                    path: relative,
+                    start: staged.start,
+                    end: staged.end,
+                    records: staged.records,
+                }),
+            }
+            staging.written += staged.records;
+        }
+        for (tmp, ..) in renamed {
+            let _ = fs::remove_file(tmp);
+        }
+
+        self.save()?;
+        result
+    }
+
+    /// Copy of the partition at `relative` with the staged records appended, or a new file
+    /// with its metadata header when the partition does not exist yet.
+    fn write_temporary(
+        &self,
+        metadata: &Metadata,
+        id: u32,
+        staged: &Pending,
+        relative: &Path,
+    ) -> Result<PathBuf> {
+        let path = self.root.join(relative);
+        let tmp = path.with_extension("bin.tmp");
+        fs::create_dir_all(path.parent().unwrap_or(&self.root))?;
+
+        if path.exists() {
+            fs::copy(&path, &tmp)?;
+        } else {
+            let day_start = date_to_unix_nanos(&staged.date)? as u64;
+            let mut mappings = SymbolMap::new();
+            mappings.add_instrument(&staged.ticker, id);
+            let header = Metadata::new(
+                metadata.schema,
+                metadata.dataset,
+                day_start,
+                day_start + NANOS_PER_DAY,
+                mappings,
+            );
+
+            let mut encoder = CombinedEncoder::new(Vec::new());
+            encoder.encode_metadata(&header)?;
+            encoder.write_to_file(&tmp, false)?;
+        }
+
+        if let Err(e) = staged.encoder.write_to_file(&tmp, true) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+        Ok(tmp)
+    }
+
+    fn partition(
+        &self,
+        dataset: Dataset,
+        schema: Schema,
+        date: &str,
+        instrument_id: u32,
+    ) -> Option<&Partition> {
+        self.manifest.partitions.iter().find(|p| {
+            p.dataset == dataset
+                && p.schema == schema
+                && p.date == date
+                && p.instrument_id == instrument_id
+        })
+    }
+
+    /// Writes the manifest to a temporary file first, so a failed write keeps the old one.
+    fn save(&self) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.manifest)
+            .map_err(|e| Error::Encode(e.to_string()))?;
+        let path = self.root.join(MANIFEST_FILE);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Runs of consecutive days stored per dataset, schema and ticker.
+    pub fn catalog(&self) -> Result<Vec<CatalogEntry>> {
+        let mut partitions: Vec<&Partition> = self.manifest.partitions.iter().collect();
+        partitions.sort_by(|a, b| {
+            (a.dataset as u8, a.schema as u8, &a.ticker, &a.date).cmp(&(
+                b.dataset as u8,
+                b.schema as u8,
+                &b.ticker,
+                &b.date,
+            ))
+        });
+
+        let mut entries: Vec<CatalogEntry> = Vec::new();
+        let mut last_date: Option<NaiveDate> = None;
+        for partition in partitions {
+            let date = NaiveDate::parse_from_str(&partition.date, "%Y-%m-%d")?;
+            match entries.last_mut() {
+                Some(entry)
+                    if entry.dataset == partition.dataset
+                        && entry.schema == partition.schema
+                        && entry.ticker == partition.ticker
+                        && last_date.and_then(|d| d.succ_opt()) == Some(date) =>
+                {
+                    entry.end = partition.end;
+                    entry.days += 1;
+                    entry.records += partition.records;
+                }
+                _ => entries.push(CatalogEntry {
+                    dataset: partition.dataset,
+                    schema: partition.schema,
+                    ticker: partition.ticker.clone(),
+                    start: partition.start,
+                    end: partition.end,
+                    days: 1,
+                    records: partition.records,
+                }),
+            }
+            last_date = Some(date);
+        }
+        Ok(entries)
+    }
+
+    /// Records matching `params`, read a day at a time in `ts_event` order.
+    ///
+    /// Fails when a symbol has no data in the requested period.
+    pub fn read(&self, params: &RetrieveParams) -> Result<StoreReader> {
+        let adjusted = params.adjusted()?;
+        let start = adjusted.start_ts.max(0) as u64;
+        let end = adjusted.end_ts.max(0) as u64;
+
+        let mut mappings = SymbolMap::new();
+        let mut days: BTreeMap<&str, Vec<PathBuf>> = BTreeMap::new();
+        for partition in self.manifest.partitions.iter().filter(|p| {
+            p.dataset == params.dataset
+                && p.schema == params.schema
+                && params.symbols.contains(&p.ticker)
+                && p.end >= start
+                && p.start < end
+        }) {
+            mappings.add_instrument(&partition.ticker, partition.instrument_id);
+            days.entry(&partition.date)
+                .or_default()
+                .push(self.root.join(&partition.path));
+        }
+
+        let tickers: Vec<&String> = mappings.map.values().collect();
+        if let Some(missing) = params.symbols.iter().find(|s| !tickers.contains(s)) {
+            return Err(Error::CustomError(format!(
+                "Symbol {} has no data in the store for the period",
+                missing
+            )));
+        }
+
+        Ok(StoreReader {
+            metadata: Metadata::new(params.schema, params.dataset, start, end, mappings),
+            params: params.clone(),
+            days: days.into_values().collect(),
+            current: None,
+        })
+    }
+}
+
+/// Records read from a `DataStore`, see `DataStore::read`.
+pub struct StoreReader {
+    metadata: Metadata,
+    params: RetrieveParams,
+    days: VecDeque<Vec<PathBuf>>,
+    current: Option<Retrieval<BufReader<File>>>,
+}
+
+impl StoreReader {
+    /// Metadata for the read records, with the mappings of every matched symbol.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+}
+
+impl Iterator for StoreReader {
+    type Item = Result<RecordEnum>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.current.as_mut().and_then(|current| current.next()) {
+                return Some(record);
+            }
+
+            // Days hold disjoint periods, so merging one at a time keeps the order
+            let paths = self.days.pop_front()?;
+            match open_files(&paths).and_then(|d| Retrieval::merge(&self.params, d)) {
+                Ok(retrieval) => self.current = Some(retrieval),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::Stype;
+    use crate::records::{OhlcvMsg, RecordHeader};
+    use std::io::Cursor;
+
+    const MINUTE: u64 = 60_000_000_000;
+    // 2024-01-02 00:00:00 UTC
+    const DAY: u64 = 1_704_153_600_000_000_000;
+
+    fn ohlcv(instrument_id: u32, ts_event: u64) -> OhlcvMsg {
+        OhlcvMsg {
+            hd: RecordHeader::new::<OhlcvMsg>(instrument_id, ts_event, 0),
+            open: 100,
+            high: 200,
+            low: 50,
+            close: 150,
+            volume: 1000,
+        }
+    }
+
+    fn metadata() -> Metadata {
+        let mut mappings = SymbolMap::new();
+        mappings.add_instrument("AAPL", 1);
+        mappings.add_instrument("TSLA", 2);
+        Metadata::new(Schema::Ohlcv1M, Dataset::Equities, 0, 0, mappings)
+    }
+
+    fn stream(records: &[OhlcvMsg]) -> anyhow::Result<Decoder<Cursor<Vec<u8>>>> {
+        let refs: Vec<RecordRef> = records.iter().map(RecordRef::from).collect();
+        let mut buffer = Vec::new();
+        CombinedEncoder::new(&mut buffer).encode(&metadata(), &refs)?;
+        Ok(Decoder::new(Cursor::new(buffer))?)
+    }
+
+    fn params(symbols: &[&str], start: u64, end: u64) -> RetrieveParams {
+        RetrieveParams {
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            start_ts: start as i64,
+            end_ts: end as i64,
+            schema: Schema::Ohlcv1M,
+            dataset: Dataset::Equities,
+            stype: Stype::Raw,
+        }
+    }
+
+    fn headers(records: &[RecordEnum]) -> Vec<(u32, u64)> {
+        records
+            .iter()
+            .map(|r| (r.msg().header().instrument_id, r.msg().header().ts_event))
+            .collect()
+    }
+
+    #[test]
+    fn test_store_write_and_catalog() -> anyhow::Result<()> {
+        let root = PathBuf::from("tests/test_store_catalog");
+        let _ = fs::remove_dir_all(&root);
+        let mut store = DataStore::open(&root)?;
+
+        // Test
+        let first = store.write(stream(&[
+            ohlcv(1, DAY),
+            ohlcv(2, DAY + MINUTE),
+            ohlcv(1, DAY + NANOS_PER_DAY),
+        ])?)?;
+        let second = store.write(stream(&[
+            ohlcv(1, DAY + NANOS_PER_DAY + MINUTE),
+            ohlcv(1, DAY + 3 * NANOS_PER_DAY),
+        ])?)?;
+        let reopened = DataStore::open(&root)?;
+        let catalog = reopened.catalog()?;
+
+        // Validate
+        assert_eq!((first, second), (3, 2));
+        assert_eq!(reopened.manifest(), store.manifest());
+        assert_eq!(reopened.manifest().partitions.len(), 4);
+        assert!(root.join("equities/ohlcv-1m/2024-01-03/1.bin").exists());
+
+        let aapl: Vec<(u64, u64, usize, u64)> = catalog
+            .iter()
+            .filter(|e| e.ticker == "AAPL")
+            .map(|e| (e.start, e.end, e.days, e.records))
+            .collect();
+        assert_eq!(
+            aapl,
+            vec![
+                (DAY, DAY + NANOS_PER_DAY + MINUTE, 2, 3),
+                (DAY + 3 * NANOS_PER_DAY, DAY + 3 * NANOS_PER_DAY, 1, 1)
+            ]
+        );
+        assert_eq!(catalog.len(), 3);
+
+        // Cleanup
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_read() -> anyhow::Result<()> {
+        let root = PathBuf::from("tests/test_store_read");
+        let _ = fs::remove_dir_all(&root);
+        let mut store = DataStore::open(&root)?;
+        let records = [
+            ohlcv(1, DAY),
+            ohlcv(2, DAY + MINUTE),
+            ohlcv(1, DAY + NANOS_PER_DAY),
+            ohlcv(2, DAY + NANOS_PER_DAY),
+            ohlcv(1, DAY + 2 * NANOS_PER_DAY),
+        ];
+        let refs: Vec<RecordRef> = records.iter().map(RecordRef::from).collect();
+        store.write_records(&metadata(), &refs)?;
+
+        // Test
+        let reader = store.read(&params(
+            &["AAPL", "TSLA"],
+            DAY + MINUTE,
+            DAY + NANOS_PER_DAY + MINUTE,
+        ))?;
+        let read_metadata = reader.metadata().clone();
+        let read = reader.collect::<Result<Vec<_>>>()?;
+        let missing = store.read(&params(&["AAPL", "MSFT"], DAY, DAY + MINUTE));
+
+        // Validate
+        assert_eq!(
+            headers(&read),
+            vec![
+                (2, DAY + MINUTE),
+                (1, DAY + NANOS_PER_DAY),
+                (2, DAY + NANOS_PER_DAY)
+            ]
+        );
+        assert_eq!(read_metadata.mappings.map.len(), 2);
+        assert!(missing.err().unwrap().to_string().contains("MSFT"));
+
+        // Cleanup
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_write_failed_partition() -> anyhow::Result<()> {
+        let root = PathBuf::from("tests/test_store_failed");
+        let _ = fs::remove_dir_all(&root);
+        let mut store = DataStore::open(&root)?;
+        let day = root.join("equities/ohlcv-1m/2024-01-02");
+        store.write(stream(&[ohlcv(1, DAY)])?)?;
+        let before = store.manifest().clone();
+        fs::create_dir_all(day.join("2.bin.tmp/blocked"))?;
+
+        // Test
+        let records = [ohlcv(1, DAY + MINUTE), ohlcv(2, DAY + MINUTE)];
+        let failed = store.write(stream(&records)?);
+        let reopened = DataStore::open(&root)?;
+        fs::remove_dir_all(day.join("2.bin.tmp"))?;
+        let retried = store.write(stream(&records)?)?;
+        let later = store.write(stream(&[
+            ohlcv(1, DAY + 2 * MINUTE),
+            ohlcv(1, DAY + NANOS_PER_DAY),
+            ohlcv(2, DAY),
+        ])?);
+
+        // Validate
+        assert!(failed.is_err());
+        assert_eq!(reopened.manifest(), &before);
+        assert!(!day.join("1.bin.tmp").exists());
+        assert_eq!(retried, 2);
+        assert!(later.is_err());
+
+        let partitions: Vec<(&str, u32, u64)> = store
+            .manifest()
+            .partitions
+            .iter()
+            .map(|p| (p.date.as_str(), p.instrument_id, p.records))
+            .collect();
+        assert_eq!(partitions, vec![("2024-01-02", 1, 3), ("2024-01-02", 2, 1)]);
+        let stored = store
+            .read(&params(&["AAPL"], DAY, DAY + NANOS_PER_DAY))?
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(
+            headers(&stored),
+            vec![(1, DAY), (1, DAY + MINUTE), (1, DAY + 2 * MINUTE)]
+        );
+
+        // Cleanup
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_store_write_out_of_order() -> anyhow::Result<()> {
+        let root = PathBuf::from("tests/test_store_order");
+        let _ = fs::remove_dir_all(&root);
+        let mut store = DataStore::open(&root)?;
+        store.write(stream(&[ohlcv(1, DAY + MINUTE)])?)?;
+
+        // Test
+        let before_stored = store.write(stream(&[ohlcv(2, DAY), ohlcv(1, DAY)])?);
+        let unordered = store.write(stream(&[ohlcv(2, DAY + MINUTE), ohlcv(2, DAY)])?);
+
+        // Validate
+        assert!(before_stored.is_err());
+        assert!(unordered.is_err());
+        assert_eq!(store.manifest().partitions.len(), 1);
+        assert!(!root.join("equities/ohlcv-1m/2024-01-02/2.bin").exists());
+
+        // Cleanup
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -131,34 +131,42 @@ impl SymbolMap {
     }
 
     pub fn deserialize(bytes: &[u8], offset: &mut usize) -> io::Result<Self> {
+        // Bounds-checked read of `len` bytes at the offset
+        fn take<'a>(
+            bytes: &'a [u8],
+            offset: &mut usize,
+            len: usize,
+            error: &str,
+        ) -> io::Result<&'a [u8]> {
+            let slice = offset
+                .checked_add(len)
+                .and_then(|end| bytes.get(*offset..end))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+            *offset += len;
+            Ok(slice)
+        }
+
+        fn read_u32(bytes: &[u8], offset: &mut usize, error: &str) -> io::Result<u32> {
+            let slice = take(bytes, offset, 4, error)?;
+            Ok(u32::from_le_bytes(slice.try_into().unwrap()))
+        }
+
         // Deserialize the length of the map (stored as a u32)
-        let map_len =
-            u32::from_le_bytes(bytes[*offset..*offset + 4].try_into().map_err(|_| {
-                io::Error::new(io::ErrorKind::InvalidData, "Failed to read map length")
-            })?) as usize;
-        *offset += 4;
+        let map_len = read_u32(bytes, offset, "Failed to read map length")? as usize;
 
-        let mut map = HashMap::with_capacity(map_len);
+        let mut map = HashMap::new();
 
         // Deserialize each key-value pair in the map
         for _ in 0..map_len {
-            let key =
-                u32::from_le_bytes(bytes[*offset..*offset + 4].try_into().map_err(|_| {
-                    io::Error::new(io::ErrorKind::InvalidData, "Failed to read key")
-                })?);
-            *offset += 4;
+            let key = read_u32(bytes, offset, "Failed to read key")?;
 
             // Read the length of the value string (stored as u32)
-            let value_len =
-                u32::from_le_bytes(bytes[*offset..*offset + 4].try_into().map_err(|_| {
-                    io::Error::new(io::ErrorKind::InvalidData, "Failed to read value length")
-                })?) as usize;
-            *offset += 4;
+            let value_len = read_u32(bytes, offset, "Failed to read value length")? as usize;
 
             // Extract the string value of `value_len` bytes
-            let value = String::from_utf8(bytes[*offset..*offset + value_len].to_vec())
+            let value = take(bytes, offset, value_len, "Failed to read value")?;
+            let value = String::from_utf8(value.to_vec())
                 .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
-            *offset += value_len;
 
             map.insert(key, value);
         }
@@ -325,4 +333,18 @@ mod tests {
         let ticker2 = symbol_map.get_instrument_ticker(2).unwrap();
         assert_eq!(&ticker2, tsla);
     }
+
+    #[test]
+    fn test_symbol_map_truncated() {
+        let mut symbol_map = SymbolMap::new();
+        symbol_map.add_instrument("AAPL", 1);
+        let bytes = symbol_map.serialize();
+
+        // Test
+        let mut offset = 0;
+        let result = SymbolMap::deserialize(&bytes[..bytes.len() - 1], &mut offset);
+
+        // Validate
+        assert!(result.is_err());
+    }
 }
@@ -29,6 +29,12 @@ pub fn unix_nanos_to_date(unix_nanos: i64) -> Result<String> {
     Ok(formatted_date)
 }
 
+/// Returns the UTC calendar day ("YYYY-MM-DD") a nanosecond timestamp falls on.
+pub fn unix_nanos_to_day(unix_nanos: i64) -> Result<String> {
+    let datetime_utc: DateTime<Utc> = Utc.timestamp_nanos(unix_nanos);
+    Ok(datetime_utc.format("%Y-%m-%d").to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,4 +75,16 @@ mod tests {
         assert_eq!("2021-11-01 01:01:01", iso);
         Ok(())
     }
+
+    #[test]
+    fn test_unix_to_day() -> Result<()> {
+        let unix = 1635728461000000000;
+
+        //Test
+        let day = unix_nanos_to_day(unix)?;
+
+        // Validate
+        assert_eq!("2021-11-01", day);
+        Ok(())
+    }
 }